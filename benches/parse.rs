@@ -0,0 +1,53 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use lnk::{ShellLink, ShellLinkRef};
+use std::hint::black_box;
+
+/// A shell link with long Unicode strings and an EnvironmentVariableDataBlock,
+/// so that most of the time is spent decoding strings.
+fn string_heavy_link() -> Vec<u8> {
+    let mut data = std::fs::read("tests/test.lnk").unwrap()[..0x4c].to_vec();
+    // HasName, HasArguments and IsUnicode
+    data[20..24].copy_from_slice(&0xa4u32.to_le_bytes());
+    for s in ["A shortcut with a fairly long name", &"-arg ".repeat(200)] {
+        let units: Vec<u16> = s.encode_utf16().collect();
+        data.extend_from_slice(&(units.len() as u16).to_le_bytes());
+        units
+            .iter()
+            .for_each(|u| data.extend_from_slice(&u.to_le_bytes()));
+    }
+    let target = r"%SystemRoot%\System32\WindowsPowerShell\v1.0\powershell.exe";
+    data.extend_from_slice(&0x314u32.to_le_bytes());
+    data.extend_from_slice(&0xa000_0001u32.to_le_bytes());
+    let mut ansi = target.as_bytes().to_vec();
+    ansi.resize(260, 0);
+    data.extend_from_slice(&ansi);
+    let mut unicode: Vec<u8> = target.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    unicode.resize(520, 0);
+    data.extend_from_slice(&unicode);
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data
+}
+
+fn parse(c: &mut Criterion) {
+    let test_link = std::fs::read("tests/test.lnk").unwrap();
+    let string_heavy = string_heavy_link();
+
+    c.bench_function("open test.lnk", |b| {
+        b.iter(|| ShellLink::open(black_box("tests/test.lnk")).unwrap())
+    });
+    c.bench_function("from_slice test.lnk", |b| {
+        b.iter(|| ShellLink::from_slice(black_box(&test_link)).unwrap())
+    });
+    c.bench_function("from_slice string heavy", |b| {
+        b.iter(|| ShellLink::from_slice(black_box(&string_heavy)).unwrap())
+    });
+    c.bench_function("ShellLinkRef arguments string heavy", |b| {
+        b.iter(|| {
+            let (link, _) = ShellLinkRef::parse(black_box(&string_heavy)).unwrap();
+            link.arguments().unwrap().decode().len()
+        })
+    });
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
@@ -0,0 +1,61 @@
+/*
+ * C interface to the lnk shell link parser.
+ *
+ * Build the library with
+ *
+ *     cargo build --release --features ffi
+ *
+ * Shell links are opaque and must be freed with lnk_free. Strings returned
+ * by the library are NULL-terminated UTF-8 and must be freed with
+ * lnk_string_free. Functions that fail return NULL, and lnk_last_error
+ * describes why. A string holding a NULL character is not returned cut
+ * short: NULL is returned instead, and lnk_last_error says so. Times are
+ * FILETIME values: 100-nanosecond intervals since 1601-01-01 UTC.
+ */
+
+#ifndef LNK_H
+#define LNK_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct ShellLink ShellLink;
+
+/* The message of the last error on the calling thread, or NULL. Owned by
+ * the library; valid until the next failing call on the same thread. */
+const char *lnk_last_error(void);
+
+ShellLink *lnk_open(const char *path);
+ShellLink *lnk_parse(const uint8_t *data, size_t len);
+void lnk_free(ShellLink *link);
+void lnk_string_free(char *s);
+
+/* Optional strings: NULL if not set, or if they hold a NULL character. */
+char *lnk_name(const ShellLink *link);
+char *lnk_relative_path(const ShellLink *link);
+char *lnk_working_dir(const ShellLink *link);
+char *lnk_arguments(const ShellLink *link);
+char *lnk_icon_location(const ShellLink *link);
+char *lnk_local_base_path(const ShellLink *link);
+
+uint32_t lnk_link_flags(const ShellLink *link);
+uint32_t lnk_file_attributes(const ShellLink *link);
+uint64_t lnk_creation_time(const ShellLink *link);
+uint64_t lnk_access_time(const ShellLink *link);
+uint64_t lnk_write_time(const ShellLink *link);
+uint32_t lnk_file_size(const ShellLink *link);
+int32_t lnk_icon_index(const ShellLink *link);
+uint32_t lnk_show_command(const ShellLink *link);
+
+/* The whole shell link as JSON, or NULL on failure. */
+char *lnk_to_json(const ShellLink *link);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* LNK_H */
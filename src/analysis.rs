@@ -0,0 +1,639 @@
+use std::fmt;
+
+use crate::{ExtraData, ShellLink, ShowCommand};
+
+/// The shortest run of whitespace in the arguments that is reported as
+/// padding. The Properties dialog shows only the start of the command line,
+/// so padding pushes the rest of it out of sight.
+pub const PADDING_THRESHOLD: usize = 64;
+
+/// The largest PropertyStoreDataBlock or VistaAndAboveIDListDataBlock, in
+/// bytes, that is not reported as oversized. Those written by Windows are a
+/// few hundred bytes.
+pub const OVERSIZED_BLOCK_THRESHOLD: usize = 0x10000;
+
+/// How many days before the target was created its object droid may have
+/// been made before this is reported. The two are usually close together,
+/// so a droid made much earlier suggests that the header times were
+/// changed.
+pub const DROID_TIME_TOLERANCE_DAYS: u64 = 30;
+
+/// The number of 100-nanosecond intervals in a day.
+const INTERVALS_PER_DAY: u64 = 864_000_000_000;
+
+/// Programs that run scripts or commands given on their command line, and so
+/// are often the targets of malicious shell links.
+const INTERPRETERS: &[&str] = &[
+    "bash.exe",
+    "bitsadmin.exe",
+    "certutil.exe",
+    "cmd.exe",
+    "cscript.exe",
+    "forfiles.exe",
+    "msbuild.exe",
+    "msiexec.exe",
+    "mshta.exe",
+    "powershell.exe",
+    "powershell_ise.exe",
+    "pwsh.exe",
+    "regsvr32.exe",
+    "rundll32.exe",
+    "wmic.exe",
+    "wscript.exe",
+];
+
+/// Extensions of files that run code when opened.
+const EXECUTABLE_EXTENSIONS: &[&str] = &[
+    "bat", "cmd", "com", "cpl", "dll", "exe", "hta", "js", "jse", "msi", "pif", "ps1", "scr",
+    "vbe", "vbs", "wsf", "wsh",
+];
+
+/// Extensions of documents, whose icons are borrowed to disguise shell links
+/// to executables.
+const DOCUMENT_EXTENSIONS: &[&str] = &[
+    "bmp", "csv", "doc", "docx", "gif", "htm", "html", "jpeg", "jpg", "mp3", "mp4", "odt", "pdf",
+    "png", "ppt", "pptx", "rtf", "txt", "xls", "xlsx", "zip",
+];
+
+/// Characters that change the direction of the text around them, so that a
+/// name such as `invoice\u{202e}fdp.exe` is shown as `invoiceexe.pdf`.
+const BIDI_CONTROLS: &[(char, &str)] = &[
+    ('\u{061c}', "ARABIC LETTER MARK"),
+    ('\u{200e}', "LEFT-TO-RIGHT MARK"),
+    ('\u{200f}', "RIGHT-TO-LEFT MARK"),
+    ('\u{202a}', "LEFT-TO-RIGHT EMBEDDING"),
+    ('\u{202b}', "RIGHT-TO-LEFT EMBEDDING"),
+    ('\u{202c}', "POP DIRECTIONAL FORMATTING"),
+    ('\u{202d}', "LEFT-TO-RIGHT OVERRIDE"),
+    ('\u{202e}', "RIGHT-TO-LEFT OVERRIDE"),
+    ('\u{2066}', "LEFT-TO-RIGHT ISOLATE"),
+    ('\u{2067}', "RIGHT-TO-LEFT ISOLATE"),
+    ('\u{2068}', "FIRST STRONG ISOLATE"),
+    ('\u{2069}', "POP DIRECTIONAL ISOLATE"),
+];
+
+/// Characters that are not shown, and so can hide text or split a word that
+/// is searched for.
+const INVISIBLE_CHARACTERS: &[(char, &str)] = &[
+    ('\u{00ad}', "SOFT HYPHEN"),
+    ('\u{180e}', "MONGOLIAN VOWEL SEPARATOR"),
+    ('\u{200b}', "ZERO WIDTH SPACE"),
+    ('\u{200c}', "ZERO WIDTH NON-JOINER"),
+    ('\u{200d}', "ZERO WIDTH JOINER"),
+    ('\u{2060}', "WORD JOINER"),
+    ('\u{2061}', "FUNCTION APPLICATION"),
+    ('\u{2062}', "INVISIBLE TIMES"),
+    ('\u{2063}', "INVISIBLE SEPARATOR"),
+    ('\u{2064}', "INVISIBLE PLUS"),
+    ('\u{feff}', "ZERO WIDTH NO-BREAK SPACE"),
+];
+
+/// How strongly a [`Finding`] suggests that a shell link is malicious.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum Severity {
+    /// Unusual, but also found in shell links made for legitimate reasons.
+    Low,
+    /// Rarely found in shell links made for legitimate reasons.
+    Medium,
+    /// A trick known to be used by malicious shell links.
+    High,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// A trick used by malicious shell links.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum Indicator {
+    /// The arguments hold a long run of whitespace, which hides the rest of
+    /// the command line from the Properties dialog.
+    WhitespacePadding,
+    /// The target runs code, but the icon is taken from a document.
+    IconMismatch,
+    /// A script interpreter is started minimized and without focus.
+    HiddenInterpreter,
+    /// An ExtraData block is far larger than those written by Windows, and
+    /// may hold a payload.
+    OversizedExtraData,
+    /// Data follows the TerminalBlock, where Windows ignores it.
+    TrailingData,
+    /// An ANSI string and the Unicode string stored alongside it differ, so
+    /// tools reading one see a different value from Windows, which reads the
+    /// other.
+    StringMismatch,
+    /// The target was created after it was last written. Copying a file does
+    /// this, but so does changing the times by hand.
+    CreationAfterWrite,
+    /// Some of the target times are zero, although the target has a size.
+    /// Windows sets all of them, so they were cleared afterwards.
+    ZeroedTimes,
+    /// The target's object droid was made long before the target was
+    /// created, according to the header.
+    DroidTimeMismatch,
+    /// A string holds a character that changes the direction of the text,
+    /// such as RIGHT-TO-LEFT OVERRIDE, which disguises the extension of a
+    /// file name.
+    BidiControl,
+    /// A string holds a character that is not shown, such as ZERO WIDTH
+    /// SPACE.
+    InvisibleCharacter,
+    /// A word mixes Latin letters with Greek or Cyrillic ones, which look
+    /// the same, so that it reads as a different word.
+    MixedScript,
+    /// The icon is loaded from a network share, so that Windows sends the
+    /// user's NTLM credentials to its host as soon as the link is shown.
+    NetworkIcon,
+    /// PowerShell is given a command encoded in base64, which hides it from
+    /// anyone reading the command line. Found with the `heuristics`
+    /// feature.
+    EncodedPowerShell,
+    /// A signed Windows program, such as `mshta.exe`, `rundll32.exe` or
+    /// `regsvr32.exe`, is used to run a script or remote code. Found with
+    /// the `heuristics` feature.
+    ProxyExecution,
+    /// The command line holds a URL. Found with the `heuristics` feature.
+    EmbeddedUrl,
+    /// The command line holds a long run of base64, which may be an encoded
+    /// payload. Found with the `heuristics` feature.
+    Base64Blob,
+}
+
+/// Something found by [`ShellLink::analyze`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Finding {
+    /// How strongly the finding suggests that the shell link is malicious.
+    pub severity: Severity,
+    /// The trick found.
+    pub indicator: Indicator,
+    /// What was found, for reports.
+    pub description: String,
+}
+
+impl Finding {
+    pub(crate) fn new(severity: Severity, indicator: Indicator, description: String) -> Self {
+        Self {
+            severity,
+            indicator,
+            description,
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    /// Write the finding in the form `High: the arguments hold ...`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.severity, self.description)
+    }
+}
+
+impl ShellLink {
+    /// Look for tricks used by malicious shell links, returning what was
+    /// found, most severe first. Nothing is checked while parsing, so this
+    /// is only done when asked for.
+    pub fn analyze(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        self.check_padding(&mut findings);
+        self.check_icon(&mut findings);
+        self.check_icon_host(&mut findings);
+        self.check_show_command(&mut findings);
+        self.check_sizes(&mut findings);
+        self.check_strings(&mut findings);
+        self.check_unicode(&mut findings);
+        findings.extend(self.timestamp_anomalies());
+        #[cfg(feature = "heuristics")]
+        findings.extend(self.command_line_threats());
+        findings.sort_by_key(|finding| std::cmp::Reverse(finding.severity));
+        findings
+    }
+
+    /// Look for signs that the times in the shell link were changed, such as
+    /// by a tool that sets file times to hide when files were made. These
+    /// are among the findings of [`ShellLink::analyze`].
+    pub fn timestamp_anomalies(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        let header = self.header();
+        let created = header.creation_time();
+        let written = header.write_time();
+
+        if created.is_set() && written.is_set() && created > written {
+            findings.push(Finding::new(
+                Severity::Low,
+                Indicator::CreationAfterWrite,
+                format!(
+                    "the target was created at {:?}, after it was last written at {:?}",
+                    created, written
+                ),
+            ));
+        }
+
+        let zeroed: Vec<&str> = [
+            ("creation", created),
+            ("access", header.access_time()),
+            ("write", written),
+        ]
+        .into_iter()
+        .filter(|(_, time)| time.is_zero())
+        .map(|(name, _)| name)
+        .collect();
+        if !zeroed.is_empty() && header.file_size() != 0 {
+            findings.push(Finding::new(
+                Severity::Medium,
+                Indicator::ZeroedTimes,
+                format!(
+                    "the {} times are zero, although the target is {} bytes",
+                    zeroed.join(", "),
+                    header.file_size()
+                ),
+            ));
+        }
+
+        if created.is_set() {
+            for block in self.extra_data() {
+                let ExtraData::TrackerProps(tracker) = block else {
+                    continue;
+                };
+                let Some(droid) = tracker.droid_info().map(|info| info.timestamp()) else {
+                    continue;
+                };
+                let days = created.raw().saturating_sub(droid.raw()) / INTERVALS_PER_DAY;
+                if days > DROID_TIME_TOLERANCE_DAYS {
+                    findings.push(Finding::new(
+                        Severity::Low,
+                        Indicator::DroidTimeMismatch,
+                        format!(
+                            "the object droid was made at {:?}, {} days before the target was created",
+                            droid, days
+                        ),
+                    ));
+                }
+            }
+        }
+        findings
+    }
+
+    /// The hosts the icon of the shell link is loaded from over the network,
+    /// if its IconLocation or IconEnvironmentDataBlock is a UNC path such as
+    /// `\\server\share\icon.ico`. Windows connects to them when the link
+    /// is shown, sending the user's NTLM credentials.
+    pub fn icon_hosts(&self) -> Vec<String> {
+        let mut hosts: Vec<String> = Vec::new();
+        for path in self.icon_paths() {
+            if let Some(host) = unc_host(&path) {
+                if !hosts.iter().any(|h| h.eq_ignore_ascii_case(host)) {
+                    hosts.push(host.to_string());
+                }
+            }
+        }
+        hosts
+    }
+
+    /// The paths the icon may be loaded from.
+    fn icon_paths(&self) -> Vec<String> {
+        let mut paths: Vec<String> = self.icon_location().iter().cloned().collect();
+        for block in self.extra_data() {
+            if let ExtraData::IconEnvironmentProps(props) = block {
+                paths.push(props.target_ansi().clone());
+                paths.extend(props.target_unicode().clone());
+            }
+        }
+        paths
+    }
+
+    /// The paths the target may be found at, for matching on its name.
+    pub(crate) fn target_candidates(&self) -> Vec<String> {
+        self.target_path()
+            .into_iter()
+            .chain(self.relative_path().clone())
+            .collect()
+    }
+
+    fn check_padding(&self, findings: &mut Vec<Finding>) {
+        let Some(arguments) = self.arguments() else {
+            return;
+        };
+        let mut longest = 0;
+        let mut run = 0;
+        for c in arguments.chars() {
+            run = if c.is_whitespace() { run + 1 } else { 0 };
+            longest = longest.max(run);
+        }
+        if longest >= PADDING_THRESHOLD {
+            findings.push(Finding::new(
+                Severity::High,
+                Indicator::WhitespacePadding,
+                format!(
+                    "the arguments hold a run of {} whitespace characters",
+                    longest
+                ),
+            ));
+        }
+    }
+
+    fn check_icon(&self, findings: &mut Vec<Finding>) {
+        let icon = self
+            .extra_data()
+            .iter()
+            .find_map(|block| match block {
+                ExtraData::IconEnvironmentProps(props) => Some(
+                    props
+                        .target_unicode()
+                        .clone()
+                        .unwrap_or_else(|| props.target_ansi().clone()),
+                ),
+                _ => None,
+            })
+            .or_else(|| self.icon_location().clone());
+        let Some(icon) = icon else {
+            return;
+        };
+        if !DOCUMENT_EXTENSIONS.contains(&extension(&icon).as_str()) {
+            return;
+        }
+        if let Some(target) = self
+            .target_candidates()
+            .into_iter()
+            .find(|target| EXECUTABLE_EXTENSIONS.contains(&extension(target).as_str()))
+        {
+            findings.push(Finding::new(
+                Severity::Medium,
+                Indicator::IconMismatch,
+                format!(
+                    "the target {:?} runs code, but the icon is taken from {:?}",
+                    target, icon
+                ),
+            ));
+        }
+    }
+
+    fn check_icon_host(&self, findings: &mut Vec<Finding>) {
+        for path in self.icon_paths() {
+            if let Some(host) = unc_host(&path) {
+                findings.push(Finding::new(
+                    Severity::High,
+                    Indicator::NetworkIcon,
+                    format!(
+                        "the icon is loaded from {:?}, which sends the user's credentials to {:?}",
+                        path, host
+                    ),
+                ));
+                return;
+            }
+        }
+    }
+
+    fn check_show_command(&self, findings: &mut Vec<Finding>) {
+        if *self.header().show_command() != ShowCommand::ShowMinNoActive {
+            return;
+        }
+        if let Some(target) = self
+            .target_candidates()
+            .into_iter()
+            .find(|target| INTERPRETERS.contains(&file_name(target).as_str()))
+        {
+            findings.push(Finding::new(
+                Severity::High,
+                Indicator::HiddenInterpreter,
+                format!(
+                    "the interpreter {:?} is started minimized and without focus",
+                    target
+                ),
+            ));
+        }
+    }
+
+    fn check_sizes(&self, findings: &mut Vec<Finding>) {
+        for block in self.extra_data() {
+            let (name, size) = match block {
+                ExtraData::PropertyStoreProps(props) => {
+                    ("PropertyStoreDataBlock", props.property_store().len())
+                }
+                ExtraData::VistaAndAboveIdListProps(props) => (
+                    "VistaAndAboveIDListDataBlock",
+                    props.id_list().iter().map(|id| id.data().len() + 2).sum(),
+                ),
+                _ => continue,
+            };
+            if size > OVERSIZED_BLOCK_THRESHOLD {
+                findings.push(Finding::new(
+                    Severity::Medium,
+                    Indicator::OversizedExtraData,
+                    format!("the {} holds {} bytes", name, size),
+                ));
+            }
+        }
+        if let Some(trailing) = self.trailing_data() {
+            findings.push(Finding::new(
+                Severity::Medium,
+                Indicator::TrailingData,
+                format!(
+                    "{} bytes follow the TerminalBlock at offset 0x{:x}",
+                    trailing.size(),
+                    trailing.offset()
+                ),
+            ));
+        }
+    }
+
+    fn check_strings(&self, findings: &mut Vec<Finding>) {
+        let mut pairs: Vec<(&str, &String, &Option<String>)> = Vec::new();
+        if let Some(link_info) = self.link_info() {
+            if let Some(local_base_path) = link_info.local_base_path() {
+                pairs.push((
+                    "LocalBasePath",
+                    local_base_path,
+                    link_info.local_base_path_unicode(),
+                ));
+            }
+            pairs.push((
+                "CommonPathSuffix",
+                link_info.common_path_suffix(),
+                link_info.common_path_suffix_unicode(),
+            ));
+            if let Some(network) = link_info.common_network_relative_link() {
+                pairs.push((
+                    "NetName",
+                    network.net_name_ansi(),
+                    network.net_name_unicode(),
+                ));
+                if let Some(device_name) = network.device_name_ansi() {
+                    pairs.push(("DeviceName", device_name, network.device_name_unicode()));
+                }
+            }
+        }
+        for block in self.extra_data() {
+            match block {
+                ExtraData::EnvironmentProps(props) => pairs.push((
+                    "EnvironmentVariableDataBlock",
+                    props.target_ansi(),
+                    props.target_unicode(),
+                )),
+                ExtraData::IconEnvironmentProps(props) => pairs.push((
+                    "IconEnvironmentDataBlock",
+                    props.target_ansi(),
+                    props.target_unicode(),
+                )),
+                ExtraData::DarwinProps(props) => pairs.push((
+                    "DarwinDataBlock",
+                    props.darwin_data_ansi(),
+                    props.darwin_data_unicode(),
+                )),
+                _ => {}
+            }
+        }
+        for (name, ansi, unicode) in pairs {
+            let Some(unicode) = unicode else {
+                continue;
+            };
+            if !ansi.is_empty() && !ansi_matches(ansi, unicode) {
+                findings.push(Finding::new(
+                    Severity::High,
+                    Indicator::StringMismatch,
+                    format!(
+                        "the {} is {:?} as ANSI, but {:?} as Unicode",
+                        name, ansi, unicode
+                    ),
+                ));
+            }
+        }
+    }
+
+    fn check_unicode(&self, findings: &mut Vec<Finding>) {
+        let mut strings: Vec<(&str, String)> = Vec::new();
+        strings.extend(self.name().clone().map(|s| ("name", s)));
+        strings.extend(self.arguments().clone().map(|s| ("arguments", s)));
+        strings.extend(self.icon_location().clone().map(|s| ("icon location", s)));
+        for block in self.extra_data() {
+            if let ExtraData::IconEnvironmentProps(props) = block {
+                strings.extend(props.target_unicode().clone().map(|s| ("icon location", s)));
+            }
+        }
+        strings.extend(self.target_path().map(|s| ("target", s)));
+        strings.extend(self.relative_path().clone().map(|s| ("relative path", s)));
+
+        for (name, s) in strings {
+            for (severity, indicator, characters) in [
+                (Severity::High, Indicator::BidiControl, BIDI_CONTROLS),
+                (
+                    Severity::Medium,
+                    Indicator::InvisibleCharacter,
+                    INVISIBLE_CHARACTERS,
+                ),
+            ] {
+                let found: Vec<String> = characters
+                    .iter()
+                    .filter(|(c, _)| s.contains(*c))
+                    .map(|(c, description)| format!("U+{:04X} {}", *c as u32, description))
+                    .collect();
+                if !found.is_empty() {
+                    findings.push(Finding::new(
+                        severity,
+                        indicator,
+                        format!("the {} {:?} holds {}", name, s, found.join(", ")),
+                    ));
+                }
+            }
+            let mixed: Vec<&str> = s
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|word| is_mixed_script(word))
+                .collect();
+            if !mixed.is_empty() {
+                findings.push(Finding::new(
+                    Severity::Medium,
+                    Indicator::MixedScript,
+                    format!(
+                        "the {} {:?} mixes Latin letters with Greek or Cyrillic ones in {:?}",
+                        name,
+                        s,
+                        mixed.join(" ")
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// The scripts whose letters look alike.
+#[derive(PartialEq)]
+enum Script {
+    Latin,
+    Greek,
+    Cyrillic,
+}
+
+impl Script {
+    fn of(c: char) -> Option<Self> {
+        match c {
+            'A'..='Z' | 'a'..='z' | '\u{00c0}'..='\u{024f}' if c.is_alphabetic() => {
+                Some(Self::Latin)
+            }
+            '\u{0370}'..='\u{03ff}' | '\u{1f00}'..='\u{1fff}' => Some(Self::Greek),
+            '\u{0400}'..='\u{052f}' => Some(Self::Cyrillic),
+            _ => None,
+        }
+    }
+}
+
+/// Whether a word holds Latin letters and also Greek or Cyrillic ones.
+fn is_mixed_script(word: &str) -> bool {
+    let mut scripts = word.chars().filter_map(Script::of);
+    let Some(first) = scripts.next() else {
+        return false;
+    };
+    scripts.any(|script| script != first)
+        && word.chars().any(|c| Script::of(c) == Some(Script::Latin))
+}
+
+/// Whether an ANSI string is the Unicode string stored alongside it, as
+/// Windows writes it: characters that do not fit the code page are written
+/// as `?`, or as a similar character.
+fn ansi_matches(ansi: &str, unicode: &str) -> bool {
+    ansi.chars().count() == unicode.chars().count()
+        && ansi
+            .chars()
+            .zip(unicode.chars())
+            .all(|(a, u)| a == u || !u.is_ascii())
+}
+
+/// The host of a UNC path, such as `server` in `\\server\share` or
+/// `\\?\UNC\server\share`. Paths to local devices, such as `\\?\C:\`,
+/// have none.
+fn unc_host(path: &str) -> Option<&str> {
+    let path = path.trim().trim_matches('"');
+    let rest = match path.get(..8) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("\\\\?\\UNC\\") => &path[8..],
+        _ => path
+            .strip_prefix("\\\\")
+            .or_else(|| path.strip_prefix("//"))?,
+    };
+    let host = rest.split(['\\', '/']).next()?;
+    (!host.is_empty() && host != "?" && host != ".").then_some(host)
+}
+
+/// The file name at the end of a path, in lower case.
+fn file_name(path: &str) -> String {
+    path.rsplit(['\\', '/'])
+        .next()
+        .unwrap_or_default()
+        .trim_matches('"')
+        .to_lowercase()
+}
+
+/// The extension of the file at the end of a path, in lower case, ignoring
+/// any icon index after a comma.
+fn extension(path: &str) -> String {
+    let name = file_name(path);
+    let name = name.split(',').next().unwrap_or_default();
+    match name.rsplit_once('.') {
+        Some((_, extension)) => extension.trim().to_string(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,121 @@
+use crate::extradata::property_store_data;
+use crate::guids::{CLSID_APPS_FOLDER, FMTID_APP_USER_MODEL};
+use crate::{ExtraData, Guid, ShellLink};
+
+/// The property ID of System.AppUserModel.ID.
+pub(crate) const APP_USER_MODEL_ID: u32 = 5;
+
+/// The identity of the packaged (UWP or AppX) app that a shell link to a
+/// Store app starts, taken from its AppUserModelID, which has the form
+/// `PackageFamilyName!ApplicationId`. See [`ShellLink::appx_identity`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct AppxIdentity {
+    package_family_name: String,
+    application_id: String,
+}
+
+impl AppxIdentity {
+    /// Read the identity from the AppUserModelID of a packaged app, such as
+    /// `Microsoft.WindowsCalculator_8wekyb3d8bbwe!App`. Returns `None` for
+    /// the AppUserModelIDs of desktop apps, which have no package family
+    /// name.
+    pub fn from_app_user_model_id(app_user_model_id: &str) -> Option<Self> {
+        let (package_family_name, application_id) = app_user_model_id.split_once('!')?;
+        let (name, publisher_id) = package_family_name.rsplit_once('_')?;
+        if name.is_empty() || publisher_id.is_empty() || application_id.is_empty() {
+            return None;
+        }
+        Some(Self {
+            package_family_name: package_family_name.to_string(),
+            application_id: application_id.to_string(),
+        })
+    }
+
+    /// The package family name, such as
+    /// `Microsoft.WindowsCalculator_8wekyb3d8bbwe`.
+    pub fn package_family_name(&self) -> &str {
+        &self.package_family_name
+    }
+
+    /// The name of the package, such as `Microsoft.WindowsCalculator`.
+    pub fn package_name(&self) -> &str {
+        self.split_family_name().0
+    }
+
+    /// The publisher ID, a hash of the publisher of the package, such as
+    /// `8wekyb3d8bbwe` for Microsoft.
+    pub fn publisher_id(&self) -> &str {
+        self.split_family_name().1
+    }
+
+    /// The ID of the application within the package, such as `App`.
+    pub fn application_id(&self) -> &str {
+        &self.application_id
+    }
+
+    /// The AppUserModelID, `PackageFamilyName!ApplicationId`.
+    pub fn app_user_model_id(&self) -> String {
+        format!("{}!{}", self.package_family_name, self.application_id)
+    }
+
+    fn split_family_name(&self) -> (&str, &str) {
+        self.package_family_name
+            .rsplit_once('_')
+            .unwrap_or((&self.package_family_name, ""))
+    }
+}
+
+impl ShellLink {
+    /// The AppUserModelID of the app the shell link starts, from the
+    /// System.AppUserModel.ID property in a PropertyStoreDataBlock or, for
+    /// shell links whose LinkTargetIDList is an item of the Applications
+    /// folder, from the properties that item holds.
+    pub fn app_user_model_id(&self) -> Option<String> {
+        let find = |strings: Vec<(Guid, u32, String)>| {
+            strings
+                .into_iter()
+                .find(|(format_id, id, value)| {
+                    *format_id == FMTID_APP_USER_MODEL
+                        && *id == APP_USER_MODEL_ID
+                        && !value.is_empty()
+                })
+                .map(|(_, _, value)| value)
+        };
+        let from_extra_data = self.extra_data().iter().find_map(|block| match block {
+            ExtraData::PropertyStoreProps(props) => find(props.strings()),
+            _ => None,
+        });
+        if from_extra_data.is_some() {
+            return from_extra_data;
+        }
+
+        let items = self.link_target_id_list().as_ref()?.id_list();
+        let root = items.first()?.data();
+        if root.first() != Some(&0x1f) || root.get(2..18).map(Guid::read) != Some(CLSID_APPS_FOLDER)
+        {
+            return None;
+        }
+        // The app item holds a serialized property storage, which starts
+        // with its size and the Version `1SPS`.
+        items[1..].iter().find_map(|item| {
+            let data = item.data();
+            (4..data.len().saturating_sub(4))
+                .filter(|&i| &data[i..i + 4] == b"1SPS")
+                .find_map(|i| find(property_store_data::strings(&data[i - 4..])))
+        })
+    }
+
+    /// The identity of the packaged (UWP or AppX) app the shell link starts,
+    /// if it is a shell link to a Store app. See
+    /// [`ShellLink::app_user_model_id`].
+    pub fn appx_identity(&self) -> Option<AppxIdentity> {
+        AppxIdentity::from_app_user_model_id(&self.app_user_model_id()?)
+    }
+
+    /// Whether the shell link starts a packaged (UWP or AppX) app.
+    pub fn is_appx(&self) -> bool {
+        self.appx_identity().is_some()
+    }
+}
@@ -0,0 +1,110 @@
+#[allow(unused)]
+use crate::logging::{debug, error, info, trace, warn};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::{Error, ParseOptions, ShellLink};
+
+/// What [`ShellLink::open_many`] does with a shell link that cannot be
+/// opened.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ErrorPolicy {
+    /// Stop at the first shell link that cannot be opened, and return its
+    /// path and error.
+    FailFast,
+    /// Open every shell link, keeping the error for each that cannot be
+    /// opened alongside the others.
+    #[default]
+    Collect,
+}
+
+/// The shell links opened by [`ShellLink::open_many`], keyed by the path each
+/// was opened from.
+pub type OpenedLinks = BTreeMap<PathBuf, Result<ShellLink, Error>>;
+
+impl ShellLink {
+    /// Open and parse the shell link at each of `paths` with the given
+    /// options, one after another, returning the result for each keyed by
+    /// its path. A path given more than once is opened once.
+    ///
+    /// With [`ErrorPolicy::FailFast`], the first shell link that cannot be
+    /// opened stops the rest and is returned along with its path, so every
+    /// result in the map is `Ok`. With [`ErrorPolicy::Collect`] this never
+    /// fails, and the error for each shell link is in the map.
+    pub fn open_many<I, P>(
+        paths: I,
+        options: &ParseOptions,
+        policy: ErrorPolicy,
+    ) -> Result<OpenedLinks, (PathBuf, Error)>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let mut links = BTreeMap::new();
+        for path in paths {
+            let path = path.as_ref();
+            if links.contains_key(path) {
+                continue;
+            }
+            trace!("Opening {:?}", path);
+            match (Self::open_with_options(path, options), policy) {
+                (Err(e), ErrorPolicy::FailFast) => return Err((path.to_path_buf(), e)),
+                (result, _) => links.insert(path.to_path_buf(), result),
+            };
+        }
+        Ok(links)
+    }
+}
+
+#[cfg(feature = "rayon")]
+/// Walk the directory tree at `path` and parse every file in it with a `.lnk`
+/// extension, in parallel, with the given options.
+///
+/// Each file is yielded along with the result of parsing it. Directories
+/// that cannot be read are yielded along with the error reading them.
+/// Symbolic links to directories are not followed.
+pub fn parse_dir<P: AsRef<Path>>(
+    path: P,
+    options: &ParseOptions,
+) -> impl ParallelIterator<Item = (PathBuf, Result<ShellLink, Error>)> {
+    let mut found = Vec::new();
+    walk(path.as_ref(), &mut found);
+    let options = options.clone();
+    found.into_par_iter().map(move |(path, walked)| {
+        let result = walked.and_then(|()| ShellLink::open_with_options(&path, &options));
+        (path, result)
+    })
+}
+
+#[cfg(feature = "rayon")]
+/// Collect the shell links in the tree at `dir`, along with any directory
+/// that cannot be read and the error reading it.
+fn walk(dir: &Path, found: &mut Vec<(PathBuf, Result<(), Error>)>) {
+    trace!("Walking {:?}", dir);
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => return found.push((dir.to_path_buf(), Err(e.into()))),
+    };
+    for entry in entries {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => return found.push((dir.to_path_buf(), Err(e.into()))),
+        };
+        let path = entry.path();
+        match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => walk(&path, found),
+            Ok(_) if is_shell_link_name(&path) => found.push((path, Ok(()))),
+            Ok(_) => {}
+            Err(e) => found.push((path, Err(e.into()))),
+        }
+    }
+}
+
+#[cfg(feature = "rayon")]
+fn is_shell_link_name(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("lnk"))
+}
@@ -0,0 +1,264 @@
+//! Print a short report on each of the shell links named on the command
+//! line, with the details usually wanted in incident notes: the target and
+//! how to launch it, the timestamps, where the target was stored, the
+//! machine the link was made on and any tricks used by malicious links.
+//!
+//! Custom jump lists are reported on one shell link at a time, as are
+//! automatic jump lists with the `cfb` feature.
+
+use std::env;
+use std::fmt::Display;
+use std::process::ExitCode;
+
+use lnk::guids::FMTID_LINK;
+use lnk::{CustomDestinations, ExtraData, FileTime, HotkeyKey, ShellLink, TargetLocation};
+
+/// The width of the field names in the report.
+const NAME_WIDTH: usize = 14;
+
+/// The property ID of System.Link.TargetParsingPath.
+const TARGET_PARSING_PATH: u32 = 2;
+
+fn main() -> ExitCode {
+    let paths: Vec<String> = env::args().skip(1).collect();
+    if paths.is_empty() {
+        eprintln!("Usage: lnkcat FILE...");
+        return ExitCode::from(2);
+    }
+
+    let mut failed = false;
+    for (i, path) in paths.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        if path
+            .to_ascii_lowercase()
+            .ends_with(".customdestinations-ms")
+        {
+            failed |= !report_custom_destinations(path);
+            continue;
+        }
+        #[cfg(feature = "cfb")]
+        if is_jump_list(path) {
+            failed |= !report_jump_list(path);
+            continue;
+        }
+        match ShellLink::open(path) {
+            Ok(link) => report(path, &link),
+            Err(e) => {
+                eprintln!("{}: {}", path, e);
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Report on each shell link in a custom jump list, returning whether the
+/// whole file could be parsed.
+fn report_custom_destinations(path: &str) -> bool {
+    let jump_list = match CustomDestinations::open(path) {
+        Ok(jump_list) => jump_list,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            return false;
+        }
+    };
+    for (index, link) in jump_list.links() {
+        if index > 0 {
+            println!();
+        }
+        report(&format!("{} [{}]", path, index), link);
+    }
+    for warning in jump_list.warnings() {
+        eprintln!("{}: {}", path, warning);
+    }
+    jump_list.warnings().is_empty()
+}
+
+#[cfg(feature = "cfb")]
+fn is_jump_list(path: &str) -> bool {
+    path.to_ascii_lowercase()
+        .ends_with(".automaticdestinations-ms")
+}
+
+/// Report on each shell link in a jump list, returning whether they could
+/// all be parsed.
+#[cfg(feature = "cfb")]
+fn report_jump_list(path: &str) -> bool {
+    let jump_list = match lnk::JumpList::open(path) {
+        Ok(jump_list) => jump_list,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            return false;
+        }
+    };
+    let mut ok = true;
+    for (i, entry) in jump_list.entries().iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        let name = format!("{} [{}]", path, entry.stream_name());
+        match entry.link() {
+            Ok(link) => report(&name, link),
+            Err(e) => {
+                eprintln!("{}: {}", name, e);
+                ok = false;
+                continue;
+            }
+        }
+        if let Some(destination) = entry.destination() {
+            time("Last used", destination.modification_time());
+            if let Some(position) = destination.pin_position() {
+                field("Pinned", position);
+            }
+            field("Host", destination.hostname());
+        }
+    }
+    for warning in jump_list.warnings() {
+        field("Warning", warning);
+    }
+    ok
+}
+
+fn field(name: &str, value: impl Display) {
+    println!(
+        "  {:<width$} {}",
+        format!("{}:", name),
+        value,
+        width = NAME_WIDTH
+    );
+}
+
+fn optional(name: &str, value: &Option<String>) {
+    if let Some(value) = value {
+        field(name, value);
+    }
+}
+
+fn time(name: &str, value: FileTime) {
+    field(name, value)
+}
+
+fn report(path: &str, link: &ShellLink) {
+    let header = link.header();
+    println!("{}", path);
+
+    match link.target_path() {
+        Some(target) if link.target_location() == TargetLocation::Environment => {
+            field("Target", format_args!("{} (environment)", target))
+        }
+        Some(target) => field("Target", target),
+        None => field("Target", "unknown"),
+    }
+    optional("Arguments", link.arguments());
+    optional("Working dir", link.working_dir());
+    optional("Relative path", link.relative_path());
+    if let Some(icon) = link.icon_location() {
+        field("Icon", format_args!("{},{}", icon, header.icon_index()));
+    }
+    optional("Description", link.name());
+    optional("App ID", &link.app_user_model_id());
+
+    time("Created", header.creation_time());
+    time("Modified", header.write_time());
+    time("Accessed", header.access_time());
+    field("Size", format_args!("{} bytes", header.file_size()));
+    let attributes: Vec<&str> = header
+        .file_attributes()
+        .iter_names()
+        .map(|(name, _)| name)
+        .collect();
+    if !attributes.is_empty() {
+        field("Attributes", attributes.join(" | "));
+    }
+    field("Show", format_args!("{:?}", header.show_command()));
+    if !matches!(header.hotkey().key(), HotkeyKey::NoKeyAssigned) {
+        field("Hotkey", header.hotkey());
+    }
+
+    if let Some(link_info) = link.link_info() {
+        if let Some(volume) = link_info.volume_id() {
+            field(
+                "Volume",
+                format_args!(
+                    "{:?}, serial {:04X}-{:04X}, label {:?}",
+                    volume.drive_type(),
+                    volume.drive_serial_number() >> 16,
+                    volume.drive_serial_number() & 0xffff,
+                    volume.volume_label(),
+                ),
+            );
+        }
+        if let Some(network) = link_info.common_network_relative_link() {
+            field("Share", network.net_name());
+            if let Some(device) = network.device_name().filter(|d| !d.is_empty()) {
+                field("Device", device);
+            }
+            if let Some(provider) = network.network_provider_type() {
+                field("Provider", format_args!("{:?}", provider));
+            }
+        }
+    }
+
+    for block in link.extra_data() {
+        match block {
+            ExtraData::TrackerProps(tracker) => {
+                field("Machine", tracker.machine_id());
+                field("Volume droid", tracker.droid()[0]);
+                field("File droid", tracker.droid()[1]);
+                if let Some(info) = tracker.droid_info() {
+                    field("MAC address", info.mac_address());
+                    time("Droid made", info.timestamp());
+                }
+                if tracker.droid_birth() != tracker.droid() {
+                    field("Birth volume", tracker.droid_birth()[0]);
+                    field("Birth file", tracker.droid_birth()[1]);
+                    if let Some(info) = tracker.droid_birth_info() {
+                        field("Birth MAC", info.mac_address());
+                        time("Birth made", info.timestamp());
+                    }
+                }
+            }
+            ExtraData::IconEnvironmentProps(props) => field(
+                "Icon env",
+                props
+                    .target_unicode()
+                    .as_ref()
+                    .unwrap_or(props.target_ansi()),
+            ),
+            ExtraData::KnownFolderProps(props) => field("Known folder", props.known_folder_id()),
+            ExtraData::SpecialFolderProps(props) => field(
+                "Special folder",
+                format_args!("CSIDL 0x{:02x}", props.special_folder_id()),
+            ),
+            ExtraData::DarwinProps(props) => field(
+                "Darwin",
+                props
+                    .darwin_data_unicode()
+                    .as_ref()
+                    .unwrap_or(props.darwin_data_ansi()),
+            ),
+            ExtraData::ShimProps(props) => field("Shim", props.layer_name()),
+            ExtraData::PropertyStoreProps(props) => {
+                for (format_id, id, value) in props.strings() {
+                    if format_id == FMTID_LINK && id == TARGET_PARSING_PATH {
+                        field("Parsing path", value);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for finding in link.analyze() {
+        field("Finding", finding);
+    }
+    for warning in link.warnings() {
+        field("Warning", warning);
+    }
+}
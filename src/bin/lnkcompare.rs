@@ -0,0 +1,218 @@
+//! Compare two shell links field by field, printing what was removed from
+//! the first, added in the second and changed between them.
+//!
+//! Each line of the output starts with `-` for a field only the first link
+//! has, `+` for a field only the second has, or `~` for a field whose value
+//! differs. Like `diff`, the exit status is 0 if the links are the same, 1
+//! if they differ and 2 if either cannot be parsed.
+
+use std::env;
+use std::fmt::Debug;
+use std::process::ExitCode;
+
+use bitflags::Flags;
+use lnk::{ExtraData, FileTime, ShellLink};
+
+/// The fields of a shell link, as names and printed values in the order
+/// they appear in the file.
+type Fields = Vec<(String, String)>;
+
+fn main() -> ExitCode {
+    let paths: Vec<String> = env::args().skip(1).collect();
+    let [first, second] = &paths[..] else {
+        eprintln!("Usage: lnkcompare FIRST SECOND");
+        return ExitCode::from(2);
+    };
+
+    let open = |path: &String| {
+        ShellLink::open(path).map_err(|e| {
+            eprintln!("{}: {}", path, e);
+        })
+    };
+    let (Ok(first), Ok(second)) = (open(first), open(second)) else {
+        return ExitCode::from(2);
+    };
+
+    let differences = compare(&fields(&first), &fields(&second));
+    for line in &differences {
+        println!("{}", line);
+    }
+    if differences.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Compare two lists of fields in the order of the first, followed by the
+/// fields only the second has.
+fn compare(first: &Fields, second: &Fields) -> Vec<String> {
+    let find = |fields: &Fields, name: &str| {
+        fields
+            .iter()
+            .find(|(other, _)| other == name)
+            .map(|(_, value)| value.clone())
+    };
+    let mut lines = Vec::new();
+    for (name, value) in first {
+        match find(second, name) {
+            None => lines.push(format!("- {}: {}", name, value)),
+            Some(other) if &other != value => {
+                lines.push(format!("~ {}: {} -> {}", name, value, other))
+            }
+            Some(_) => {}
+        }
+    }
+    lines.extend(
+        second
+            .iter()
+            .filter(|(name, _)| find(first, name).is_none())
+            .map(|(name, value)| format!("+ {}: {}", name, value)),
+    );
+    lines
+}
+
+fn push(fields: &mut Fields, name: impl Into<String>, value: impl Debug) {
+    fields.push((name.into(), format!("{:?}", value)));
+}
+
+fn push_flags<F: Flags>(fields: &mut Fields, prefix: &str, flags: &F) {
+    for (name, _) in flags.iter_names() {
+        fields.push((format!("{}.{}", prefix, name), "set".to_string()));
+    }
+}
+
+fn push_time(fields: &mut Fields, name: &str, value: FileTime) {
+    let printed = match value.is_valid() && !value.is_zero() {
+        true => value.to_string(),
+        false => format!("0x{:016x}", value.raw()),
+    };
+    fields.push((name.to_string(), printed));
+}
+
+fn push_optional(fields: &mut Fields, name: &str, value: &Option<String>) {
+    if let Some(value) = value {
+        push(fields, name, value);
+    }
+}
+
+fn fields(link: &ShellLink) -> Fields {
+    let mut fields = Vec::new();
+    let header = link.header();
+    push_flags(&mut fields, "LinkFlags", header.link_flags());
+    push_flags(&mut fields, "FileAttributes", header.file_attributes());
+    push_time(&mut fields, "CreationTime", header.creation_time());
+    push_time(&mut fields, "AccessTime", header.access_time());
+    push_time(&mut fields, "WriteTime", header.write_time());
+    push(&mut fields, "FileSize", header.file_size());
+    push(&mut fields, "IconIndex", header.icon_index());
+    push(&mut fields, "ShowCommand", header.show_command());
+    fields.push(("HotKey".to_string(), header.hotkey().to_string()));
+    push_optional(&mut fields, "Target", &link.target_path());
+
+    if let Some(id_list) = link.link_target_id_list() {
+        for (i, item) in id_list.id_list().iter().enumerate() {
+            let hex: String = item.data().iter().map(|b| format!("{:02x}", b)).collect();
+            fields.push((format!("LinkTargetIDList[{}]", i), hex));
+        }
+    }
+
+    if let Some(link_info) = link.link_info() {
+        if let Some(volume) = link_info.volume_id() {
+            push(&mut fields, "VolumeID.DriveType", volume.drive_type());
+            fields.push((
+                "VolumeID.DriveSerialNumber".to_string(),
+                format!("{:08X}", volume.drive_serial_number()),
+            ));
+            push(&mut fields, "VolumeID.VolumeLabel", volume.volume_label());
+        }
+        push_optional(&mut fields, "LocalBasePath", link_info.local_base_path());
+        if let Some(network) = link_info.common_network_relative_link() {
+            let prefix = "CommonNetworkRelativeLink";
+            push_flags(&mut fields, prefix, network.flags());
+            if let Some(provider) = network.network_provider_type() {
+                push(
+                    &mut fields,
+                    format!("{}.NetworkProviderType", prefix),
+                    provider,
+                );
+            }
+            push(
+                &mut fields,
+                format!("{}.NetName", prefix),
+                network.net_name_ansi(),
+            );
+            if let Some(device_name) = network.device_name_ansi() {
+                push(&mut fields, format!("{}.DeviceName", prefix), device_name);
+            }
+            if let Some(net_name) = network.net_name_unicode() {
+                push(&mut fields, format!("{}.NetNameUnicode", prefix), net_name);
+            }
+            if let Some(device_name) = network.device_name_unicode() {
+                push(
+                    &mut fields,
+                    format!("{}.DeviceNameUnicode", prefix),
+                    device_name,
+                );
+            }
+        }
+        push(
+            &mut fields,
+            "CommonPathSuffix",
+            link_info.common_path_suffix(),
+        );
+        push_optional(
+            &mut fields,
+            "LocalBasePathUnicode",
+            link_info.local_base_path_unicode(),
+        );
+        push_optional(
+            &mut fields,
+            "CommonPathSuffixUnicode",
+            link_info.common_path_suffix_unicode(),
+        );
+    }
+
+    push_optional(&mut fields, "NameString", link.name());
+    push_optional(&mut fields, "RelativePath", link.relative_path());
+    push_optional(&mut fields, "WorkingDir", link.working_dir());
+    push_optional(&mut fields, "CommandLineArguments", link.arguments());
+    push_optional(&mut fields, "IconLocation", link.icon_location());
+
+    for block in link.extra_data() {
+        let (name, value) = match block {
+            ExtraData::ConsoleProps(block) => ("ConsoleDataBlock", format!("{:?}", block)),
+            ExtraData::ConsoleFeProps(block) => ("ConsoleFEDataBlock", format!("{:?}", block)),
+            ExtraData::DarwinProps(block) => ("DarwinDataBlock", format!("{:?}", block)),
+            ExtraData::EnvironmentProps(block) => {
+                ("EnvironmentVariableDataBlock", format!("{:?}", block))
+            }
+            ExtraData::IconEnvironmentProps(block) => {
+                ("IconEnvironmentDataBlock", format!("{:?}", block))
+            }
+            ExtraData::KnownFolderProps(block) => ("KnownFolderDataBlock", format!("{:?}", block)),
+            ExtraData::PropertyStoreProps(block) => (
+                "PropertyStoreDataBlock",
+                block
+                    .property_store()
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect(),
+            ),
+            ExtraData::ShimProps(block) => ("ShimDataBlock", format!("{:?}", block)),
+            ExtraData::SpecialFolderProps(block) => {
+                ("SpecialFolderDataBlock", format!("{:?}", block))
+            }
+            ExtraData::TrackerProps(block) => ("TrackerDataBlock", format!("{:?}", block)),
+            ExtraData::VistaAndAboveIdListProps(block) => {
+                ("VistaAndAboveIDListDataBlock", format!("{:?}", block))
+            }
+        };
+        fields.push((name.to_string(), value));
+    }
+
+    if let Some(trailing_data) = link.trailing_data() {
+        push(&mut fields, "TrailingDataSize", trailing_data.size());
+    }
+    fields
+}
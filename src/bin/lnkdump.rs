@@ -0,0 +1,167 @@
+//! Print a hexdump of a shell link with each run of bytes labelled by the
+//! field it holds and that field's value, under a heading for each
+//! structure, as a binary template in a hex editor would.
+//!
+//! Each top-level structure is shown in its own colour, and slack, the bytes
+//! that no structure accounts for, in bold red. Colour is used when the
+//! output is a terminal, unless `--no-color` is given or `NO_COLOR` is set.
+
+use std::env;
+use std::io::IsTerminal;
+use std::ops::Range;
+use std::process::ExitCode;
+
+use lnk::{ExtentMap, Field};
+
+/// The number of bytes shown on each line.
+const WIDTH: usize = 16;
+
+/// The colours of the top-level structures, as ANSI SGR parameters.
+const COLOURS: &[(&str, &str)] = &[
+    ("ShellLinkHeader", "36"),
+    ("LinkTargetIDList", "32"),
+    ("LinkInfo", "33"),
+    ("StringData", "35"),
+    ("ExtraData", "34"),
+];
+
+/// The colour of slack.
+const SLACK_COLOUR: &str = "1;31";
+
+fn main() -> ExitCode {
+    let mut colour = std::io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none();
+    let mut paths = Vec::new();
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--no-color" => colour = false,
+            _ => paths.push(arg),
+        }
+    }
+    let [path] = &paths[..] else {
+        eprintln!("Usage: lnkdump [--no-color] FILE");
+        return ExitCode::from(2);
+    };
+
+    let data = match std::fs::read(path) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let map = match ExtentMap::from_slice(&data) {
+        Ok(map) => map,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let paint = |code: &str, text: &str| match colour {
+        true => format!("\x1b[{}m{}\x1b[0m", code, text),
+        false => text.to_string(),
+    };
+
+    let mut heading = None;
+    for (range, field) in regions(&map, data.len()) {
+        let slack = map
+            .slack()
+            .iter()
+            .find(|slack| slack.range().contains(&range.start));
+        // The structure holding the run, rather than one that is only the
+        // field in it.
+        let extent = map.extents().iter().rev().find(|extent| {
+            extent.range().contains(&range.start)
+                && field.is_none_or(|field| field.path != extent.name)
+        });
+        let (structure, title) = match (slack, extent) {
+            (Some(slack), _) => (slack.name.as_str(), format!("{} (slack)", slack)),
+            (None, Some(extent)) => (extent.name.as_str(), extent.to_string()),
+            // A structure that is only a field, such as the TerminalBlock,
+            // is shown under the structure holding it.
+            (None, None) => {
+                let parent = field
+                    .and_then(|field| field.path.rsplit_once('/'))
+                    .map_or("", |(parent, _)| parent);
+                (parent, parent.to_string())
+            }
+        };
+        let code = match slack {
+            Some(_) => SLACK_COLOUR,
+            None => COLOURS
+                .iter()
+                .find(|(name, _)| structure.split('/').next() == Some(name))
+                .map_or("0", |(_, code)| code),
+        };
+        if heading.as_ref() != Some(&title) {
+            println!("{}", paint(&format!("1;{}", code), &title));
+            heading = Some(title);
+        }
+
+        let label = field.map(|field| {
+            // Name the field within the structure its heading names.
+            let name = field
+                .path
+                .strip_prefix(structure)
+                .and_then(|name| name.strip_prefix('/'))
+                .unwrap_or(&field.path);
+            format!("{} = {}", name, field.value)
+        });
+        let mut previous: Option<&[u8]> = None;
+        let mut repeated = false;
+        for (i, line) in data[range.clone()].chunks(WIDTH).enumerate() {
+            // Show a line that repeats the one before it only as `*`, as
+            // hexdump does, unless it is the last.
+            let offset = range.start + i * WIDTH;
+            let last = offset + line.len() == range.end;
+            if previous == Some(line) && !last {
+                if !repeated {
+                    println!("  *");
+                    repeated = true;
+                }
+                continue;
+            }
+            previous = Some(line);
+            repeated = false;
+
+            let hex: Vec<String> = line.iter().map(|b| format!("{:02x}", b)).collect();
+            let text: String = line
+                .iter()
+                .map(|b| match b {
+                    0x20..=0x7e => *b as char,
+                    _ => '.',
+                })
+                .collect();
+            let label = match (i, &label) {
+                (0, Some(label)) => label.as_str(),
+                _ => "",
+            };
+            println!(
+                "  {:08x}  {}  {:<width$}  {}",
+                offset,
+                paint(code, &format!("{:<1$}", hex.join(" "), WIDTH * 3 - 1)),
+                text,
+                label,
+                width = WIDTH,
+            );
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+/// Split the data into runs of bytes, each holding either a field or bytes
+/// that no field accounts for.
+fn regions(map: &ExtentMap, len: usize) -> Vec<(Range<usize>, Option<&Field>)> {
+    let mut regions = Vec::new();
+    let mut cursor = 0;
+    for field in map.fields() {
+        if field.offset > cursor {
+            regions.push((cursor..field.offset, None));
+        }
+        regions.push((field.range(), Some(field)));
+        cursor = field.range().end;
+    }
+    if len > cursor {
+        regions.push((cursor..len, None));
+    }
+    regions
+}
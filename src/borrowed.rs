@@ -0,0 +1,416 @@
+#[allow(unused)]
+use crate::logging::{debug, error, info, trace, warn};
+
+use byteorder::{ByteOrder, LE};
+use encoding_rs::Encoding;
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::Range;
+
+use crate::extradata::ExtraData;
+use crate::linkinfo::{LinkInfo, LinkInfoFlags};
+use crate::linktarget::LinkTargetIdList;
+use crate::{
+    strings, Error, LinkFlags, ParseContext, ParseOptions, ParseWarning, ShellLink, ShellLinkHeader,
+};
+
+/// Where each structure of a shell link lies in its data, found by reading
+/// only the sizes the structures declare.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Sections {
+    /// The LinkTargetIDList, including its size.
+    pub(crate) link_target_id_list: Option<Range<usize>>,
+    pub(crate) link_info: Option<Range<usize>>,
+    /// The characters of each StringData, in the order they are stored.
+    pub(crate) strings: [Option<Range<usize>>; 5],
+    /// The ExtraData blocks, without the TerminalBlock. Each block is known
+    /// to have a BlockSize that fits.
+    pub(crate) extra_data: Range<usize>,
+    /// The number of bytes the shell link occupies.
+    pub(crate) size: usize,
+}
+
+impl Sections {
+    /// Find the structures of the shell link at the start of `data`, parsing
+    /// only its header.
+    pub(crate) fn locate(
+        data: &[u8],
+        ctx: &ParseContext,
+    ) -> Result<(ShellLinkHeader, Self), Error> {
+        if data.len() < 0x4c {
+            return Err(Error::NotAShellLinkError);
+        }
+        ctx.enter("ShellLinkHeader", 0);
+        let header = ShellLinkHeader::parse(&data[0..0x4c], ctx)?;
+        let link_flags = *header.link_flags();
+        let mut sections = Self {
+            size: 0x4c,
+            ..Default::default()
+        };
+        if ctx.options.header_only {
+            return Ok((header, sections));
+        }
+        let mut cursor = 0x4c;
+
+        if link_flags.contains(LinkFlags::HAS_LINK_TARGET_ID_LIST) {
+            ctx.enter("LinkTargetIDList", cursor);
+            let rest = &data[cursor..];
+            ctx.need(rest, 0, 2)?;
+            let size = 2 + LE::read_u16(rest) as usize;
+            ctx.need(rest, 0, size)?;
+            sections.link_target_id_list = Some(cursor..cursor + size);
+            cursor += size;
+        }
+
+        if link_flags.contains(LinkFlags::HAS_LINK_INFO) {
+            ctx.enter("LinkInfo", cursor);
+            let rest = &data[cursor..];
+            ctx.need(rest, 0, 4)?;
+            let size = LE::read_u32(rest) as usize;
+            ctx.check_size(0, size)?;
+            ctx.need(rest, 0, size)?;
+            sections.link_info = Some(cursor..cursor + size);
+            cursor += size;
+        }
+
+        let char_size = if link_flags.contains(LinkFlags::IS_UNICODE) {
+            2
+        } else {
+            1
+        };
+        let string_flags = [
+            LinkFlags::HAS_NAME,
+            LinkFlags::HAS_RELATIVE_PATH,
+            LinkFlags::HAS_WORKING_DIR,
+            LinkFlags::HAS_ARGUMENTS,
+            LinkFlags::HAS_ICON_LOCATION,
+        ];
+        for (string, flag) in sections.strings.iter_mut().zip(string_flags) {
+            if link_flags.contains(flag) {
+                ctx.enter("StringData", cursor);
+                let rest = &data[cursor..];
+                ctx.need(rest, 0, 2)?;
+                let size = LE::read_u16(rest) as usize * char_size;
+                ctx.need(rest, 2, size)?;
+                *string = Some(cursor + 2..cursor + 2 + size);
+                cursor += 2 + size;
+            }
+        }
+
+        let extra_data_start = cursor;
+        loop {
+            ctx.enter("ExtraData", cursor);
+            let rest = &data[cursor..];
+            ctx.need(rest, 0, 4)?;
+            let size = LE::read_u32(rest) as usize;
+            if size < 0x04 {
+                break;
+            }
+            if size < 8 {
+                return Err(ctx.unexpected_value(0, "BlockSize", "at least 8", size));
+            }
+            ctx.check_size(0, size)?;
+            ctx.need(rest, 0, size)?;
+            cursor += size;
+        }
+        sections.extra_data = extra_data_start..cursor;
+        sections.size = cursor + 4; // add the TerminalBlock
+        Ok((header, sections))
+    }
+}
+
+/// A view of a shell link that borrows from the data it was parsed from.
+///
+/// Only the header and the sizes of the other structures are read up front.
+/// Strings are kept as slices of the data and decoded when asked for, and
+/// the LinkTargetIDList, LinkInfo and ExtraData are parsed by their
+/// accessors, each time they are called. This makes it much cheaper than a
+/// [`ShellLink`] when only a few fields of each shell link are needed.
+///
+/// Unlike [`ShellLink`], a structure that runs past the end of the data is an
+/// error rather than being read in part.
+#[derive(Clone, Debug)]
+pub struct ShellLinkRef<'a> {
+    data: &'a [u8],
+    options: ParseOptions,
+    header: ShellLinkHeader,
+    sections: Sections,
+    warnings: Vec<ParseWarning>,
+}
+
+impl<'a> ShellLinkRef<'a> {
+    /// Parse a shell link from the start of `data`, returning it along with
+    /// the number of bytes it occupies.
+    pub fn parse(data: &'a [u8]) -> Result<(Self, usize), Error> {
+        Self::parse_with_options(data, &ParseOptions::default())
+    }
+
+    /// Parse a shell link from the start of `data` with the given options,
+    /// returning it along with the number of bytes it occupies.
+    pub fn parse_with_options(
+        data: &'a [u8],
+        options: &ParseOptions,
+    ) -> Result<(Self, usize), Error> {
+        let ctx = ParseContext::new(options);
+        let (header, sections) = Sections::locate(data, &ctx)?;
+        let size = sections.size;
+        let link = Self {
+            data: &data[..size],
+            options: options.clone(),
+            header,
+            sections,
+            warnings: ctx.into_warnings(),
+        };
+        Ok((link, size))
+    }
+
+    pub(crate) fn from_parts(
+        data: &'a [u8],
+        options: &ParseOptions,
+        header: &ShellLinkHeader,
+        sections: &Sections,
+    ) -> Self {
+        Self {
+            data,
+            options: options.clone(),
+            header: *header,
+            sections: sections.clone(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// The data of the whole shell link.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Get the header of the shell link
+    pub fn header(&self) -> &ShellLinkHeader {
+        &self.header
+    }
+
+    /// Parse the link target ID list, if present.
+    pub fn link_target_id_list(&self) -> Result<Option<LinkTargetIdList>, Error> {
+        self.sections
+            .link_target_id_list
+            .clone()
+            .map(|range| {
+                let ctx = self.context("LinkTargetIDList", range.start);
+                LinkTargetIdList::parse(&self.data[range], &ctx)
+            })
+            .transpose()
+    }
+
+    /// Get a view of the link info structure, if present.
+    pub fn link_info(&self) -> Option<LinkInfoRef<'a>> {
+        self.sections.link_info.clone().map(|range| LinkInfoRef {
+            offset: range.start,
+            data: &self.data[range],
+            options: self.options.clone(),
+        })
+    }
+
+    pub(crate) fn string(&self, index: usize) -> Option<StringRef<'a>> {
+        let unicode = self.header.link_flags().contains(LinkFlags::IS_UNICODE);
+        self.sections.strings[index].clone().map(|range| StringRef {
+            data: &self.data[range],
+            unicode,
+            encoding: self.options.default_encoding,
+        })
+    }
+
+    /// Get the shell link's name, if set
+    pub fn name(&self) -> Option<StringRef<'a>> {
+        self.string(0)
+    }
+
+    /// Get the shell link's relative path, if set
+    pub fn relative_path(&self) -> Option<StringRef<'a>> {
+        self.string(1)
+    }
+
+    /// Get the shell link's working directory, if set
+    pub fn working_dir(&self) -> Option<StringRef<'a>> {
+        self.string(2)
+    }
+
+    /// Get the shell link's arguments, if set
+    pub fn arguments(&self) -> Option<StringRef<'a>> {
+        self.string(3)
+    }
+
+    /// Get the shell link's icon location, if set
+    pub fn icon_location(&self) -> Option<StringRef<'a>> {
+        self.string(4)
+    }
+
+    /// Parse the shell link's extra data blocks one at a time. Each block is
+    /// parsed on its own, so one that cannot be parsed does not stop the
+    /// rest.
+    pub fn extra_data(&self) -> impl Iterator<Item = Result<ExtraData, Error>> + 'a {
+        let mut offset = self.sections.extra_data.start;
+        let end = self.sections.extra_data.end;
+        let data = self.data;
+        let options = self.options.clone();
+        std::iter::from_fn(move || {
+            if offset >= end {
+                return None;
+            }
+            let ctx = ParseContext::new(&options);
+            ctx.enter("ExtraData", offset);
+            let block = &data[offset..end];
+            offset += LE::read_u32(block) as usize;
+            Some(ExtraData::parse(block, &ctx))
+        })
+    }
+
+    /// Get the problems found while parsing the header that did not stop the
+    /// parse.
+    pub fn warnings(&self) -> &[ParseWarning] {
+        &self.warnings
+    }
+
+    /// Parse the whole shell link into an owned [`ShellLink`].
+    pub fn to_shell_link(&self) -> Result<ShellLink, Error> {
+        ShellLink::from_slice_with_options(self.data, &self.options).map(|(link, _)| link)
+    }
+
+    fn context(&self, structure: &'static str, offset: usize) -> ParseContext {
+        let ctx = ParseContext::new(&self.options);
+        ctx.enter(structure, offset);
+        ctx
+    }
+}
+
+/// A view of a LinkInfo structure that borrows from the data it was parsed
+/// from, obtained from [`ShellLinkRef::link_info`]. Strings are only looked
+/// for within the size the structure declares.
+#[derive(Clone, Debug)]
+pub struct LinkInfoRef<'a> {
+    data: &'a [u8],
+    offset: usize,
+    options: ParseOptions,
+}
+
+impl<'a> LinkInfoRef<'a> {
+    /// The data of the whole LinkInfo structure.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    fn read_u32(&self, offset: usize) -> Option<u32> {
+        self.data.get(offset..offset + 4).map(LE::read_u32)
+    }
+
+    fn flags(&self) -> LinkInfoFlags {
+        LinkInfoFlags::from_bits_truncate(self.read_u32(8).unwrap_or(0))
+    }
+
+    fn has_unicode_offsets(&self) -> bool {
+        self.read_u32(4).unwrap_or(0) >= 0x24
+    }
+
+    /// The NULL-terminated string at the offset held `field` bytes into the
+    /// structure, if it is set and the string ends within the structure.
+    fn string_at(&self, field: usize, unicode: bool) -> Option<StringRef<'a>> {
+        let offset = self.read_u32(field)? as usize;
+        if offset == 0 {
+            return None;
+        }
+        let size = strings::nul_terminated_size(self.data, offset, unicode)?;
+        let terminator = if unicode { 2 } else { 1 };
+        Some(StringRef {
+            data: &self.data[offset..offset + size - terminator],
+            unicode,
+            encoding: self.options.default_encoding,
+        })
+    }
+
+    /// The LocalBasePath, if present.
+    pub fn local_base_path(&self) -> Option<StringRef<'a>> {
+        if !self
+            .flags()
+            .contains(LinkInfoFlags::VOLUME_ID_AND_LOCAL_BASE_PATH)
+        {
+            return None;
+        }
+        self.string_at(16, false)
+    }
+
+    /// The CommonPathSuffix.
+    pub fn common_path_suffix(&self) -> Option<StringRef<'a>> {
+        self.string_at(24, false)
+    }
+
+    /// The LocalBasePathUnicode, if present.
+    pub fn local_base_path_unicode(&self) -> Option<StringRef<'a>> {
+        if !self.has_unicode_offsets()
+            || !self
+                .flags()
+                .contains(LinkInfoFlags::VOLUME_ID_AND_LOCAL_BASE_PATH)
+        {
+            return None;
+        }
+        self.string_at(28, true)
+    }
+
+    /// The CommonPathSuffixUnicode, if present.
+    pub fn common_path_suffix_unicode(&self) -> Option<StringRef<'a>> {
+        if !self.has_unicode_offsets() {
+            return None;
+        }
+        self.string_at(32, true)
+    }
+
+    /// Parse the whole LinkInfo structure into an owned [`LinkInfo`].
+    pub fn to_link_info(&self) -> Result<LinkInfo, Error> {
+        let ctx = ParseContext::new(&self.options);
+        ctx.enter("LinkInfo", self.offset);
+        LinkInfo::parse(self.data, &ctx)
+    }
+}
+
+/// A string in a shell link, borrowed from the data it was parsed from and
+/// decoded when asked for.
+#[derive(Clone, Copy)]
+pub struct StringRef<'a> {
+    data: &'a [u8],
+    unicode: bool,
+    encoding: &'static Encoding,
+}
+
+impl<'a> StringRef<'a> {
+    /// The encoded string, without any NULL terminator.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Whether the string is stored as UTF-16, rather than in the system
+    /// default code page.
+    pub fn is_unicode(&self) -> bool {
+        self.unicode
+    }
+
+    /// Decode the string. Strings in the system default code page that only
+    /// hold ASCII characters are borrowed rather than copied.
+    pub fn decode(&self) -> Cow<'a, str> {
+        let encoding = if self.unicode {
+            encoding_rs::UTF_16LE
+        } else {
+            self.encoding
+        };
+        encoding.decode_without_bom_handling(self.data).0
+    }
+}
+
+impl fmt::Debug for StringRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.decode(), f)
+    }
+}
+
+impl fmt::Display for StringRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.decode())
+    }
+}
@@ -0,0 +1,131 @@
+#[allow(unused)]
+use crate::logging::{debug, error, info, trace, warn};
+
+use std::io::{ErrorKind, Read};
+
+use crate::{Error, ParseOptions, ShellLink, ShellLinkHeader};
+
+/// The number of bytes read from the underlying reader at a time.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The number of bytes [`ShellLinkHeader::has_signature`] looks at.
+const SIGNATURE_SIZE: usize = 20;
+
+/// Scan `reader`, which may hold any data such as a memory dump or the
+/// contents of unallocated disk space, for embedded shell links. See
+/// [`carve_with_options`].
+pub fn carve<R: Read>(reader: R) -> Carver<R> {
+    carve_with_options(reader, &ParseOptions::default())
+}
+
+/// Scan `reader` for embedded shell links with the given options.
+///
+/// Every place the data holds the 0x4C HeaderSize followed by the shell link
+/// CLSID is parsed, looking at no more than `options.max_input_size` bytes.
+/// Each shell link that parses is yielded along with its offset in the data,
+/// and the scan carries on after the end of it. Places that do not parse are
+/// skipped.
+pub fn carve_with_options<R: Read>(reader: R, options: &ParseOptions) -> Carver<R> {
+    Carver {
+        reader,
+        options: options.clone(),
+        buffer: Vec::new(),
+        base: 0,
+        pos: 0,
+        eof: false,
+    }
+}
+
+/// An iterator over the shell links embedded in a reader, created by
+/// [`carve`] or [`carve_with_options`].
+///
+/// It yields each shell link along with its offset in the data. Shell links
+/// that are found have no trailing data, as everything after them is the rest
+/// of the data being scanned. If reading fails, the error is yielded and the
+/// scan ends.
+#[derive(Debug)]
+pub struct Carver<R> {
+    reader: R,
+    options: ParseOptions,
+    buffer: Vec<u8>,
+    /// The offset in the data of the start of `buffer`.
+    base: u64,
+    /// The position in `buffer` to scan from.
+    pos: usize,
+    eof: bool,
+}
+
+impl<R: Read> Carver<R> {
+    /// Read until at least `len` bytes are buffered after the scan position,
+    /// or the data ends.
+    fn fill(&mut self, len: usize) -> Result<(), Error> {
+        while !self.eof && self.buffer.len() - self.pos < len {
+            if self.pos >= CHUNK_SIZE {
+                self.buffer.drain(..self.pos);
+                self.base += self.pos as u64;
+                self.pos = 0;
+            }
+            let start = self.buffer.len();
+            self.buffer.resize(start + CHUNK_SIZE, 0);
+            let read = loop {
+                match self.reader.read(&mut self.buffer[start..]) {
+                    Ok(read) => break read,
+                    Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                    Err(e) => {
+                        self.buffer.truncate(start);
+                        self.eof = true;
+                        self.pos = start;
+                        return Err(e.into());
+                    }
+                }
+            };
+            self.buffer.truncate(start + read);
+            self.eof = read == 0;
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Iterator for Carver<R> {
+    type Item = Result<(u64, ShellLink), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Err(e) = self.fill(SIGNATURE_SIZE) {
+                return Some(Err(e));
+            }
+            let data = &self.buffer[self.pos..];
+            if data.len() < SIGNATURE_SIZE {
+                return None;
+            }
+            let found = data
+                .windows(SIGNATURE_SIZE)
+                .position(ShellLinkHeader::has_signature);
+            let Some(found) = found else {
+                self.pos = self.buffer.len() - (SIGNATURE_SIZE - 1);
+                continue;
+            };
+            self.pos += found;
+
+            let limit = self.options.max_input_size;
+            if let Err(e) = self.fill(limit) {
+                return Some(Err(e));
+            }
+            let offset = self.base + self.pos as u64;
+            let end = self.buffer.len().min(self.pos.saturating_add(limit));
+            let data = &self.buffer[self.pos..end];
+            match ShellLink::from_slice_with_options(data, &self.options) {
+                Ok((mut link, consumed)) => {
+                    debug!("Carved a shell link at offset 0x{:x}.", offset);
+                    link.trailing_data = None;
+                    self.pos += consumed;
+                    return Some(Ok((offset, link)));
+                }
+                Err(e) => {
+                    debug!("Skipping signature at offset 0x{:x}: {}", offset, e);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! Differential checking against Windows itself, on Windows only.
+//!
+//! [`resolve`] loads a shell link through the shell's own `IShellLink` and
+//! `IPersistFile` COM interfaces, and [`verify`] compares what Windows makes
+//! of it against what this crate parses. Any difference is either a bug in
+//! the parser or a link crafted to look different to forensic tools than it
+//! does to Explorer.
+
+#[allow(unused)]
+use crate::logging::{debug, error, info, trace, warn};
+
+use std::path::Path;
+
+use windows::core::{Interface, HSTRING};
+use windows::Win32::Foundation::RPC_E_CHANGED_MODE;
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CoUninitialize, IPersistFile, CLSCTX_INPROC_SERVER,
+    COINIT_APARTMENTTHREADED, STGM_READ,
+};
+use windows::Win32::UI::Shell::{IShellLinkW, ShellLink as CLSID_SHELL_LINK, SLGP_RAWPATH};
+
+use crate::{Error, ShellLink};
+
+/// The longest string Windows hands back, in UTF-16 code units.
+const BUFFER_LEN: usize = 32768;
+
+/// What the Windows shell reports about a shell link.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Resolved {
+    /// The path of the target, without environment variables expanded.
+    pub target: Option<String>,
+    /// The command line arguments.
+    pub arguments: Option<String>,
+    /// The working directory.
+    pub working_dir: Option<String>,
+    /// The description, which this crate calls the name.
+    pub description: Option<String>,
+    /// The location of the icon.
+    pub icon_location: Option<String>,
+    /// The index of the icon within the icon location.
+    pub icon_index: i32,
+    /// The show command, as passed to `ShowWindow`.
+    pub show_command: i32,
+    /// The hotkey, with the key in the low byte and the modifiers in the
+    /// high byte.
+    pub hotkey: u16,
+}
+
+/// A field on which this crate and Windows disagree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    /// The name of the field.
+    pub field: &'static str,
+    /// The value parsed by this crate.
+    pub parsed: Option<String>,
+    /// The value reported by Windows.
+    pub resolved: Option<String>,
+}
+
+/// Initializes COM on the calling thread for as long as it lives, unless it
+/// was already initialized in another mode.
+struct ComGuard(bool);
+
+impl ComGuard {
+    fn new() -> Result<Self, Error> {
+        let result = unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED) };
+        if result == RPC_E_CHANGED_MODE {
+            return Ok(Self(false));
+        }
+        result.ok().map_err(std::io::Error::from)?;
+        Ok(Self(true))
+    }
+}
+
+impl Drop for ComGuard {
+    fn drop(&mut self) {
+        if self.0 {
+            unsafe { CoUninitialize() };
+        }
+    }
+}
+
+/// Load a shell link through the Windows shell and report what it makes of
+/// it. The target is not resolved, so a missing target is not an error and
+/// the link is left untouched.
+pub fn resolve<P: AsRef<Path>>(path: P) -> Result<Resolved, Error> {
+    debug!("Loading {:?} through IShellLink", path.as_ref());
+    let _com = ComGuard::new()?;
+    unsafe { load(path.as_ref()) }.map_err(|e| std::io::Error::from(e).into())
+}
+
+unsafe fn load(path: &Path) -> windows::core::Result<Resolved> {
+    let link: IShellLinkW = CoCreateInstance(&CLSID_SHELL_LINK, None, CLSCTX_INPROC_SERVER)?;
+    link.cast::<IPersistFile>()?
+        .Load(&HSTRING::from(path), STGM_READ)?;
+
+    let target = read(|b| link.GetPath(b, std::ptr::null_mut(), SLGP_RAWPATH.0 as u32))?;
+    let arguments = read(|b| link.GetArguments(b))?;
+    let working_dir = read(|b| link.GetWorkingDirectory(b))?;
+    let description = read(|b| link.GetDescription(b))?;
+    let mut icon_index = 0;
+    let icon_location = read(|b| link.GetIconLocation(b, &mut icon_index))?;
+
+    Ok(Resolved {
+        target,
+        arguments,
+        working_dir,
+        description,
+        icon_location,
+        icon_index,
+        show_command: link.GetShowCmd()?.0,
+        hotkey: link.GetHotkey()?,
+    })
+}
+
+/// Read a string that `f` writes into a buffer, or `None` if it is empty,
+/// as Windows reports fields that are not set.
+fn read(
+    f: impl FnOnce(&mut [u16]) -> windows::core::Result<()>,
+) -> windows::core::Result<Option<String>> {
+    let mut buffer = vec![0u16; BUFFER_LEN];
+    f(&mut buffer)?;
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    Ok((len > 0).then(|| String::from_utf16_lossy(&buffer[..len])))
+}
+
+/// Compare a parsed shell link against what Windows reports about it,
+/// returning the fields on which they disagree. Strings are compared
+/// exactly, and the target is compared against
+/// [`ShellLink::target_path`]. A link whose target is only given by its
+/// LinkTargetIDList has no target path to compare, so its target is not
+/// compared.
+pub fn compare(link: &ShellLink, resolved: &Resolved) -> Vec<Mismatch> {
+    let mut mismatches = Vec::new();
+    let mut check = |field, parsed: Option<String>, resolved: Option<String>| {
+        if parsed != resolved {
+            mismatches.push(Mismatch {
+                field,
+                parsed,
+                resolved,
+            });
+        }
+    };
+
+    if let Some(target) = link.target_path() {
+        check("Target", Some(target), resolved.target.clone());
+    }
+    check(
+        "CommandLineArguments",
+        link.arguments().clone(),
+        resolved.arguments.clone(),
+    );
+    check(
+        "WorkingDir",
+        link.working_dir().clone(),
+        resolved.working_dir.clone(),
+    );
+    check(
+        "NameString",
+        link.name().clone(),
+        resolved.description.clone(),
+    );
+    check(
+        "IconLocation",
+        link.icon_location().clone(),
+        resolved.icon_location.clone(),
+    );
+
+    let header = link.header();
+    let hotkey = u16::from(u8::from(*header.hotkey().key()))
+        | u16::from(header.hotkey().modifiers().bits()) << 8;
+    let numbers = [
+        (
+            "IconIndex",
+            header.icon_index() as i64,
+            resolved.icon_index as i64,
+        ),
+        (
+            "ShowCommand",
+            header.show_command_raw() as i64,
+            resolved.show_command as i64,
+        ),
+        ("HotKey", hotkey as i64, resolved.hotkey as i64),
+    ];
+    for (field, parsed, resolved) in numbers {
+        check(field, Some(parsed.to_string()), Some(resolved.to_string()));
+    }
+    mismatches
+}
+
+/// Parse the shell link at `path` with this crate and load it through the
+/// Windows shell, returning the fields on which they disagree.
+pub fn verify<P: AsRef<Path>>(path: P) -> Result<Vec<Mismatch>, Error> {
+    let link = ShellLink::open(path.as_ref())?;
+    let resolved = resolve(path)?;
+    Ok(compare(&link, &resolved))
+}
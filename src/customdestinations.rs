@@ -0,0 +1,224 @@
+#[allow(unused)]
+use crate::logging::{debug, error, info, trace, warn};
+
+use byteorder::{ByteOrder, LE};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use crate::{header, strings, Error, Guid, ParseContext, ParseOptions, ParseWarning, ShellLink};
+
+/// The signature that ends each category.
+const FOOTER: u32 = 0xbabffbab;
+
+/// A custom jump list (`*.customDestinations-ms`), as kept by Windows for
+/// applications that fill in their own jump list categories and tasks, in
+/// `%APPDATA%\Microsoft\Windows\Recent\CustomDestinations`.
+///
+/// The file is a short header followed by the categories, each holding the
+/// shell links shown under it, one after another.
+#[derive(Debug)]
+pub struct CustomDestinations {
+    categories: Vec<Category>,
+    warnings: Vec<ParseWarning>,
+}
+
+impl CustomDestinations {
+    /// Open and parse a custom jump list.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::open_with_options(path, &ParseOptions::default())
+    }
+
+    /// Open and parse a custom jump list with the given options, which also
+    /// apply to the shell links in it.
+    pub fn open_with_options<P: AsRef<Path>>(
+        path: P,
+        options: &ParseOptions,
+    ) -> Result<Self, Error> {
+        debug!("Opening custom jump list {:?}", path.as_ref());
+        let limit = options.max_input_size;
+        let mut data = Vec::new();
+        File::open(path)?
+            .take(limit.saturating_add(1) as u64)
+            .read_to_end(&mut data)?;
+        if data.len() > limit {
+            return Err(Error::LimitExceeded {
+                structure: "CustomDestinations",
+                offset: 0,
+                limit,
+                size: data.len(),
+            });
+        }
+        Self::from_slice_with_options(&data, options)
+    }
+
+    /// Parse a custom jump list from `data`.
+    pub fn from_slice(data: &[u8]) -> Result<Self, Error> {
+        Self::from_slice_with_options(data, &ParseOptions::default())
+    }
+
+    /// Parse a custom jump list from `data` with the given options, which
+    /// also apply to the shell links in it.
+    ///
+    /// If an entry cannot be parsed, where the entries after it start cannot
+    /// be known, so the rest of the file is skipped with a warning, or the
+    /// error is returned in strict mode.
+    pub fn from_slice_with_options(data: &[u8], options: &ParseOptions) -> Result<Self, Error> {
+        let ctx = ParseContext::new(options);
+        ctx.enter("CustomDestinations", 0);
+        ctx.need(data, 0, 12)?;
+        let version = LE::read_u32(data);
+        if version != 2 {
+            return Err(ctx.unexpected_value(0, "Version", "2", version));
+        }
+        let count = LE::read_u32(&data[4..]);
+
+        let mut categories = Vec::new();
+        let mut cursor = 12;
+        let mut index = 0;
+        for _ in 0..count {
+            ctx.enter("Category", cursor);
+            match parse_category(&data[cursor..], &mut index, &ctx) {
+                Ok((category, size)) => {
+                    categories.push(category);
+                    cursor += size;
+                }
+                Err(e) if e.is_malformed() => {
+                    ctx.nonconformance(0, format!("Skipping the rest of the file: {}", e))?;
+                    break;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(Self {
+            categories,
+            warnings: ctx.into_warnings(),
+        })
+    }
+
+    /// Get the categories of the jump list, in the order they are shown.
+    pub fn categories(&self) -> &[Category] {
+        &self.categories
+    }
+
+    /// Iterate over every shell link in the jump list along with its index,
+    /// counting from zero across all the categories.
+    pub fn links(&self) -> impl Iterator<Item = (usize, &ShellLink)> {
+        self.categories
+            .iter()
+            .flat_map(|category| category.entries.iter())
+            .map(|(index, link)| (*index, link))
+    }
+
+    /// Get the problems found while parsing that did not stop the parse.
+    pub fn warnings(&self) -> &[ParseWarning] {
+        &self.warnings
+    }
+}
+
+/// A category of a custom jump list.
+#[derive(Debug)]
+pub struct Category {
+    kind: CategoryKind,
+    entries: Vec<(usize, ShellLink)>,
+}
+
+impl Category {
+    /// The kind of category.
+    pub fn kind(&self) -> &CategoryKind {
+        &self.kind
+    }
+
+    /// The shell links in the category, each with its index in the jump
+    /// list.
+    pub fn entries(&self) -> &[(usize, ShellLink)] {
+        &self.entries
+    }
+}
+
+/// The kind of a category of a custom jump list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum CategoryKind {
+    /// A category named by the application.
+    Custom {
+        /// The name shown as the heading of the category.
+        title: String,
+    },
+    /// A category Windows fills in itself, from the automatic jump list.
+    /// Known categories have no entries here.
+    Known(KnownCategory),
+    /// The application's tasks.
+    Tasks,
+}
+
+/// A category Windows fills in itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum KnownCategory {
+    /// The most frequently used destinations.
+    Frequent,
+    /// The most recently used destinations.
+    Recent,
+    /// A category identifier that is not known.
+    Unknown(i32),
+}
+
+/// Parse a category starting at the start of `data`, returning it along with
+/// its size. Entries are numbered from `index` on.
+fn parse_category(
+    data: &[u8],
+    index: &mut usize,
+    ctx: &ParseContext,
+) -> Result<(Category, usize), Error> {
+    ctx.need(data, 0, 8)?;
+    let (kind, mut cursor, count) = match LE::read_u32(data) {
+        0 => {
+            let title_size = LE::read_u16(&data[4..]) as usize * 2;
+            ctx.need(data, 6, title_size + 4)?;
+            let title = strings::decode_utf16le(&data[6..6 + title_size]);
+            let count = LE::read_u32(&data[6 + title_size..]);
+            (CategoryKind::Custom { title }, 10 + title_size, count)
+        }
+        1 => {
+            let known = match LE::read_i32(&data[4..]) {
+                -1 => KnownCategory::Frequent,
+                -2 => KnownCategory::Recent,
+                other => KnownCategory::Unknown(other),
+            };
+            (CategoryKind::Known(known), 8, 0)
+        }
+        2 => (CategoryKind::Tasks, 8, LE::read_u32(&data[4..])),
+        other => return Err(ctx.unexpected_value(0, "CategoryType", "0, 1 or 2", other)),
+    };
+
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        ctx.need(data, cursor, 16)?;
+        let class = Guid::read(&data[cursor..]);
+        if class != header::CLSID {
+            return Err(ctx.unexpected_value(cursor, "ClassIdentifier", header::CLSID, class));
+        }
+        cursor += 16;
+        let (mut link, size) = ShellLink::from_slice_with_options(&data[cursor..], &ctx.options)?;
+        trace!(
+            "Parsed custom jump list entry {} of 0x{:x} bytes.",
+            index,
+            size
+        );
+        link.trailing_data = None;
+        entries.push((*index, link));
+        *index += 1;
+        cursor += size;
+    }
+
+    ctx.need(data, cursor, 4)?;
+    let footer = LE::read_u32(&data[cursor..]);
+    if footer != FOOTER {
+        ctx.nonconformance(cursor, format!("The category footer is 0x{:08x}.", footer))?;
+    }
+    Ok((Category { kind, entries }, cursor + 4))
+}
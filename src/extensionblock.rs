@@ -0,0 +1,245 @@
+//! The extension blocks that shell items, the ItemIDs of an IDList, carry
+//! after their own data since Windows XP. Each starts with its size, a
+//! version and a signature of the form `0xBEEFxxxx`, and they follow one
+//! another to the end of the item.
+//!
+//! The blocks with the signatures below are decoded; the data of any other
+//! block is kept as it is.
+
+use byteorder::{ByteOrder, LE};
+
+use crate::linktarget::ItemID;
+use crate::{strings, DosDateTime, FileTime, Guid};
+
+/// The signature of the extension block holding a GUID.
+pub const GUID_BLOCK: u32 = 0xbeef0003;
+/// The signature of the extension block of a file entry item, holding its
+/// long name.
+pub const FILE_ENTRY_BLOCK: u32 = 0xbeef0004;
+/// The signature of an extension block holding two timestamps.
+pub const TIMESTAMPS_BLOCK: u32 = 0xbeef0025;
+/// The signature of an extension block holding three timestamps.
+pub const TIMESTAMPS_3_BLOCK: u32 = 0xbeef0026;
+
+/// The size of the ExtensionSize, ExtensionVersion and ExtensionSignature
+/// that start every extension block.
+const HEADER_SIZE: usize = 8;
+
+/// An extension block of a shell item.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ExtensionBlock {
+    size: u16,
+    version: u16,
+    signature: u32,
+    data: Vec<u8>,
+    contents: ExtensionBlockContents,
+}
+
+/// What an extension block holds, decoded according to its signature.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum ExtensionBlockContents {
+    /// A [`GUID_BLOCK`], holding a GUID, such as the CLSID of a shell
+    /// folder.
+    Guid(Guid),
+    /// A [`FILE_ENTRY_BLOCK`].
+    FileEntry(FileEntryExtension),
+    /// A [`TIMESTAMPS_BLOCK`] or [`TIMESTAMPS_3_BLOCK`]. What the times
+    /// record is not documented.
+    Timestamps(Vec<FileTime>),
+    /// A block with another signature, or one too short for its signature,
+    /// whose data is only available as [`ExtensionBlock::data`].
+    Raw,
+}
+
+/// The extension block of a file entry item, which holds the long name of
+/// the file or folder, and when it was created and last accessed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct FileEntryExtension {
+    created: DosDateTime,
+    accessed: DosDateTime,
+    file_reference: Option<u64>,
+    long_name: String,
+    localized_name: Option<String>,
+}
+
+impl ExtensionBlock {
+    /// Read the extension block at the start of `data`, or `None` if there
+    /// is none: if `data` is shorter than its size, or its signature is not
+    /// of the form `0xBEEFxxxx`.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        if data.len() < HEADER_SIZE {
+            return None;
+        }
+        let size = LE::read_u16(data);
+        let signature = LE::read_u32(&data[4..]);
+        if (size as usize) < HEADER_SIZE || signature >> 16 != 0xbeef {
+            return None;
+        }
+        let version = LE::read_u16(&data[2..]);
+        let data = data.get(HEADER_SIZE..size as usize)?.to_vec();
+        let contents = ExtensionBlockContents::decode(signature, version, &data)
+            .unwrap_or(ExtensionBlockContents::Raw);
+        Some(Self {
+            size,
+            version,
+            signature,
+            data,
+            contents,
+        })
+    }
+
+    /// The size, in bytes, of the block, including its size, version and
+    /// signature.
+    pub fn size(&self) -> u16 {
+        self.size
+    }
+
+    /// The version of the block, which decides the fields it holds.
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    /// The signature of the block, such as [`FILE_ENTRY_BLOCK`].
+    pub fn signature(&self) -> u32 {
+        self.signature
+    }
+
+    /// The data of the block, following its size, version and signature.
+    pub fn data(&self) -> &Vec<u8> {
+        &self.data
+    }
+
+    /// What the block holds.
+    pub fn contents(&self) -> &ExtensionBlockContents {
+        &self.contents
+    }
+}
+
+impl ExtensionBlockContents {
+    /// Decode the data of a block with a known signature, or `None` if it
+    /// is too short.
+    fn decode(signature: u32, version: u16, data: &[u8]) -> Option<Self> {
+        let file_times = |start: usize, count: usize| {
+            let times = data.get(start..start + count * 8)?;
+            Some(Self::Timestamps(
+                times
+                    .chunks_exact(8)
+                    .map(|time| FileTime::from(LE::read_u64(time)))
+                    .collect(),
+            ))
+        };
+        match signature {
+            GUID_BLOCK => Some(Self::Guid(Guid::read(data.get(..16)?))),
+            FILE_ENTRY_BLOCK => FileEntryExtension::parse(version, data).map(Self::FileEntry),
+            TIMESTAMPS_BLOCK => file_times(2, 2),
+            TIMESTAMPS_3_BLOCK => file_times(4, 3),
+            _ => Some(Self::Raw),
+        }
+    }
+}
+
+impl FileEntryExtension {
+    /// Read the data of a [`FILE_ENTRY_BLOCK`] of version `version`. The
+    /// fields before the long name vary with the version.
+    fn parse(version: u16, data: &[u8]) -> Option<Self> {
+        let mut extension = Self {
+            created: LE::read_u32(data.get(0..4)?).into(),
+            accessed: LE::read_u32(data.get(4..8)?).into(),
+            ..Default::default()
+        };
+        let mut offset = 10;
+        if version >= 7 {
+            extension.file_reference = Some(LE::read_u64(data.get(offset + 2..offset + 10)?));
+            offset += 18;
+        }
+        let mut localized_name_size = 0;
+        if version >= 3 {
+            localized_name_size = LE::read_u16(data.get(offset..offset + 2)?);
+            offset += 2;
+        }
+        if version >= 9 {
+            offset += 4;
+        }
+        if version >= 8 {
+            offset += 4;
+        }
+
+        let size = strings::nul_terminated_size(data, offset, true)?;
+        extension.long_name = strings::decode_utf16le(&data[offset..offset + size - 2]);
+        offset += size;
+        if version >= 3 && localized_name_size > 0 {
+            let unicode = version >= 7;
+            let size = strings::nul_terminated_size(data, offset, unicode)?;
+            let name = &data[offset..offset + size - if unicode { 2 } else { 1 }];
+            extension.localized_name = Some(match unicode {
+                true => strings::decode_utf16le(name),
+                false => strings::decode_exact(encoding_rs::WINDOWS_1252, name),
+            });
+        }
+        Some(extension)
+    }
+
+    /// When the file was created, in local time.
+    pub fn created(&self) -> DosDateTime {
+        self.created
+    }
+
+    /// When the file was last accessed, in local time.
+    pub fn accessed(&self) -> DosDateTime {
+        self.accessed
+    }
+
+    /// The NTFS file reference of the file, its MFT entry index in the low
+    /// 48 bits and sequence number in the high 16, held from version 7 on.
+    pub fn file_reference(&self) -> Option<u64> {
+        self.file_reference
+    }
+
+    /// The long name of the file or folder.
+    pub fn long_name(&self) -> &String {
+        &self.long_name
+    }
+
+    /// The localized name of the file or folder, as shown by Explorer,
+    /// where it has one.
+    pub fn localized_name(&self) -> &Option<String> {
+        &self.localized_name
+    }
+}
+
+impl ItemID {
+    /// The extension blocks of the item, in the order they are stored.
+    ///
+    /// Where the blocks start is not recorded reliably, so the data of the
+    /// item is searched for the first extension block from which a run of
+    /// blocks reaches the end of the item, allowing for a final 16-bit
+    /// offset that follows the last block in some items.
+    pub fn extension_blocks(&self) -> Vec<ExtensionBlock> {
+        let data = self.data();
+        (0..data.len().saturating_sub(HEADER_SIZE - 1))
+            .step_by(2)
+            .find_map(|start| extension_blocks(data, start))
+            .unwrap_or_default()
+    }
+}
+
+/// The run of extension blocks starting `start` bytes into the data of an
+/// item, if there is one that reaches the end of the item.
+fn extension_blocks(data: &[u8], mut start: usize) -> Option<Vec<ExtensionBlock>> {
+    let mut blocks = Vec::new();
+    while let Some(block) = ExtensionBlock::parse(&data[start..]) {
+        start += block.size() as usize;
+        blocks.push(block);
+        if data.len() - start <= 2 {
+            return Some(blocks);
+        }
+    }
+    None
+}
@@ -0,0 +1,775 @@
+use std::fmt;
+use std::ops::Range;
+use std::path::Path;
+
+use bitflags::Flags;
+use byteorder::{ByteOrder, LE};
+use num_traits::FromPrimitive;
+
+use crate::borrowed::Sections;
+use crate::extradata::block_name;
+use crate::linkinfo::{
+    CommonNetworkRelativeLinkFlags, DriveType, LinkInfoFlags, NetworkProviderType,
+};
+use crate::strings::{decode_fixed, decode_fixed_unicode, decode_utf16le};
+use crate::{
+    Error, FileAttributeFlags, FileTime, Guid, HotkeyFlags, LinkFlags, ParseContext, ParseOptions,
+    ShowCommand,
+};
+
+/// The names of the StringData structures, in the order they are stored.
+const STRING_NAMES: [&str; 5] = [
+    "NameString",
+    "RelativePath",
+    "WorkingDir",
+    "CommandLineArguments",
+    "IconLocation",
+];
+
+/// The length of a field that runs to the end of its structure.
+const REST: usize = usize::MAX;
+
+/// The fields of a structure: their offsets into it, lengths, names and how
+/// their values are read.
+type Layout = &'static [(usize, usize, &'static str, Kind)];
+
+const HEADER: Layout = &[
+    (0x00, 4, "HeaderSize", Kind::U32),
+    (0x04, 16, "LinkCLSID", Kind::Guid),
+    (0x14, 4, "LinkFlags", Kind::LinkFlags),
+    (0x18, 4, "FileAttributes", Kind::FileAttributes),
+    (0x1c, 8, "CreationTime", Kind::Time),
+    (0x24, 8, "AccessTime", Kind::Time),
+    (0x2c, 8, "WriteTime", Kind::Time),
+    (0x34, 4, "FileSize", Kind::U32),
+    (0x38, 4, "IconIndex", Kind::I32),
+    (0x3c, 4, "ShowCommand", Kind::ShowCommand),
+    (0x40, 2, "HotKey", Kind::HotKey),
+    (0x42, 2, "Reserved1", Kind::Hex16),
+    (0x44, 4, "Reserved2", Kind::Hex32),
+    (0x48, 4, "Reserved3", Kind::Hex32),
+];
+
+const LINK_INFO_HEADER: Layout = &[
+    (0x00, 4, "LinkInfoSize", Kind::U32),
+    (0x04, 4, "LinkInfoHeaderSize", Kind::U32),
+    (0x08, 4, "LinkInfoFlags", Kind::LinkInfoFlags),
+    (0x0c, 4, "VolumeIDOffset", Kind::Offset),
+    (0x10, 4, "LocalBasePathOffset", Kind::Offset),
+    (0x14, 4, "CommonNetworkRelativeLinkOffset", Kind::Offset),
+    (0x18, 4, "CommonPathSuffixOffset", Kind::Offset),
+    (0x1c, 4, "LocalBasePathOffsetUnicode", Kind::Offset),
+    (0x20, 4, "CommonPathSuffixOffsetUnicode", Kind::Offset),
+];
+
+const VOLUME_ID: Layout = &[
+    (0x00, 4, "VolumeIDSize", Kind::U32),
+    (0x04, 4, "DriveType", Kind::DriveType),
+    (0x08, 4, "DriveSerialNumber", Kind::SerialNumber),
+    (0x0c, 4, "VolumeLabelOffset", Kind::Offset),
+    (0x10, 4, "VolumeLabelOffsetUnicode", Kind::Offset),
+];
+
+const COMMON_NETWORK_RELATIVE_LINK: Layout = &[
+    (0x00, 4, "CommonNetworkRelativeLinkSize", Kind::U32),
+    (
+        0x04,
+        4,
+        "CommonNetworkRelativeLinkFlags",
+        Kind::NetworkFlags,
+    ),
+    (0x08, 4, "NetNameOffset", Kind::Offset),
+    (0x0c, 4, "DeviceNameOffset", Kind::Offset),
+    (0x10, 4, "NetworkProviderType", Kind::NetworkProviderType),
+    (0x14, 4, "NetNameOffsetUnicode", Kind::Offset),
+    (0x18, 4, "DeviceNameOffsetUnicode", Kind::Offset),
+];
+
+const EXTRA_DATA_BLOCK: Layout = &[
+    (0x00, 4, "BlockSize", Kind::U32),
+    (0x04, 4, "BlockSignature", Kind::Hex32),
+];
+
+const CONSOLE: Layout = &[
+    (0x08, 2, "FillAttributes", Kind::Hex16),
+    (0x0a, 2, "PopupFillAttributes", Kind::Hex16),
+    (0x0c, 2, "ScreenBufferSizeX", Kind::U16),
+    (0x0e, 2, "ScreenBufferSizeY", Kind::U16),
+    (0x10, 2, "WindowSizeX", Kind::U16),
+    (0x12, 2, "WindowSizeY", Kind::U16),
+    (0x14, 2, "WindowOriginX", Kind::U16),
+    (0x16, 2, "WindowOriginY", Kind::U16),
+    (0x18, 4, "Unused1", Kind::Hex32),
+    (0x1c, 4, "Unused2", Kind::Hex32),
+    (0x20, 4, "FontSize", Kind::Hex32),
+    (0x24, 4, "FontFamily", Kind::Hex32),
+    (0x28, 4, "FontWeight", Kind::U32),
+    (0x2c, 64, "FaceName", Kind::Unicode),
+    (0x6c, 4, "CursorSize", Kind::U32),
+    (0x70, 4, "FullScreen", Kind::U32),
+    (0x74, 4, "QuickEdit", Kind::U32),
+    (0x78, 4, "InsertMode", Kind::U32),
+    (0x7c, 4, "AutoPosition", Kind::U32),
+    (0x80, 4, "HistoryBufferSize", Kind::U32),
+    (0x84, 4, "NumberOfHistoryBuffers", Kind::U32),
+    (0x88, 4, "HistoryNoDup", Kind::U32),
+    (0x8c, 64, "ColorTable", Kind::Bytes),
+];
+
+const CONSOLE_FE: Layout = &[(0x08, 4, "CodePage", Kind::U32)];
+
+const DARWIN: Layout = &[
+    (0x008, 260, "DarwinDataAnsi", Kind::Ansi),
+    (0x10c, 520, "DarwinDataUnicode", Kind::Unicode),
+];
+
+const ENVIRONMENT: Layout = &[
+    (0x008, 260, "TargetAnsi", Kind::Ansi),
+    (0x10c, 520, "TargetUnicode", Kind::Unicode),
+];
+
+const KNOWN_FOLDER: Layout = &[
+    (0x08, 16, "KnownFolderID", Kind::Guid),
+    (0x18, 4, "Offset", Kind::U32),
+];
+
+const PROPERTY_STORE: Layout = &[(0x08, REST, "PropertyStore", Kind::Bytes)];
+
+const SHIM: Layout = &[(0x08, REST, "LayerName", Kind::Unicode)];
+
+const SPECIAL_FOLDER: Layout = &[
+    (0x08, 4, "SpecialFolderID", Kind::U32),
+    (0x0c, 4, "Offset", Kind::U32),
+];
+
+const TRACKER: Layout = &[
+    (0x08, 4, "Length", Kind::U32),
+    (0x0c, 4, "Version", Kind::U32),
+];
+
+const TRACKER_DROIDS: Layout = &[
+    (0x00, 16, "Droid[0]", Kind::Guid),
+    (0x10, 16, "Droid[1]", Kind::Guid),
+    (0x20, 16, "DroidBirth[0]", Kind::Guid),
+    (0x30, 16, "DroidBirth[1]", Kind::Guid),
+];
+
+const UNKNOWN_BLOCK: Layout = &[(0x08, REST, "Data", Kind::Bytes)];
+
+/// How the value of a field is read from its bytes.
+#[derive(Clone, Copy)]
+enum Kind {
+    U16,
+    U32,
+    I32,
+    Hex16,
+    Hex32,
+    Offset,
+    Time,
+    Guid,
+    LinkFlags,
+    FileAttributes,
+    ShowCommand,
+    HotKey,
+    LinkInfoFlags,
+    DriveType,
+    SerialNumber,
+    NetworkFlags,
+    NetworkProviderType,
+    /// A NULL-terminated string in the default code page.
+    Ansi,
+    /// A NULL-terminated UTF-16 string.
+    Unicode,
+    /// A string in the default code page filling the field.
+    AnsiText,
+    /// A UTF-16 string filling the field.
+    UnicodeText,
+    Bytes,
+}
+
+impl Kind {
+    /// Read the value of a field from its bytes, which are long enough for
+    /// the kind.
+    fn read(self, bytes: &[u8], ctx: &ParseContext) -> String {
+        let u32 = || LE::read_u32(bytes);
+        match self {
+            Kind::U16 => LE::read_u16(bytes).to_string(),
+            Kind::U32 => u32().to_string(),
+            Kind::I32 => LE::read_i32(bytes).to_string(),
+            Kind::Hex16 => format!("0x{:04x}", LE::read_u16(bytes)),
+            Kind::Hex32 => format!("0x{:08x}", u32()),
+            Kind::Offset => format!("0x{:04x}", u32()),
+            Kind::Time => {
+                let time = FileTime::from_raw(LE::read_u64(bytes));
+                if time.is_zero() {
+                    "not set".to_string()
+                } else {
+                    format!("{:?}", time)
+                }
+            }
+            Kind::Guid => Guid::read(bytes).to_string(),
+            Kind::LinkFlags => flag_names(LinkFlags::from_bits_retain(u32())),
+            Kind::FileAttributes => flag_names(FileAttributeFlags::from_bits_retain(u32())),
+            Kind::ShowCommand => enum_name(ShowCommand::from_u32(u32()), u32()),
+            Kind::HotKey => HotkeyFlags::from_bits(LE::read_u16(bytes)).to_string(),
+            Kind::LinkInfoFlags => flag_names(LinkInfoFlags::from_bits_retain(u32())),
+            Kind::DriveType => enum_name(DriveType::from_u32(u32()), u32()),
+            Kind::SerialNumber => format!("{:04X}-{:04X}", u32() >> 16, u32() & 0xffff),
+            Kind::NetworkFlags => {
+                flag_names(CommonNetworkRelativeLinkFlags::from_bits_retain(u32()))
+            }
+            Kind::NetworkProviderType => enum_name(NetworkProviderType::from_u32(u32()), u32()),
+            Kind::Ansi => decode_fixed(bytes, ctx),
+            Kind::Unicode => decode_fixed_unicode(bytes),
+            Kind::AnsiText => ctx.decode(bytes),
+            Kind::UnicodeText => decode_utf16le(bytes),
+            Kind::Bytes => {
+                let mut hex: Vec<String> = bytes
+                    .iter()
+                    .take(16)
+                    .map(|b| format!("{:02x}", b))
+                    .collect();
+                if bytes.len() > 16 {
+                    hex.push("...".to_string());
+                }
+                hex.join(" ")
+            }
+        }
+    }
+}
+
+/// The names of the flags set, separated by ` | `.
+fn flag_names<F: Flags>(flags: F) -> String {
+    let names: Vec<&str> = flags.iter_names().map(|(name, _)| name).collect();
+    names.join(" | ")
+}
+
+/// The name of a value of an enumeration, or the value in hex if it is not
+/// one of them.
+fn enum_name<T: fmt::Debug>(value: Option<T>, raw: u32) -> String {
+    match value {
+        Some(value) => format!("{:?}", value),
+        None => format!("0x{:08x}", raw),
+    }
+}
+
+/// Where a structure of a shell link lies in its data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Extent {
+    /// The offset of the structure from the start of the shell link.
+    pub offset: usize,
+    /// The number of bytes the structure occupies.
+    pub length: usize,
+    /// The structure, with the structures holding it before it, such as
+    /// `LinkInfo/VolumeID`. For slack, the structure holding the bytes not
+    /// accounted for, or `TrailingData` for those after the shell link.
+    pub name: String,
+}
+
+impl Extent {
+    fn new(offset: usize, length: usize, name: impl Into<String>) -> Self {
+        Self {
+            offset,
+            length,
+            name: name.into(),
+        }
+    }
+
+    /// The bytes the structure occupies.
+    pub fn range(&self) -> Range<usize> {
+        self.offset..self.offset + self.length
+    }
+
+    /// How many structures hold this one.
+    pub fn depth(&self) -> usize {
+        self.name.matches('/').count()
+    }
+}
+
+impl fmt::Display for Extent {
+    /// Write the extent in the form `0x004c..0x0150 LinkTargetIDList`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "0x{:04x}..0x{:04x} {}",
+            self.offset,
+            self.offset + self.length,
+            self.name
+        )
+    }
+}
+
+/// Where a field of a shell link lies in its data, and its value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Field {
+    /// The offset of the field from the start of the shell link.
+    pub offset: usize,
+    /// The number of bytes the field occupies.
+    pub length: usize,
+    /// The field, after the structures holding it, such as
+    /// `LinkInfo/VolumeID/DriveType`.
+    pub path: String,
+    /// The value of the field as read from its bytes: a number, the names
+    /// of the flags set, a time, a string, or the first bytes of data in
+    /// hex.
+    pub value: String,
+}
+
+impl Field {
+    /// The bytes the field occupies.
+    pub fn range(&self) -> Range<usize> {
+        self.offset..self.offset + self.length
+    }
+}
+
+impl fmt::Display for Field {
+    /// Write the field in the form `0x0038..0x003c ShellLinkHeader/IconIndex
+    /// = 0`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "0x{:04x}..0x{:04x} {} = {}",
+            self.offset,
+            self.offset + self.length,
+            self.path,
+            self.value
+        )
+    }
+}
+
+/// A map of where each structure of a shell link lies in its data, and of
+/// the slack: bytes that no structure accounts for, where data can be hidden
+/// from tools that read only the structures. The fields of the structures
+/// are annotated with their values, for hex viewers and for debugging
+/// malformed files.
+///
+/// The structures are found by reading only the sizes and offsets they
+/// declare. Slack is looked for between the structures of the
+/// LinkTargetIDList and LinkInfo, and after the shell link.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ExtentMap {
+    extents: Vec<Extent>,
+    slack: Vec<Extent>,
+    fields: Vec<Field>,
+}
+
+impl ExtentMap {
+    /// Map the file at `path`, which holds a shell link at its start.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::from_slice(&std::fs::read(path)?)
+    }
+
+    /// Map `data`, which holds a shell link at its start.
+    pub fn from_slice(data: &[u8]) -> Result<Self, Error> {
+        Self::from_slice_with_options(data, &ParseOptions::default())
+    }
+
+    /// Map `data`, which holds a shell link at its start, finding its
+    /// structures with the given options.
+    pub fn from_slice_with_options(data: &[u8], options: &ParseOptions) -> Result<Self, Error> {
+        let ctx = ParseContext::new(options);
+        let (_, sections) = Sections::locate(data, &ctx)?;
+        let mut map = Self::default();
+        map.extents
+            .push(Extent::new(0, 0x4c, "ShellLinkHeader".to_string()));
+        map.layout(data, &ctx, 0..0x4c, "ShellLinkHeader", HEADER);
+
+        if let Some(range) = sections.link_target_id_list {
+            map.id_list(data, &ctx, range);
+        }
+        if let Some(range) = sections.link_info {
+            map.link_info(data, &ctx, range);
+        }
+        let flags = LinkFlags::from_bits_retain(LE::read_u32(&data[0x14..]));
+        let kind = match flags.contains(LinkFlags::IS_UNICODE) {
+            true => Kind::UnicodeText,
+            false => Kind::AnsiText,
+        };
+        for (range, name) in sections.strings.iter().zip(STRING_NAMES) {
+            if let Some(range) = range {
+                let name = format!("StringData/{}", name);
+                let count = format!("{}/CountCharacters", name);
+                map.field(data, &ctx, range.start - 2, 2, count, Kind::U16);
+                let string = format!("{}/String", name);
+                map.field(data, &ctx, range.start, range.len(), string, kind);
+                map.extents
+                    .push(Extent::new(range.start - 2, range.len() + 2, name));
+            }
+        }
+
+        let mut offset = sections.extra_data.start;
+        while offset < sections.extra_data.end {
+            let size = LE::read_u32(&data[offset..]) as usize;
+            let signature = LE::read_u32(&data[offset + 4..]);
+            let name = match block_name(signature) {
+                Some(name) => format!("ExtraData/{}", name),
+                None => format!("ExtraData/0x{:08x}", signature),
+            };
+            map.extra_data_block(data, &ctx, offset..offset + size, signature, &name);
+            map.extents.push(Extent::new(offset, size, name));
+            offset += size;
+        }
+        if !options.header_only {
+            map.extents
+                .push(Extent::new(offset, 4, "ExtraData/TerminalBlock"));
+            let path = "ExtraData/TerminalBlock".to_string();
+            map.field(data, &ctx, offset, 4, path, Kind::U32);
+        }
+
+        if data.len() > sections.size {
+            map.slack.push(Extent::new(
+                sections.size,
+                data.len() - sections.size,
+                "TrailingData",
+            ));
+        }
+        map.extents
+            .sort_by_key(|extent| (extent.offset, std::cmp::Reverse(extent.length)));
+        map.slack.sort_by_key(|extent| extent.offset);
+        map.fields.sort_by_key(|field| field.offset);
+        Ok(map)
+    }
+
+    /// The structures found, in order of offset, with each structure before
+    /// those it holds.
+    pub fn extents(&self) -> &[Extent] {
+        &self.extents
+    }
+
+    /// The ranges of bytes that no structure accounts for, in order of
+    /// offset.
+    pub fn slack(&self) -> &[Extent] {
+        &self.slack
+    }
+
+    /// The fields of the structures found, in order of offset.
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    /// The innermost structure holding the byte at `offset`, if any.
+    pub fn at(&self, offset: usize) -> Option<&Extent> {
+        self.extents
+            .iter()
+            .filter(|extent| extent.range().contains(&offset))
+            .max_by_key(|extent| extent.depth())
+    }
+
+    /// The field holding the byte at `offset`, if any.
+    pub fn field_at(&self, offset: usize) -> Option<&Field> {
+        self.fields
+            .iter()
+            .find(|field| field.range().contains(&offset))
+    }
+
+    /// Record the field at `path`, `length` bytes at `offset`, if it lies
+    /// within `data`.
+    fn field(
+        &mut self,
+        data: &[u8],
+        ctx: &ParseContext,
+        offset: usize,
+        length: usize,
+        path: String,
+        kind: Kind,
+    ) {
+        if length == 0 {
+            return;
+        }
+        if let Some(bytes) = data.get(offset..offset + length) {
+            self.fields.push(Field {
+                offset,
+                length,
+                path,
+                value: kind.read(bytes, ctx),
+            });
+        }
+    }
+
+    /// Record the fields of `layout` for the structure `name` at `range`,
+    /// leaving out those that do not fit in it.
+    fn layout(
+        &mut self,
+        data: &[u8],
+        ctx: &ParseContext,
+        range: Range<usize>,
+        name: &str,
+        layout: Layout,
+    ) {
+        for &(offset, length, field, kind) in layout {
+            let offset = range.start + offset;
+            let length = match length {
+                REST => range.end.saturating_sub(offset),
+                length => length,
+            };
+            if offset + length <= range.end {
+                let path = format!("{}/{}", name, field);
+                self.field(data, ctx, offset, length, path, kind);
+            }
+        }
+    }
+
+    /// Record the ItemIDs of the IDList of the structure `name`, from
+    /// `range.start` to the TerminalID, returning the range of each and the
+    /// offset of the TerminalID.
+    fn item_ids(
+        &mut self,
+        data: &[u8],
+        ctx: &ParseContext,
+        range: Range<usize>,
+        name: &str,
+    ) -> (Vec<Range<usize>>, Option<usize>) {
+        let mut items = Vec::new();
+        let mut cursor = range.start;
+        while cursor + 2 <= range.end {
+            let size = LE::read_u16(&data[cursor..]) as usize;
+            if size == 0 {
+                let path = format!("{}/TerminalID", name);
+                self.field(data, ctx, cursor, 2, path, Kind::U16);
+                return (items, Some(cursor));
+            }
+            if size < 2 || cursor + size > range.end {
+                break;
+            }
+            let item = format!("{}/ItemID[{}]", name, items.len());
+            let path = format!("{}/ItemIDSize", item);
+            self.field(data, ctx, cursor, 2, path, Kind::U16);
+            let path = format!("{}/Data", item);
+            self.field(data, ctx, cursor + 2, size - 2, path, Kind::Bytes);
+            items.push(cursor..cursor + size);
+            cursor += size;
+        }
+        (items, None)
+    }
+
+    /// Map the ItemIDs of the LinkTargetIDList at `range`.
+    fn id_list(&mut self, data: &[u8], ctx: &ParseContext, range: Range<usize>) {
+        self.extents
+            .push(Extent::new(range.start, range.len(), "LinkTargetIDList"));
+        self.extents
+            .push(Extent::new(range.start, 2, "LinkTargetIDList/IDListSize"));
+        let path = "LinkTargetIDList/IDListSize".to_string();
+        self.field(data, ctx, range.start, 2, path, Kind::U16);
+        let (items, terminal) =
+            self.item_ids(data, ctx, range.start + 2..range.end, "LinkTargetIDList");
+        for (index, item) in items.into_iter().enumerate() {
+            self.extents.push(Extent::new(
+                item.start,
+                item.len(),
+                format!("LinkTargetIDList/ItemID[{}]", index),
+            ));
+        }
+        if let Some(terminal) = terminal {
+            self.extents
+                .push(Extent::new(terminal, 2, "LinkTargetIDList/TerminalID"));
+        }
+        self.fill_slack(range, "LinkTargetIDList");
+    }
+
+    /// Map the structures the LinkInfo at `range` points to.
+    fn link_info(&mut self, data: &[u8], ctx: &ParseContext, range: Range<usize>) {
+        let link_info = &data[range.clone()];
+        self.extents
+            .push(Extent::new(range.start, range.len(), "LinkInfo"));
+        let read = |offset: usize| {
+            link_info
+                .get(offset..offset + 4)
+                .map_or(0, |b| LE::read_u32(b) as usize)
+        };
+        let header_size = read(4).min(link_info.len());
+        self.extents.push(Extent::new(
+            range.start,
+            header_size,
+            "LinkInfo/LinkInfoHeader",
+        ));
+        let header = range.start..range.start + header_size;
+        self.layout(
+            data,
+            ctx,
+            header,
+            "LinkInfo/LinkInfoHeader",
+            LINK_INFO_HEADER,
+        );
+        let flags = LinkInfoFlags::from_bits_retain(read(8) as u32);
+        let unicode = header_size >= 0x24;
+
+        // Structures that begin with their size, at the offset in the field
+        // `field`.
+        let sized = |field: usize| {
+            let offset = read(field);
+            // The size is at least that of the size field, unless the
+            // LinkInfo ends first.
+            (offset != 0 && offset < link_info.len())
+                .then(|| offset..offset + read(offset).max(4).min(link_info.len() - offset))
+        };
+        // NULL-terminated strings, at the offset in the field `field` from
+        // the start of the structure `base` bytes in. Those of the LinkInfo
+        // itself are structures, and those of the structures it holds are
+        // fields of them.
+        let mut strings = Vec::new();
+        let mut string = |base: usize, field: usize, kind: Kind, name: &str| {
+            let offset = read(base + field);
+            if offset != 0 {
+                strings.push((base + offset, kind, name.to_string(), base == 0));
+            }
+        };
+
+        if flags.contains(LinkInfoFlags::VOLUME_ID_AND_LOCAL_BASE_PATH) {
+            if let Some(volume) = sized(0x0c) {
+                let name = "LinkInfo/VolumeID";
+                let start = range.start + volume.start;
+                self.extents.push(Extent::new(start, volume.len(), name));
+                // A VolumeLabelOffset of 0x14 gives the offset of the Unicode
+                // label in the field after it.
+                let unicode_label = read(volume.start + 0x0c) == 0x14;
+                let end = start + volume.len().min(if unicode_label { 0x14 } else { 0x10 });
+                self.layout(data, ctx, start..end, name, VOLUME_ID);
+                let (field, kind) = match unicode_label {
+                    true => (0x10, Kind::Unicode),
+                    false => (0x0c, Kind::Ansi),
+                };
+                string(volume.start, field, kind, "LinkInfo/VolumeID/VolumeLabel");
+            }
+            string(0, 0x10, Kind::Ansi, "LinkInfo/LocalBasePath");
+            if unicode {
+                string(0, 0x1c, Kind::Unicode, "LinkInfo/LocalBasePathUnicode");
+            }
+        }
+        if flags.contains(LinkInfoFlags::COMMON_NETWORK_RELATIVE_LINK_AND_PATH_SUFFIX) {
+            if let Some(network) = sized(0x14) {
+                let name = "LinkInfo/CommonNetworkRelativeLink";
+                let start = range.start + network.start;
+                self.extents.push(Extent::new(start, network.len(), name));
+                // A NetNameOffset past 0x14 makes room for the offsets of the
+                // Unicode names.
+                let unicode_names = read(network.start + 0x08) > 0x14;
+                let end = start + network.len().min(if unicode_names { 0x1c } else { 0x14 });
+                self.layout(data, ctx, start..end, name, COMMON_NETWORK_RELATIVE_LINK);
+                let network_flags = read(network.start + 4) as u32;
+                let device = CommonNetworkRelativeLinkFlags::from_bits_retain(network_flags)
+                    .contains(CommonNetworkRelativeLinkFlags::VALID_DEVICE);
+                let names = [
+                    (0x08, Kind::Ansi, "NetName", true),
+                    (0x0c, Kind::Ansi, "DeviceName", device),
+                    (0x14, Kind::Unicode, "NetNameUnicode", unicode_names),
+                    (
+                        0x18,
+                        Kind::Unicode,
+                        "DeviceNameUnicode",
+                        unicode_names && device,
+                    ),
+                ];
+                for (field, kind, string_name, present) in names {
+                    if present {
+                        string(
+                            network.start,
+                            field,
+                            kind,
+                            &format!("{}/{}", name, string_name),
+                        );
+                    }
+                }
+            }
+        }
+        string(0, 0x18, Kind::Ansi, "LinkInfo/CommonPathSuffix");
+        if unicode {
+            string(0, 0x20, Kind::Unicode, "LinkInfo/CommonPathSuffixUnicode");
+        }
+
+        for (offset, kind, name, structure) in strings {
+            if offset >= link_info.len() {
+                continue;
+            }
+            let unit = match kind {
+                Kind::Unicode => 2,
+                _ => 1,
+            };
+            let length = link_info[offset..]
+                .chunks(unit)
+                .position(|c| c.iter().all(|b| *b == 0))
+                .map_or(link_info.len() - offset, |end| (end + 1) * unit)
+                .min(link_info.len() - offset);
+            if structure {
+                self.extents
+                    .push(Extent::new(range.start + offset, length, name.clone()));
+            }
+            self.field(data, ctx, range.start + offset, length, name, kind);
+        }
+        self.fill_slack(range, "LinkInfo");
+    }
+
+    /// Record the fields of the ExtraData block `name` at `range`.
+    fn extra_data_block(
+        &mut self,
+        data: &[u8],
+        ctx: &ParseContext,
+        range: Range<usize>,
+        signature: u32,
+        name: &str,
+    ) {
+        self.layout(data, ctx, range.clone(), name, EXTRA_DATA_BLOCK);
+        let layout = match signature {
+            0xa0000001 | 0xa0000007 => ENVIRONMENT,
+            0xa0000002 => CONSOLE,
+            0xa0000003 => TRACKER,
+            0xa0000004 => CONSOLE_FE,
+            0xa0000005 => SPECIAL_FOLDER,
+            0xa0000006 => DARWIN,
+            0xa0000008 => SHIM,
+            0xa0000009 => PROPERTY_STORE,
+            0xa000000a => {
+                self.item_ids(data, ctx, range.start + 8..range.end, name);
+                return;
+            }
+            0xa000000b => KNOWN_FOLDER,
+            _ => UNKNOWN_BLOCK,
+        };
+        self.layout(data, ctx, range.clone(), name, layout);
+        if signature == 0xa0000003 {
+            // The MachineID fills the Length not taken by the other fields.
+            let length = data
+                .get(range.start + 8..range.start + 12)
+                .map_or(0, |b| LE::read_u32(b) as usize);
+            let machine_id = match length > 0x58 && range.start + 8 + length <= range.end {
+                true => length - 0x48,
+                false => 16,
+            };
+            let start = range.start + 0x10;
+            let path = format!("{}/MachineID", name);
+            self.field(data, ctx, start, machine_id, path, Kind::Ansi);
+            let droids = start + machine_id;
+            self.layout(data, ctx, droids..range.end, name, TRACKER_DROIDS);
+        }
+    }
+
+    /// Record as slack the bytes of the structure `name` at `range` that none
+    /// of the structures it holds account for.
+    fn fill_slack(&mut self, range: Range<usize>, name: &str) {
+        let prefix = format!("{}/", name);
+        let mut held: Vec<Range<usize>> = self
+            .extents
+            .iter()
+            .filter(|extent| extent.name.starts_with(&prefix))
+            .map(Extent::range)
+            .collect();
+        held.sort_by_key(|held| held.start);
+        let mut cursor = range.start;
+        for held in held {
+            if held.start > cursor {
+                self.slack
+                    .push(Extent::new(cursor, held.start - cursor, name));
+            }
+            cursor = cursor.max(held.end);
+        }
+        if range.end > cursor {
+            self.slack
+                .push(Extent::new(cursor, range.end - cursor, name));
+        }
+    }
+}
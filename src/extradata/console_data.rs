@@ -1,13 +1,16 @@
 use bitflags::bitflags;
 use byteorder::{ByteOrder, LE};
 
-use crate::strings;
+use std::convert::TryFrom;
+
+use crate::{strings, ParseContext};
 
 bitflags! {
   /// A 16-bit, unsigned integer that specifies the fill attributes that
   /// control the foreground and background text colors in the console
   /// window. The following bit definitions can be combined to specify 16
   /// different values each for the foreground and background colors:
+  #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
   pub struct FillAttributeFlags: u16 {
     /// The foreground text color contains blue.
     const FOREGROUND_BLUE      = 0b0000_0000_0000_0001;
@@ -33,6 +36,7 @@ bitflags! {
   /// A 32-bit, unsigned integer that specifies the family of the font
   /// used in the console window. This value MUST be comprised of a font
   /// family and an optional font pitch.
+  #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
   pub struct FontFamilyFlags: u32 {
     /// The font family is unknown.
     const FF_DONT_CARE  = 0x0000;
@@ -62,6 +66,9 @@ bitflags! {
 /// when a link target specifies an application that is run in a console
 /// window.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ConsoleDataBlock {
     /// A 16-bit, unsigned integer that specifies the fill attributes that
     /// control the foreground and background text colors in the console
@@ -105,6 +112,7 @@ pub struct ConsoleDataBlock {
     font_weight: u32,
     /// A 32-character Unicode string that specifies the face name of the font
     /// used in the console window.
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::fuzz::unicode_string::<31>))]
     face_name: String,
     /// A 32-bit, unsigned integer that specifies the size of the cursor, in
     /// pixels, used in the console window.
@@ -149,6 +157,11 @@ impl ConsoleDataBlock {
         &self.fill_attributes
     }
 
+    /// Set the fill attributes
+    pub fn set_fill_attributes(&mut self, fill_attributes: FillAttributeFlags) {
+        self.fill_attributes = fill_attributes;
+    }
+
     /// A 16-bit, unsigned integer that specifies the fill attributes that
     /// control the foreground and background text color in the console
     /// window popup. The values are the same as for the FillAttributes
@@ -157,42 +170,77 @@ impl ConsoleDataBlock {
         &self.popup_fill_attributes
     }
 
+    /// Set the popup fill attributes
+    pub fn set_popup_fill_attributes(&mut self, popup_fill_attributes: FillAttributeFlags) {
+        self.popup_fill_attributes = popup_fill_attributes;
+    }
+
     /// A 16-bit, signed integer that specifies the horizontal size (X axis),
     /// in characters, of the console window buffer.
     pub fn screen_buffer_size_x(&self) -> &i16 {
         &self.screen_buffer_size_x
     }
 
+    /// Set the horizontal size of the console window buffer
+    pub fn set_screen_buffer_size_x(&mut self, screen_buffer_size_x: i16) {
+        self.screen_buffer_size_x = screen_buffer_size_x;
+    }
+
     /// A 16-bit, signed integer that specifies the vertical size (Y axis),
     /// in characters, of the console window buffer.
     pub fn screen_buffer_size_y(&self) -> &i16 {
         &self.screen_buffer_size_y
     }
 
+    /// Set the vertical size of the console window buffer
+    pub fn set_screen_buffer_size_y(&mut self, screen_buffer_size_y: i16) {
+        self.screen_buffer_size_y = screen_buffer_size_y;
+    }
+
     /// A 16-bit, signed integer that specifies the horizontal size (X axis),
     /// in characters, of the console window.
     pub fn window_size_x(&self) -> &i16 {
         &self.window_size_x
     }
 
+    /// Set the horizontal size of the console window
+    pub fn set_window_size_x(&mut self, window_size_x: i16) {
+        self.window_size_x = window_size_x;
+    }
+
     /// A 16-bit, signed integer that specifies the vertical size (Y axis),
     /// in characters, of the console window.
     pub fn window_size_y(&self) -> &i16 {
         &self.window_size_y
     }
 
+    /// Set the vertical size of the console window
+    pub fn set_window_size_y(&mut self, window_size_y: i16) {
+        self.window_size_y = window_size_y;
+    }
+
     /// A 16-bit, signed integer that specifies the horizontal coordinate (X axis),
     /// in pixels, of the console window origin.
     pub fn window_origin_x(&self) -> &i16 {
         &self.window_origin_x
     }
 
+    /// Set the horizontal coordinate of the console window origin
+    pub fn set_window_origin_x(&mut self, window_origin_x: i16) {
+        self.window_origin_x = window_origin_x;
+    }
+
     /// A 16-bit, signed integer that specifies the vertical coordinate (Y axis),
     /// in pixels, of the console window origin.
     pub fn window_origin_y(&self) -> &i16 {
         &self.window_origin_y
     }
 
+    /// Set the vertical coordinate of the console window origin
+    pub fn set_window_origin_y(&mut self, window_origin_y: i16) {
+        self.window_origin_y = window_origin_y;
+    }
+
     /// A 32-bit, unsigned integer that specifies the size, in pixels, of the
     /// font used in the console window. The two most significant bytes contain
     /// the font height and the two least significant bytes contain the font
@@ -201,6 +249,11 @@ impl ConsoleDataBlock {
         &self.font_size
     }
 
+    /// Set the font size
+    pub fn set_font_size(&mut self, font_size: u32) {
+        self.font_size = font_size;
+    }
+
     /// A 32-bit, unsigned integer that specifies the family of the font used
     /// in the console window. This value MUST be comprised of a font family
     /// and an optional font pitch.
@@ -208,30 +261,56 @@ impl ConsoleDataBlock {
         &self.font_family
     }
 
+    /// Set the font family
+    pub fn set_font_family(&mut self, font_family: FontFamilyFlags) {
+        self.font_family = font_family;
+    }
+
     /// A 32-bit, unsigned integer that specifies the stroke weight of the font
     /// used in the console window.
     pub fn font_weight(&self) -> &u32 {
         &self.font_weight
     }
 
+    /// Set the font weight
+    pub fn set_font_weight(&mut self, font_weight: u32) {
+        self.font_weight = font_weight;
+    }
+
     /// A 32-character Unicode string that specifies the face name of the font
     /// used in the console window.
     pub fn face_name(&self) -> &String {
         &self.face_name
     }
 
+    /// Set the face name
+    /// The face name is stored in 32 characters, including a NULL terminator.
+    pub fn set_face_name(&mut self, face_name: String) {
+        self.face_name = face_name;
+    }
+
     /// A 32-bit, unsigned integer that specifies the size of the cursor, in
     /// pixels, used in the console window.
     pub fn cursor_size(&self) -> &u32 {
         &self.cursor_size
     }
 
+    /// Set the cursor size
+    pub fn set_cursor_size(&mut self, cursor_size: u32) {
+        self.cursor_size = cursor_size;
+    }
+
     /// A 32-bit, unsigned integer that specifies whether to open the console
     /// window in full-screen mode.
     pub fn full_screen(&self) -> &bool {
         &self.full_screen
     }
 
+    /// Set the full screen
+    pub fn set_full_screen(&mut self, full_screen: bool) {
+        self.full_screen = full_screen;
+    }
+
     /// A 32-bit, unsigned integer that specifies whether to open the console
     /// window in QuikEdit mode. In QuickEdit mode, the mouse can be used to
     /// cut, copy, and paste text in the console window.
@@ -239,18 +318,33 @@ impl ConsoleDataBlock {
         &self.quick_edit
     }
 
+    /// Set the quick edit
+    pub fn set_quick_edit(&mut self, quick_edit: bool) {
+        self.quick_edit = quick_edit;
+    }
+
     /// A 32-bit, unsigned integer that specifies insert mode in the console
     /// window.
     pub fn insert_mode(&self) -> &bool {
         &self.insert_mode
     }
 
+    /// Set the insert mode
+    pub fn set_insert_mode(&mut self, insert_mode: bool) {
+        self.insert_mode = insert_mode;
+    }
+
     /// A 32-bit, unsigned integer that specifies auto-position mode of the
     /// console window.
     pub fn auto_position(&self) -> &bool {
         &self.auto_position
     }
 
+    /// Set the auto position
+    pub fn set_auto_position(&mut self, auto_position: bool) {
+        self.auto_position = auto_position;
+    }
+
     /// A 32-bit, unsigned integer that specifies the size, in characters, of
     /// the buffer that is used to store a history of user input into the
     /// console window.
@@ -258,18 +352,33 @@ impl ConsoleDataBlock {
         &self.history_buffer_size
     }
 
+    /// Set the history buffer size
+    pub fn set_history_buffer_size(&mut self, history_buffer_size: u32) {
+        self.history_buffer_size = history_buffer_size;
+    }
+
     /// A 32-bit, unsigned integer that specifies the number of history
     /// buffers to use.
     pub fn number_of_history_buffers(&self) -> &u32 {
         &self.number_of_history_buffers
     }
 
+    /// Set the number of history buffers
+    pub fn set_number_of_history_buffers(&mut self, number_of_history_buffers: u32) {
+        self.number_of_history_buffers = number_of_history_buffers;
+    }
+
     /// A 32-bit, unsigned integer that specifies whether to remove duplicates
     /// in the history buffer.
     pub fn history_no_dup(&self) -> &bool {
         &self.history_no_dup
     }
 
+    /// Set the history no dup
+    pub fn set_history_no_dup(&mut self, history_no_dup: bool) {
+        self.history_no_dup = history_no_dup;
+    }
+
     /// A table of 16 32-bit, unsigned integers specifying the RGB colors that
     /// are used for text in the console window. The values of the fill
     /// attribute fields FillAttributes and PopupFillAttributes are used as
@@ -278,10 +387,59 @@ impl ConsoleDataBlock {
     pub fn color_table(&self) -> &[u32; 16] {
         &self.color_table
     }
+
+    /// Set the color table
+    pub fn set_color_table(&mut self, color_table: [u32; 16]) {
+        self.color_table = color_table;
+    }
 }
 
-impl From<&[u8]> for ConsoleDataBlock {
-    fn from(data: &[u8]) -> Self {
+impl Default for ConsoleDataBlock {
+    /// The settings of a new console window on Windows 10: grey text on
+    /// black in 16-pixel Consolas, in a window of 120 by 30 characters, with
+    /// the classic colour table.
+    fn default() -> Self {
+        Self {
+            fill_attributes: FillAttributeFlags::FOREGROUND_RED
+                | FillAttributeFlags::FOREGROUND_GREEN
+                | FillAttributeFlags::FOREGROUND_BLUE,
+            popup_fill_attributes: FillAttributeFlags::FOREGROUND_RED
+                | FillAttributeFlags::FOREGROUND_BLUE
+                | FillAttributeFlags::BACKGROUND_RED
+                | FillAttributeFlags::BACKGROUND_GREEN
+                | FillAttributeFlags::BACKGROUND_BLUE
+                | FillAttributeFlags::BACKGROUND_INTENSITY,
+            screen_buffer_size_x: 120,
+            screen_buffer_size_y: 9001,
+            window_size_x: 120,
+            window_size_y: 30,
+            window_origin_x: 0,
+            window_origin_y: 0,
+            font_size: 16 << 16,
+            font_family: FontFamilyFlags::FF_MODERN | FontFamilyFlags::TMPF_TRUETYPE,
+            font_weight: 400,
+            face_name: "Consolas".to_string(),
+            cursor_size: 25,
+            full_screen: false,
+            quick_edit: true,
+            insert_mode: true,
+            auto_position: true,
+            history_buffer_size: 50,
+            number_of_history_buffers: 4,
+            history_no_dup: false,
+            color_table: [
+                0x000000, 0x800000, 0x008000, 0x808000, 0x000080, 0x800080, 0x008080, 0xc0c0c0,
+                0x808080, 0xff0000, 0x00ff00, 0xffff00, 0x0000ff, 0xff00ff, 0x00ffff, 0xffffff,
+            ],
+        }
+    }
+}
+
+impl ConsoleDataBlock {
+    /// Parse a ConsoleDataBlock, without its BlockSize and
+    /// BlockSignature, with the given parsing context.
+    pub(crate) fn parse(data: &[u8], ctx: &ParseContext) -> Result<Self, crate::Error> {
+        ctx.need(data, 0, 196)?;
         let fill_attributes = FillAttributeFlags::from_bits_truncate(LE::read_u16(data));
         let popup_fill_attributes =
             FillAttributeFlags::from_bits_truncate(LE::read_u16(&data[2..]));
@@ -295,10 +453,7 @@ impl From<&[u8]> for ConsoleDataBlock {
         let font_family = FontFamilyFlags::from_bits_truncate(LE::read_u32(&data[28..]));
         let font_weight = LE::read_u32(&data[32..]);
 
-        let mut string_data = [0u16; 32];
-        LE::read_u16_into(&data[36..100], &mut string_data);
-        let face_name =
-            strings::trim_nul_terminated_string(String::from_utf16_lossy(&string_data).to_string());
+        let face_name = strings::decode_fixed_unicode(&data[36..100]);
         let cursor_size = LE::read_u32(&data[100..]);
         let full_screen = LE::read_u32(&data[104..]) != 0;
         let quick_edit = LE::read_u32(&data[108..]) != 0;
@@ -311,7 +466,7 @@ impl From<&[u8]> for ConsoleDataBlock {
         for idx in 0..16 {
             color_table[idx] = LE::read_u32(&data[(132 + idx * 4)..]);
         }
-        Self {
+        Ok(Self {
             fill_attributes,
             popup_fill_attributes,
             screen_buffer_size_x,
@@ -333,6 +488,14 @@ impl From<&[u8]> for ConsoleDataBlock {
             number_of_history_buffers,
             history_no_dup,
             color_table,
-        }
+        })
+    }
+}
+
+impl TryFrom<&[u8]> for ConsoleDataBlock {
+    type Error = crate::Error;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Self::parse(data, &ParseContext::default())
     }
 }
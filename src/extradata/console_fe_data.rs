@@ -1,9 +1,16 @@
 use byteorder::{ByteOrder, LE};
 
+use std::convert::TryFrom;
+
+use crate::ParseContext;
+
 /// The ConsoleFEDataBlock structure specifies the code page to use
 /// for displaying text when a link target specifies an application
 /// that is run in a console window.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ConsoleFEDataBlock {
     /// A 32-bit, unsigned integer that specifies a code page language
     /// code identifier. For details concerning the structure and
@@ -12,6 +19,12 @@ pub struct ConsoleFEDataBlock {
 }
 
 impl ConsoleFEDataBlock {
+    /// Create a ConsoleFEDataBlock for the code page language code
+    /// identifier `code_page`.
+    pub fn new(code_page: u32) -> Self {
+        Self { code_page }
+    }
+
     /// A 32-bit, unsigned integer that specifies a code page language
     /// code identifier. For details concerning the structure and
     /// meaning of language code identifiers, see [MS-LCID].
@@ -20,9 +33,20 @@ impl ConsoleFEDataBlock {
     }
 }
 
-impl From<&[u8]> for ConsoleFEDataBlock {
-    fn from(data: &[u8]) -> Self {
+impl ConsoleFEDataBlock {
+    /// Parse a ConsoleFEDataBlock, without its BlockSize and
+    /// BlockSignature, with the given parsing context.
+    pub(crate) fn parse(data: &[u8], ctx: &ParseContext) -> Result<Self, crate::Error> {
+        ctx.need(data, 0, 4)?;
         let code_page = LE::read_u32(data);
-        Self { code_page }
+        Ok(Self { code_page })
+    }
+}
+
+impl TryFrom<&[u8]> for ConsoleFEDataBlock {
+    type Error = crate::Error;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Self::parse(data, &ParseContext::default())
     }
 }
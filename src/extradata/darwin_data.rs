@@ -1,20 +1,41 @@
-use crate::strings;
+use std::convert::TryFrom;
+
+use crate::{strings, ParseContext};
 
 /// The DarwinDataBlock structure specifies an application identifier
 /// that can be used instead of a link target IDList to install an
 /// application when a shell link is activated.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct DarwinDataBlock {
     /// A NULL–terminated string, defined by the system default code
     /// page, which specifies an application identifier. This field
     /// SHOULD be ignored.
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::fuzz::ansi_string::<259>))]
     darwin_data_ansi: String,
     /// An optional, NULL–terminated, Unicode string that specifies
     /// an application identifier.
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::fuzz::optional_fixed_unicode_string::<259>))]
     darwin_data_unicode: Option<String>,
 }
 
 impl DarwinDataBlock {
+    /// Create a DarwinDataBlock for the application identifier
+    /// `darwin_data`, stored both in the system default code page and as
+    /// Unicode, or only as Unicode if it cannot be written in Windows-1252.
+    /// Fails with [`Error::FieldTooLong`](crate::Error::FieldTooLong) if it
+    /// is longer than the 259 characters the block holds.
+    pub fn new(darwin_data: impl Into<String>) -> Result<Self, crate::Error> {
+        let (darwin_data_ansi, darwin_data_unicode) =
+            strings::fixed_path("DarwinDataBlock", "DarwinDataUnicode", darwin_data.into())?;
+        Ok(Self {
+            darwin_data_ansi,
+            darwin_data_unicode,
+        })
+    }
+
     /// A NULL–terminated string, defined by the system default code
     /// page, which specifies an application identifier. This field
     /// SHOULD be ignored.
@@ -29,20 +50,29 @@ impl DarwinDataBlock {
     }
 }
 
-impl From<&[u8]> for DarwinDataBlock {
-    fn from(data: &[u8]) -> Self {
-        let darwin_data_ansi =
-            strings::trim_nul_terminated_string(String::from_utf8_lossy(&data[0..260]));
-        let darwin_data_unicode_raw =
-            strings::trim_nul_terminated_string(String::from_utf8_lossy(&data[260..520]));
-        let darwin_data_unicode = if darwin_data_unicode_raw.len() == 0 {
+impl DarwinDataBlock {
+    /// Parse a DarwinDataBlock, without its BlockSize and
+    /// BlockSignature, with the given parsing context.
+    pub(crate) fn parse(data: &[u8], ctx: &ParseContext) -> Result<Self, crate::Error> {
+        ctx.need(data, 0, 780)?;
+        let darwin_data_ansi = strings::decode_fixed(&data[0..260], ctx);
+        let darwin_data_unicode_raw = strings::decode_fixed_unicode(&data[260..780]);
+        let darwin_data_unicode = if darwin_data_unicode_raw.is_empty() {
             None
         } else {
             Some(darwin_data_unicode_raw)
         };
-        Self {
+        Ok(Self {
             darwin_data_ansi,
             darwin_data_unicode,
-        }
+        })
+    }
+}
+
+impl TryFrom<&[u8]> for DarwinDataBlock {
+    type Error = crate::Error;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Self::parse(data, &ParseContext::default())
     }
 }
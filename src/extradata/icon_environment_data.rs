@@ -1,21 +1,42 @@
-use crate::strings;
+use std::convert::TryFrom;
+
+use crate::{strings, ParseContext};
 
 /// The IconEnvironmentDataBlock structure specifies the path to an
 /// icon. The path is encoded using environment variables, which makes
 /// it possible to find the icon across machines where the locations
 /// vary but are expressed using environment variables.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct IconEnvironmentDataBlock {
     /// A NULL-terminated string, defined by the system default code
     /// page, which specifies a path that is constructed with
     /// environment variables.
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::fuzz::ansi_string::<259>))]
     target_ansi: String,
     /// An optional, NULL-terminated, Unicode string that specifies a
     /// path that is constructed with environment variables.
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::fuzz::optional_fixed_unicode_string::<259>))]
     target_unicode: Option<String>,
 }
 
 impl IconEnvironmentDataBlock {
+    /// Create an IconEnvironmentDataBlock for the icon path `target`, stored
+    /// both in the system default code page and as Unicode, or only as
+    /// Unicode if it cannot be written in Windows-1252. Fails with
+    /// [`Error::FieldTooLong`](crate::Error::FieldTooLong) if the path is
+    /// longer than the 259 characters the block holds.
+    pub fn new(target: impl Into<String>) -> Result<Self, crate::Error> {
+        let (target_ansi, target_unicode) =
+            strings::fixed_path("IconEnvironmentDataBlock", "TargetUnicode", target.into())?;
+        Ok(Self {
+            target_ansi,
+            target_unicode,
+        })
+    }
+
     /// A NULL-terminated string, defined by the system default code
     /// page, which specifies a path that is constructed with
     /// environment variables.
@@ -30,20 +51,39 @@ impl IconEnvironmentDataBlock {
     }
 }
 
-impl From<&[u8]> for IconEnvironmentDataBlock {
-    fn from(data: &[u8]) -> Self {
-        let target_ansi =
-            strings::trim_nul_terminated_string(String::from_utf8_lossy(&data[0..260]));
-        let target_unicode_raw =
-            strings::trim_nul_terminated_string(String::from_utf8_lossy(&data[260..520]));
-        let target_unicode = if target_unicode_raw.len() == 0 {
+impl IconEnvironmentDataBlock {
+    /// Parse an IconEnvironmentDataBlock, without its BlockSize and
+    /// BlockSignature, with the given parsing context.
+    pub(crate) fn parse(data: &[u8], ctx: &ParseContext) -> Result<Self, crate::Error> {
+        ctx.need(data, 0, 780)?;
+        let target_ansi = strings::decode_fixed(&data[0..260], ctx);
+        let target_unicode_raw = strings::decode_fixed_unicode(&data[260..780]);
+        let target_unicode = if target_unicode_raw.is_empty() {
             None
         } else {
             Some(target_unicode_raw)
         };
-        Self {
+        Ok(Self {
             target_ansi,
             target_unicode,
+        })
+    }
+}
+
+impl IconEnvironmentDataBlock {
+    /// Remove identifying details from the paths.
+    pub(crate) fn redact(&mut self, redactor: &crate::redact::Redactor) {
+        redactor.string(&mut self.target_ansi);
+        if let Some(target_unicode) = &mut self.target_unicode {
+            redactor.string(target_unicode);
         }
     }
 }
+
+impl TryFrom<&[u8]> for IconEnvironmentDataBlock {
+    type Error = crate::Error;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Self::parse(data, &ParseContext::default())
+    }
+}
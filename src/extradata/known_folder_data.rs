@@ -1,14 +1,21 @@
 use byteorder::{ByteOrder, LE};
 
+use std::convert::TryFrom;
+
+use crate::{Guid, ParseContext};
+
 /// The KnownFolderDataBlock structure specifies the location of a
 /// known folder. This data can be used when a link target is a
 /// known folder to keep track of the folder so that the link target
 /// IDList can be translated when the link is loaded.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct KnownFolderDataBlock {
     /// A value in GUID packet representation ([MS-DTYP] section
     /// 2.3.4.2) that specifies the folder GUID ID.
-    known_folder_id: u128,
+    known_folder_id: Guid,
     /// A 32-bit, unsigned integer that specifies the location
     /// of the ItemID of the first child segment of the IDList specified
     /// by KnownFolderID. This value is the offset, in bytes, into the
@@ -17,9 +24,18 @@ pub struct KnownFolderDataBlock {
 }
 
 impl KnownFolderDataBlock {
+    /// Create a KnownFolderDataBlock for the known folder `known_folder_id`,
+    /// whose first child item is `offset` bytes into the link target IDList.
+    pub fn new(known_folder_id: Guid, offset: u32) -> Self {
+        Self {
+            known_folder_id,
+            offset,
+        }
+    }
+
     /// A value in GUID packet representation ([MS-DTYP] section
     /// 2.3.4.2) that specifies the folder GUID ID.
-    pub fn known_folder_id(&self) -> u128 {
+    pub fn known_folder_id(&self) -> Guid {
         self.known_folder_id
     }
 
@@ -32,13 +48,24 @@ impl KnownFolderDataBlock {
     }
 }
 
-impl From<&[u8]> for KnownFolderDataBlock {
-    fn from(data: &[u8]) -> Self {
-        let known_folder_id = LE::read_u128(data);
+impl KnownFolderDataBlock {
+    /// Parse a KnownFolderDataBlock, without its BlockSize and
+    /// BlockSignature, with the given parsing context.
+    pub(crate) fn parse(data: &[u8], ctx: &ParseContext) -> Result<Self, crate::Error> {
+        ctx.need(data, 0, 20)?;
+        let known_folder_id = Guid::read(data);
         let offset = LE::read_u32(&data[16..]);
-        Self {
+        Ok(Self {
             known_folder_id,
             offset,
-        }
+        })
+    }
+}
+
+impl TryFrom<&[u8]> for KnownFolderDataBlock {
+    type Error = crate::Error;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Self::parse(data, &ParseContext::default())
     }
 }
@@ -1,6 +1,10 @@
-use byteorder::{ByteOrder, LE};
 #[allow(unused)]
-use log::{debug, error, info, trace, warn};
+use crate::logging::{debug, error, info, trace, warn};
+use byteorder::{ByteOrder, LE};
+
+use std::convert::TryFrom;
+
+use crate::ParseContext;
 
 use self::{
     console_data::ConsoleDataBlock, console_fe_data::ConsoleFEDataBlock,
@@ -76,6 +80,9 @@ pub mod vista_and_above_id_list_data;
 /// At the moment, ExtraData can only be read, not written to shortcuts.
 #[allow(missing_docs)]
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ExtraData {
     ConsoleProps(ConsoleDataBlock),
     ConsoleFeProps(ConsoleFEDataBlock),
@@ -90,26 +97,127 @@ pub enum ExtraData {
     VistaAndAboveIdListProps(VistaAndAboveIdListDataBlock),
 }
 
-impl From<&[u8]> for ExtraData {
-    fn from(data: &[u8]) -> Self {
+impl ExtraData {
+    /// Parse an ExtraData block, returning an error if its size does not fit
+    /// the data, its signature is not known, or it is too short to hold the
+    /// block its signature names.
+    pub(crate) fn parse(data: &[u8], ctx: &ParseContext) -> Result<Self, crate::Error> {
+        ctx.need(data, 0, 8)?;
         let size = LE::read_u32(data) as usize;
         let sig = LE::read_u32(&data[4..]);
+        if size < 8 {
+            return Err(ctx.unexpected_value(0, "BlockSize", "at least 8", size));
+        }
+        ctx.check_size(0, size)?;
+        ctx.need(data, 0, size)?;
         debug!("Signature {:x}", sig);
+        if let Some(expected) = fixed_size(sig) {
+            if size > expected {
+                ctx.nonconformance(
+                    0,
+                    format!(
+                        "The block is 0x{:x} bytes, but should be 0x{:x}. The rest is skipped.",
+                        size, expected
+                    ),
+                )?;
+            }
+        }
+        // Each block is read from its declared size only, and the next one
+        // starts straight after it, however much of it was read.
         let data = &data[8..size];
 
         match sig {
-            0xa0000002 => Self::ConsoleProps(ConsoleDataBlock::from(data)),
-            0xa0000004 => Self::ConsoleFeProps(ConsoleFEDataBlock::from(data)),
-            0xa0000006 => Self::DarwinProps(DarwinDataBlock::from(data)),
-            0xa0000001 => Self::EnvironmentProps(EnvironmentVariableDataBlock::from(data)),
-            0xa0000007 => Self::IconEnvironmentProps(IconEnvironmentDataBlock::from(data)),
-            0xa000000b => Self::KnownFolderProps(KnownFolderDataBlock::from(data)),
-            0xa0000009 => Self::PropertyStoreProps(PropertyStoreDataBlock::from(data)),
-            0xa0000008 => Self::ShimProps(ShimDataBlock::from(data)),
-            0xa0000005 => Self::SpecialFolderProps(SpecialFolderDataBlock::from(data)),
-            0xa0000003 => Self::TrackerProps(TrackerDataBlock::from(data)),
-            0xa000000a => Self::VistaAndAboveIdListProps(VistaAndAboveIdListDataBlock::from(data)),
-            _ => panic!("Invalid extra data type!"),
+            0xa0000002 => ctx.within("ConsoleDataBlock", 8, || {
+                ConsoleDataBlock::parse(data, ctx).map(Self::ConsoleProps)
+            }),
+            0xa0000004 => ctx.within("ConsoleFEDataBlock", 8, || {
+                ConsoleFEDataBlock::parse(data, ctx).map(Self::ConsoleFeProps)
+            }),
+            0xa0000006 => ctx.within("DarwinDataBlock", 8, || {
+                DarwinDataBlock::parse(data, ctx).map(Self::DarwinProps)
+            }),
+            0xa0000001 => ctx.within("EnvironmentVariableDataBlock", 8, || {
+                EnvironmentVariableDataBlock::parse(data, ctx).map(Self::EnvironmentProps)
+            }),
+            0xa0000007 => ctx.within("IconEnvironmentDataBlock", 8, || {
+                IconEnvironmentDataBlock::parse(data, ctx).map(Self::IconEnvironmentProps)
+            }),
+            0xa000000b => ctx.within("KnownFolderDataBlock", 8, || {
+                KnownFolderDataBlock::parse(data, ctx).map(Self::KnownFolderProps)
+            }),
+            0xa0000009 => Ok(Self::PropertyStoreProps(PropertyStoreDataBlock::from(data))),
+            0xa0000008 => Ok(Self::ShimProps(ShimDataBlock::from(data))),
+            0xa0000005 => ctx.within("SpecialFolderDataBlock", 8, || {
+                SpecialFolderDataBlock::parse(data, ctx).map(Self::SpecialFolderProps)
+            }),
+            0xa0000003 => ctx.within("TrackerDataBlock", 8, || {
+                TrackerDataBlock::parse(data, ctx).map(Self::TrackerProps)
+            }),
+            0xa000000a => ctx.within("VistaAndAboveIDListDataBlock", 8, || {
+                VistaAndAboveIdListDataBlock::parse(data, ctx).map(Self::VistaAndAboveIdListProps)
+            }),
+            _ => Err(ctx.unexpected_value(
+                4,
+                "BlockSignature",
+                "0xa0000001 to 0xa000000b",
+                format_args!("0x{:x}", sig),
+            )),
+        }
+    }
+}
+
+impl ExtraData {
+    /// Remove identifying details from the block.
+    pub(crate) fn redact(&mut self, redactor: &crate::redact::Redactor) {
+        match self {
+            Self::EnvironmentProps(block) => block.redact(redactor),
+            Self::IconEnvironmentProps(block) => block.redact(redactor),
+            Self::PropertyStoreProps(block) => block.redact(redactor),
+            Self::TrackerProps(block) => block.redact(redactor),
+            Self::VistaAndAboveIdListProps(block) => block.redact(redactor),
+            _ => {}
         }
     }
 }
+
+/// The size, including BlockSize and BlockSignature, the specification gives
+/// the block with signature `sig`, if it has a fixed size. The
+/// TrackerDataBlock declares its own Length, so it checks its size itself.
+fn fixed_size(sig: u32) -> Option<usize> {
+    match sig {
+        0xa0000002 => Some(0xcc),
+        0xa0000004 => Some(0xc),
+        0xa0000006 | 0xa0000001 | 0xa0000007 => Some(0x314),
+        0xa000000b => Some(0x1c),
+        0xa0000005 => Some(0x10),
+        _ => None,
+    }
+}
+
+/// The name the specification gives the block with signature `sig`.
+pub(crate) fn block_name(sig: u32) -> Option<&'static str> {
+    match sig {
+        0xa0000001 => Some("EnvironmentVariableDataBlock"),
+        0xa0000002 => Some("ConsoleDataBlock"),
+        0xa0000003 => Some("TrackerDataBlock"),
+        0xa0000004 => Some("ConsoleFEDataBlock"),
+        0xa0000005 => Some("SpecialFolderDataBlock"),
+        0xa0000006 => Some("DarwinDataBlock"),
+        0xa0000007 => Some("IconEnvironmentDataBlock"),
+        0xa0000008 => Some("ShimDataBlock"),
+        0xa0000009 => Some("PropertyStoreDataBlock"),
+        0xa000000a => Some("VistaAndAboveIDListDataBlock"),
+        0xa000000b => Some("KnownFolderDataBlock"),
+        _ => None,
+    }
+}
+
+impl TryFrom<&[u8]> for ExtraData {
+    type Error = crate::Error;
+
+    /// Read an ExtraData block, including its BlockSize and BlockSignature,
+    /// from the start of `data`.
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Self::parse(data, &ParseContext::default())
+    }
+}
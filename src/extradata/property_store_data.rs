@@ -1,19 +1,121 @@
 use std::fmt;
 
+use byteorder::{ByteOrder, LE};
+
+use crate::{strings, FileTime, Guid};
+
+/// The Version of a serialized property storage, `1SPS`.
+const STORAGE_VERSION: u32 = 0x5350_5331;
+
+/// The FormatID of property storages whose properties are named by strings
+/// rather than integers.
+const STRING_NAMED_FORMAT_ID: Guid = crate::guids::FMTID_USER_DEFINED_PROPERTIES;
+
+/// The property type of a FILETIME value ([MS-OLEPS] section 2.15).
+const VT_FILETIME: u16 = 0x0040;
+
+/// The property type of a NULL-terminated Unicode string value.
+const VT_LPWSTR: u16 = 0x001f;
+
 /// A PropertyStoreDataBlock structure specifies a set of properties
 /// that can be used by applications to store extra data in the
 /// shell link.
-#[derive(Clone)]
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct PropertyStoreDataBlock {
     /// A serialized property storage structure ([MS-PROPSTORE] section 2.2).
     property_store: Vec<u8>,
 }
 
 impl PropertyStoreDataBlock {
+    /// Create a PropertyStoreDataBlock holding the serialized property
+    /// storage `property_store`.
+    pub fn new(property_store: Vec<u8>) -> Self {
+        Self { property_store }
+    }
+
     /// A serialized property storage structure ([MS-PROPSTORE] section 2.2).
     pub fn property_store(&self) -> &Vec<u8> {
         &self.property_store
     }
+
+    /// Remove identifying details from the property store, keeping its size.
+    pub(crate) fn redact(&mut self, redactor: &crate::redact::Redactor) {
+        redactor.bytes(&mut self.property_store);
+    }
+
+    /// The FILETIME values of the integer-named properties in the property
+    /// store, each with the FormatID and property ID naming it, in the order
+    /// they are stored. Reading stops at the first structure that does not
+    /// fit.
+    pub fn filetimes(&self) -> Vec<(Guid, u32, FileTime)> {
+        values(&self.property_store)
+            .into_iter()
+            .filter(|(_, _, value)| value.len() >= 12 && LE::read_u16(value) == VT_FILETIME)
+            .map(|(format_id, id, value)| {
+                (format_id, id, FileTime::from_raw(LE::read_u64(&value[4..])))
+            })
+            .collect()
+    }
+
+    /// The string values of the integer-named properties in the property
+    /// store, each with the FormatID and property ID naming it, in the order
+    /// they are stored. Reading stops at the first structure that does not
+    /// fit.
+    pub fn strings(&self) -> Vec<(Guid, u32, String)> {
+        strings(&self.property_store)
+    }
+}
+
+/// The typed values of the integer-named properties in a serialized
+/// property store, each starting with its property type, with the FormatID
+/// and property ID naming it. Reading stops at the first structure that
+/// does not fit.
+fn values(mut rest: &[u8]) -> Vec<(Guid, u32, &[u8])> {
+    let mut values = Vec::new();
+    while rest.len() >= 24 {
+        let size = LE::read_u32(rest) as usize;
+        if size < 24 || size > rest.len() || LE::read_u32(&rest[4..]) != STORAGE_VERSION {
+            break;
+        }
+        let format_id = Guid::read(&rest[8..]);
+        let mut storage = &rest[24..size];
+        rest = &rest[size..];
+        if format_id == STRING_NAMED_FORMAT_ID {
+            continue;
+        }
+        while storage.len() >= 9 {
+            let value_size = LE::read_u32(storage) as usize;
+            if value_size < 9 || value_size > storage.len() {
+                break;
+            }
+            values.push((
+                format_id,
+                LE::read_u32(&storage[4..]),
+                &storage[9..value_size],
+            ));
+            storage = &storage[value_size..];
+        }
+    }
+    values
+}
+
+/// The string values of the integer-named properties in a serialized
+/// property store, such as one held by a shell item.
+pub(crate) fn strings(store: &[u8]) -> Vec<(Guid, u32, String)> {
+    values(store)
+        .into_iter()
+        .filter_map(|(format_id, id, value)| {
+            if value.len() < 8 || LE::read_u16(value) != VT_LPWSTR {
+                return None;
+            }
+            let length = (LE::read_u32(&value[4..]) as usize).checked_mul(2)?;
+            let characters = value.get(8..8 + length)?;
+            Some((format_id, id, strings::decode_fixed_unicode(characters)))
+        })
+        .collect()
 }
 
 impl fmt::Debug for PropertyStoreDataBlock {
@@ -1,15 +1,28 @@
+use crate::strings;
+
 /// The ShimDataBlock structure specifies the name of a shim that can
 /// be applied when activating a link target.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ShimDataBlock {
     /// A Unicode string that specifies the name of a shim layer to apply
     /// to a link target when it is being activated.
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::fuzz::unicode_string::<{ crate::fuzz::STRING_DATA_MAX }>))]
     layer_name: String,
 }
 
 impl ShimDataBlock {
+    /// Create a ShimDataBlock for the shim layer `layer_name`.
+    pub fn new(layer_name: impl Into<String>) -> Self {
+        Self {
+            layer_name: layer_name.into(),
+        }
+    }
+
     /// A Unicode string that specifies the name of a shim layer to apply
-    /// to a link target when it is being activated.s
+    /// to a link target when it is being activated.
     pub fn layer_name(&self) -> &String {
         &self.layer_name
     }
@@ -17,7 +30,7 @@ impl ShimDataBlock {
 
 impl From<&[u8]> for ShimDataBlock {
     fn from(value: &[u8]) -> Self {
-        let layer_name = String::from_utf8_lossy(value).to_string();
+        let layer_name = strings::decode_fixed_unicode(value);
         Self { layer_name }
     }
 }
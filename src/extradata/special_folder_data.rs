@@ -1,10 +1,17 @@
 use byteorder::{ByteOrder, LE};
 
+use std::convert::TryFrom;
+
+use crate::ParseContext;
+
 /// The SpecialFolderDataBlock structure specifies the location of a
 /// special folder. This data can be used when a link target is a
 /// special folder to keep track of the folder, so that the link target
 /// IDList can be translated when the link is loaded.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SpecialFolderDataBlock {
     /// A 32-bit, unsigned integer that specifies the folder integer ID.
     special_folder_id: u32,
@@ -16,6 +23,16 @@ pub struct SpecialFolderDataBlock {
 }
 
 impl SpecialFolderDataBlock {
+    /// Create a SpecialFolderDataBlock for the folder `special_folder_id`, a
+    /// CSIDL, whose first child item is `offset` bytes into the link target
+    /// IDList.
+    pub fn new(special_folder_id: u32, offset: u32) -> Self {
+        Self {
+            special_folder_id,
+            offset,
+        }
+    }
+
     /// A 32-bit, unsigned integer that specifies the folder integer ID.
     pub fn special_folder_id(&self) -> u32 {
         self.special_folder_id
@@ -30,13 +47,24 @@ impl SpecialFolderDataBlock {
     }
 }
 
-impl From<&[u8]> for SpecialFolderDataBlock {
-    fn from(data: &[u8]) -> Self {
+impl SpecialFolderDataBlock {
+    /// Parse a SpecialFolderDataBlock, without its BlockSize and
+    /// BlockSignature, with the given parsing context.
+    pub(crate) fn parse(data: &[u8], ctx: &ParseContext) -> Result<Self, crate::Error> {
+        ctx.need(data, 0, 8)?;
         let special_folder_id = LE::read_u32(data);
         let offset = LE::read_u32(&data[4..]);
-        Self {
+        Ok(Self {
             special_folder_id,
             offset,
-        }
+        })
+    }
+}
+
+impl TryFrom<&[u8]> for SpecialFolderDataBlock {
+    type Error = crate::Error;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Self::parse(data, &ParseContext::default())
     }
 }
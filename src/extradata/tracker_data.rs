@@ -1,56 +1,315 @@
+use std::convert::TryFrom;
+use std::fmt;
+
 use byteorder::{ByteOrder, LE};
 
-use crate::strings;
+use crate::strings::decode_fixed;
+use crate::{FileTime, Guid, ParseContext};
+
+/// The Length the specification gives a TrackerDataBlock, which leaves 16
+/// bytes for the MachineID.
+const LENGTH: usize = 0x58;
+
+/// The bytes of a TrackerDataBlock, after BlockSize and BlockSignature,
+/// that are not the MachineID.
+const FIXED_LENGTH: usize = 0x48;
+
+/// The longest NetBIOS name, in characters of the code page.
+const NETBIOS_NAME_LENGTH: usize = 15;
+
+/// Characters that are not allowed in NetBIOS names.
+const NETBIOS_INVALID_CHARACTERS: &str = "\\/:*?\"<>|";
 
 /// The TrackerDataBlock structure specifies data that can be used to
 /// resolve a link target if it is not found in its original location
 /// when the link is resolved. This data is passed to the Link
 /// Tracking service [MS-DLTW] to find the link target.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct TrackerDataBlock {
     /// A NULL–terminated character string, as defined by the system default
     /// code page, which specifies the NetBIOS name of the machine where
     /// the link target was last known to reside.
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::fuzz::ansi_string::<15>))]
     machine_id: String,
+    /// The MachineID as stored, including its terminator and the bytes
+    /// after it.
+    machine_id_raw: Vec<u8>,
     /// Two values in GUID packet representation ([MS-DTYP] section 2.3.4.2)
     /// that are used to find the link target with the Link Tracking service,
     /// as described in [MS-DLTW].
-    droid: [u128; 2],
+    droid: [Guid; 2],
     /// Two values in GUID packet representation that are used to find the
     /// link target with the Link Tracking service
-    droid_birth: [u128; 2],
+    droid_birth: [Guid; 2],
 }
 
 impl TrackerDataBlock {
-    /// Get the machine ID
+    /// Create a TrackerDataBlock for the machine with the NetBIOS name
+    /// `machine_id`, with the droids and birth droids of the link target.
+    /// The name is stored in Windows-1252.
+    ///
+    /// A name longer than the 15 bytes that fit in the MachineID is an
+    /// error, as is one that is not a NetBIOS name or cannot be written in
+    /// Windows-1252, rather than a different name being stored.
+    pub fn new(
+        machine_id: &str,
+        droid: [Guid; 2],
+        droid_birth: [Guid; 2],
+    ) -> Result<Self, crate::Error> {
+        let unexpected = || crate::Error::UnexpectedValue {
+            structure: "TrackerDataBlock",
+            offset: 8,
+            field: "MachineID",
+            expected: "a NetBIOS name in Windows-1252".to_string(),
+            found: format!("{:?}", machine_id),
+        };
+        let (encoded, _, unmappable) = encoding_rs::WINDOWS_1252.encode(machine_id);
+        if unmappable {
+            return Err(unexpected());
+        }
+        if encoded.len() > NETBIOS_NAME_LENGTH {
+            return Err(crate::Error::FieldTooLong {
+                structure: "TrackerDataBlock",
+                field: "MachineID",
+                limit: NETBIOS_NAME_LENGTH,
+                length: encoded.len(),
+            });
+        }
+        if !is_netbios_name(machine_id) {
+            return Err(unexpected());
+        }
+        let mut machine_id_raw = encoded.into_owned();
+        machine_id_raw.resize(NETBIOS_NAME_LENGTH + 1, 0);
+        Ok(Self {
+            machine_id: machine_id.to_string(),
+            machine_id_raw,
+            droid,
+            droid_birth,
+        })
+    }
+
+    /// Get the machine ID, decoded with the configured code page up to its
+    /// terminator. It is usually the NetBIOS name of the machine, but may be
+    /// anything; see [`TrackerDataBlock::machine_name`].
     pub fn machine_id(&self) -> &String {
         &self.machine_id
     }
 
+    /// The MachineID as stored, including its terminator and whatever
+    /// follows it. It is 16 bytes, unless the block declares a longer
+    /// Length.
+    pub fn machine_id_raw(&self) -> &[u8] {
+        &self.machine_id_raw
+    }
+
+    /// The machine ID without the trailing spaces NetBIOS names are padded
+    /// with, if it is a valid NetBIOS name: 1 to 15 printable
+    /// characters other than `\ / : * ? " < > |`. `None` otherwise.
+    pub fn machine_name(&self) -> Option<&str> {
+        let name = self.machine_id.trim_end_matches(' ');
+        is_netbios_name(name).then_some(name)
+    }
+
     /// Get the droid GUIDs
-    pub fn droid(&self) -> &[u128; 2] {
+    pub fn droid(&self) -> &[Guid; 2] {
         &self.droid
     }
 
     /// Get the droid birth GUIDs
-    pub fn droid_birth(&self) -> &[u128; 2] {
+    pub fn droid_birth(&self) -> &[Guid; 2] {
         &self.droid_birth
     }
+
+    /// Decode the current object droid of the target, which records when
+    /// and on which machine the Link Tracking service first saw the target
+    /// at its current location. `None` if it is not a version 1 GUID.
+    pub fn droid_info(&self) -> Option<DroidInfo> {
+        DroidInfo::new(&self.droid[1])
+    }
+
+    /// Decode the birth object droid of the target, which records when and
+    /// on which machine the Link Tracking service first saw the target.
+    /// `None` if it is not a version 1 GUID.
+    pub fn droid_birth_info(&self) -> Option<DroidInfo> {
+        DroidInfo::new(&self.droid_birth[1])
+    }
+}
+
+/// The details held in an object droid, a version 1 GUID made by the Link
+/// Tracking service.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DroidInfo {
+    mac_address: MacAddress,
+    clock_sequence: u16,
+    timestamp: FileTime,
+}
+
+impl DroidInfo {
+    /// Decode an object droid. `None` if it is not a version 1 GUID, or
+    /// its time is before the FILETIME epoch.
+    pub fn new(droid: &Guid) -> Option<Self> {
+        Some(Self {
+            mac_address: MacAddress(droid.node()?),
+            clock_sequence: droid.clock_sequence()?,
+            timestamp: droid.timestamp()?,
+        })
+    }
+
+    /// The MAC address of the machine that made the droid.
+    pub fn mac_address(&self) -> MacAddress {
+        self.mac_address
+    }
+
+    /// The clock sequence, which tells apart droids made by one machine at
+    /// the same time.
+    pub fn clock_sequence(&self) -> u16 {
+        self.clock_sequence
+    }
+
+    /// When the droid was made.
+    pub fn timestamp(&self) -> FileTime {
+        self.timestamp
+    }
+}
+
+/// A MAC address, as found in the node of a version 1 GUID.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MacAddress([u8; 6]);
+
+impl MacAddress {
+    /// Create a MAC address from its six bytes.
+    pub const fn new(octets: [u8; 6]) -> Self {
+        Self(octets)
+    }
+
+    /// The six bytes of the MAC address.
+    pub const fn octets(&self) -> [u8; 6] {
+        self.0
+    }
+
+    /// Whether the multicast bit is set. A GUID node with this bit set was
+    /// chosen at random, as no network adapter has such an address, so it
+    /// does not identify the machine.
+    pub const fn is_multicast(&self) -> bool {
+        self.0[0] & 1 != 0
+    }
+}
+
+impl fmt::Display for MacAddress {
+    /// Write the MAC address in the form `00:13:72:16:87:4A`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let b = &self.0;
+        write!(
+            f,
+            "{:02X}:{:02X}:{:02X}:{:02X}:{:02X}:{:02X}",
+            b[0], b[1], b[2], b[3], b[4], b[5]
+        )
+    }
 }
 
-impl From<&[u8]> for TrackerDataBlock {
-    fn from(data: &[u8]) -> Self {
-        let machine_id =
-            strings::trim_nul_terminated_string(String::from_utf8_lossy(&data[8..]).to_string());
-        let droid_1 = LE::read_u128(&data[24..]);
-        let droid_2 = LE::read_u128(&data[40..]);
-        let droid_birth_1 = LE::read_u128(&data[56..]);
-        let droid_birth_2 = LE::read_u128(&data[72..]);
+impl fmt::Debug for MacAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
 
+impl Default for TrackerDataBlock {
+    /// A TrackerDataBlock with an empty MachineID and nil droids.
+    fn default() -> Self {
         Self {
+            machine_id: String::new(),
+            machine_id_raw: vec![0; NETBIOS_NAME_LENGTH + 1],
+            droid: [Guid::NIL; 2],
+            droid_birth: [Guid::NIL; 2],
+        }
+    }
+}
+
+impl TrackerDataBlock {
+    /// Parse a TrackerDataBlock, without its BlockSize and
+    /// BlockSignature, with the given parsing context.
+    pub(crate) fn parse(data: &[u8], ctx: &ParseContext) -> Result<Self, crate::Error> {
+        ctx.need(data, 0, LENGTH)?;
+        let length = LE::read_u32(data) as usize;
+        let mut machine_id_length = LENGTH - FIXED_LENGTH;
+        if length != LENGTH {
+            let message = if length > LENGTH && length <= data.len() {
+                machine_id_length = length - FIXED_LENGTH;
+                format!(
+                    "The Length is 0x{:x}, not 0x{:x}, which makes the MachineID {} bytes",
+                    length, LENGTH, machine_id_length
+                )
+            } else {
+                format!(
+                    "The Length is 0x{:x}, not 0x{:x}, so it is ignored",
+                    length, LENGTH
+                )
+            };
+            ctx.nonconformance(0, message)?;
+        }
+        let used = machine_id_length + FIXED_LENGTH;
+        if data.len() > used {
+            ctx.nonconformance(
+                0,
+                format!(
+                    "The block is 0x{:x} bytes, but should be 0x{:x}. The rest is skipped.",
+                    data.len() + 8,
+                    used + 8
+                ),
+            )?;
+        }
+        let machine_id_raw = data[8..8 + machine_id_length].to_vec();
+        let machine_id = decode_fixed(&machine_id_raw, ctx);
+        if !machine_id.is_empty() && !is_netbios_name(machine_id.trim_end_matches(' ')) {
+            ctx.nonconformance(
+                8,
+                format!("The MachineID {:?} is not a NetBIOS name", machine_id),
+            )?;
+        }
+        let droids = &data[8 + machine_id_length..];
+        let droid_1 = Guid::read(droids);
+        let droid_2 = Guid::read(&droids[16..]);
+        let droid_birth_1 = Guid::read(&droids[32..]);
+        let droid_birth_2 = Guid::read(&droids[48..]);
+
+        Ok(Self {
             machine_id,
+            machine_id_raw,
             droid: [droid_1, droid_2],
             droid_birth: [droid_birth_1, droid_birth_2],
+        })
+    }
+}
+
+impl TrackerDataBlock {
+    /// Blank the machine ID and droids, if chosen.
+    pub(crate) fn redact(&mut self, redactor: &crate::redact::Redactor) {
+        if redactor.options.machine_id {
+            self.machine_id.clear();
+            self.machine_id_raw.fill(0);
         }
+        if redactor.options.droids {
+            self.droid = [Guid::NIL; 2];
+            self.droid_birth = [Guid::NIL; 2];
+        }
+    }
+}
+
+/// Whether `name` is a valid NetBIOS name.
+fn is_netbios_name(name: &str) -> bool {
+    (1..=NETBIOS_NAME_LENGTH).contains(&name.chars().count())
+        && !name
+            .chars()
+            .any(|c| c.is_control() || NETBIOS_INVALID_CHARACTERS.contains(c))
+}
+
+impl TryFrom<&[u8]> for TrackerDataBlock {
+    type Error = crate::Error;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Self::parse(data, &ParseContext::default())
     }
 }
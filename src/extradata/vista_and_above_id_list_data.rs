@@ -1,43 +1,52 @@
-use byteorder::{ByteOrder, LE};
-use log::debug;
+use std::convert::TryFrom;
 
-use crate::linktarget::ItemID;
+use crate::linktarget::{self, ItemID};
+use crate::ParseContext;
 
 /// The VistaAndAboveIDListDataBlock structure specifies an alternate
 /// IDList that can be used instead of the LinkTargetIDList structure
 /// (section 2.2) on platforms that support it.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct VistaAndAboveIdListDataBlock {
     /// An IDList structure (section 2.2.1).
     id_list: Vec<ItemID>,
 }
 
 impl VistaAndAboveIdListDataBlock {
+    /// Create a VistaAndAboveIDListDataBlock holding the items `id_list`.
+    pub fn new(id_list: Vec<ItemID>) -> Self {
+        Self { id_list }
+    }
+
     /// Get the ID List
     pub fn id_list(&self) -> &Vec<ItemID> {
         &self.id_list
     }
 }
 
-impl From<&[u8]> for VistaAndAboveIdListDataBlock {
-    fn from(data: &[u8]) -> Self {
-        let mut id_list = Vec::new();
-        let mut inner_data = &data[8..];
-        let mut offset = 0;
-        loop {
-            // Check for terminator
-            if LE::read_u16(&data[offset..]) == 0 {
-                break;
-            }
-
-            // Read an ItemID
-            let id = ItemID::from(inner_data);
-            debug!("Read {:?}", id);
-            let size = id.size;
-            id_list.push(id);
-            inner_data = &inner_data[(size as usize)..];
-            offset += size as usize;
+impl VistaAndAboveIdListDataBlock {
+    /// Parse a VistaAndAboveIDListDataBlock, without its BlockSize and
+    /// BlockSignature, with the given parsing context.
+    pub(crate) fn parse(data: &[u8], ctx: &ParseContext) -> Result<Self, crate::Error> {
+        let id_list = linktarget::parse_id_list(data, 0, ctx)?;
+        Ok(Self { id_list })
+    }
+
+    /// Remove identifying details from the items, keeping their sizes.
+    pub(crate) fn redact(&mut self, redactor: &crate::redact::Redactor) {
+        for id in &mut self.id_list {
+            id.redact(redactor);
         }
-        Self { id_list }
+    }
+}
+
+impl TryFrom<&[u8]> for VistaAndAboveIdListDataBlock {
+    type Error = crate::Error;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Self::parse(data, &ParseContext::default())
     }
 }
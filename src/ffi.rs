@@ -0,0 +1,315 @@
+//! A C interface to the parser, so that it can be used from C, C++, C# and
+//! any other language that can call C functions.
+//!
+//! Build it as a shared library with
+//!
+//! ```sh
+//! cargo build --release --features ffi
+//! ```
+//!
+//! and declare the functions with `include/lnk.h`. Shell links are handed
+//! out as opaque pointers, which must be freed with [`lnk_free`]. Strings
+//! are handed out as NULL-terminated UTF-8, which must be freed with
+//! [`lnk_string_free`]. When a function fails it returns NULL, and
+//! [`lnk_last_error`] describes why. A string holding a NULL character
+//! cannot be handed out whole, so NULL is returned for it instead.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+use crate::{Error, ShellLink};
+
+thread_local! {
+    /// The message of the last error on this thread.
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Set the message of the last error, writing any NULL character in it as
+/// `\0`.
+fn set_last_error(message: impl ToString) {
+    let message = message.to_string().replace('\0', "\\0");
+    LAST_ERROR.with(|last| *last.borrow_mut() = CString::new(message).ok());
+}
+
+/// Hand out `s` as a C string, or NULL if it holds a NULL character, which
+/// would cut it short.
+fn into_raw_string(s: &str) -> *mut c_char {
+    match CString::new(s) {
+        Ok(s) => s.into_raw(),
+        Err(_) => {
+            set_last_error("the string holds a NULL character");
+            ptr::null_mut()
+        }
+    }
+}
+
+fn into_raw_link(result: Result<ShellLink, Error>) -> *mut ShellLink {
+    match result {
+        Ok(link) => Box::into_raw(Box::new(link)),
+        Err(e) => {
+            set_last_error(e);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Get the message of the last error on the calling thread, or NULL if
+/// there has not been one. The message is owned by the library and stays
+/// valid until the next call that fails on the same thread.
+#[no_mangle]
+pub extern "C" fn lnk_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |s| s.as_ptr()))
+}
+
+/// Open and parse the shell link at `path`, a NULL-terminated UTF-8 string.
+/// Returns NULL on failure.
+///
+/// # Safety
+///
+/// `path` must be NULL or point to a NULL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn lnk_open(path: *const c_char) -> *mut ShellLink {
+    if path.is_null() {
+        set_last_error("path is NULL");
+        return ptr::null_mut();
+    }
+    match CStr::from_ptr(path).to_str() {
+        Ok(path) => into_raw_link(ShellLink::open(path)),
+        Err(e) => {
+            set_last_error(e);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Parse a shell link from the `len` bytes at `data`. Returns NULL on
+/// failure.
+///
+/// # Safety
+///
+/// `data` must be NULL or point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn lnk_parse(data: *const u8, len: usize) -> *mut ShellLink {
+    if data.is_null() {
+        set_last_error("data is NULL");
+        return ptr::null_mut();
+    }
+    let data = std::slice::from_raw_parts(data, len);
+    into_raw_link(ShellLink::from_slice(data).map(|(link, _)| link))
+}
+
+/// Free a shell link returned by [`lnk_open`] or [`lnk_parse`]. Does nothing
+/// if `link` is NULL.
+///
+/// # Safety
+///
+/// `link` must be NULL or a shell link from this library that has not been
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn lnk_free(link: *mut ShellLink) {
+    if !link.is_null() {
+        drop(Box::from_raw(link));
+    }
+}
+
+/// Free a string returned by this library. Does nothing if `s` is NULL.
+///
+/// # Safety
+///
+/// `s` must be NULL or a string from this library that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn lnk_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Get an optional string of `link`, or NULL if `link` is NULL, the string
+/// is not set or it holds a NULL character.
+unsafe fn string(
+    link: *const ShellLink,
+    f: impl FnOnce(&ShellLink) -> Option<&str>,
+) -> *mut c_char {
+    link.as_ref()
+        .and_then(f)
+        .map_or(ptr::null_mut(), into_raw_string)
+}
+
+/// Get the name (description) of the shell link, or NULL if it is not set.
+///
+/// # Safety
+///
+/// `link` must be NULL or a live shell link from this library.
+#[no_mangle]
+pub unsafe extern "C" fn lnk_name(link: *const ShellLink) -> *mut c_char {
+    string(link, |link| link.name().as_deref())
+}
+
+/// Get the relative path of the shell link, or NULL if it is not set.
+///
+/// # Safety
+///
+/// `link` must be NULL or a live shell link from this library.
+#[no_mangle]
+pub unsafe extern "C" fn lnk_relative_path(link: *const ShellLink) -> *mut c_char {
+    string(link, |link| link.relative_path().as_deref())
+}
+
+/// Get the working directory of the shell link, or NULL if it is not set.
+///
+/// # Safety
+///
+/// `link` must be NULL or a live shell link from this library.
+#[no_mangle]
+pub unsafe extern "C" fn lnk_working_dir(link: *const ShellLink) -> *mut c_char {
+    string(link, |link| link.working_dir().as_deref())
+}
+
+/// Get the command line arguments of the shell link, or NULL if they are
+/// not set.
+///
+/// # Safety
+///
+/// `link` must be NULL or a live shell link from this library.
+#[no_mangle]
+pub unsafe extern "C" fn lnk_arguments(link: *const ShellLink) -> *mut c_char {
+    string(link, |link| link.arguments().as_deref())
+}
+
+/// Get the icon location of the shell link, or NULL if it is not set.
+///
+/// # Safety
+///
+/// `link` must be NULL or a live shell link from this library.
+#[no_mangle]
+pub unsafe extern "C" fn lnk_icon_location(link: *const ShellLink) -> *mut c_char {
+    string(link, |link| link.icon_location().as_deref())
+}
+
+/// Get the local base path from the LinkInfo of the shell link, preferring
+/// the Unicode one, or NULL if there is none.
+///
+/// # Safety
+///
+/// `link` must be NULL or a live shell link from this library.
+#[no_mangle]
+pub unsafe extern "C" fn lnk_local_base_path(link: *const ShellLink) -> *mut c_char {
+    string(link, |link| {
+        let link_info = link.link_info().as_ref()?;
+        link_info
+            .local_base_path_unicode()
+            .as_deref()
+            .or(link_info.local_base_path().as_deref())
+    })
+}
+
+/// Get the LinkFlags of the shell link, or 0 if `link` is NULL.
+///
+/// # Safety
+///
+/// `link` must be NULL or a live shell link from this library.
+#[no_mangle]
+pub unsafe extern "C" fn lnk_link_flags(link: *const ShellLink) -> u32 {
+    link.as_ref()
+        .map_or(0, |link| link.header().link_flags().bits())
+}
+
+/// Get the FileAttributes of the shell link's target, or 0 if `link` is
+/// NULL.
+///
+/// # Safety
+///
+/// `link` must be NULL or a live shell link from this library.
+#[no_mangle]
+pub unsafe extern "C" fn lnk_file_attributes(link: *const ShellLink) -> u32 {
+    link.as_ref()
+        .map_or(0, |link| link.header().file_attributes().bits())
+}
+
+/// Get the creation time of the shell link's target as a FILETIME, or 0 if
+/// `link` is NULL.
+///
+/// # Safety
+///
+/// `link` must be NULL or a live shell link from this library.
+#[no_mangle]
+pub unsafe extern "C" fn lnk_creation_time(link: *const ShellLink) -> u64 {
+    link.as_ref()
+        .map_or(0, |link| link.header().creation_time().raw())
+}
+
+/// Get the access time of the shell link's target as a FILETIME, or 0 if
+/// `link` is NULL.
+///
+/// # Safety
+///
+/// `link` must be NULL or a live shell link from this library.
+#[no_mangle]
+pub unsafe extern "C" fn lnk_access_time(link: *const ShellLink) -> u64 {
+    link.as_ref()
+        .map_or(0, |link| link.header().access_time().raw())
+}
+
+/// Get the write time of the shell link's target as a FILETIME, or 0 if
+/// `link` is NULL.
+///
+/// # Safety
+///
+/// `link` must be NULL or a live shell link from this library.
+#[no_mangle]
+pub unsafe extern "C" fn lnk_write_time(link: *const ShellLink) -> u64 {
+    link.as_ref()
+        .map_or(0, |link| link.header().write_time().raw())
+}
+
+/// Get the size of the shell link's target, or 0 if `link` is NULL.
+///
+/// # Safety
+///
+/// `link` must be NULL or a live shell link from this library.
+#[no_mangle]
+pub unsafe extern "C" fn lnk_file_size(link: *const ShellLink) -> u32 {
+    link.as_ref().map_or(0, |link| link.header().file_size())
+}
+
+/// Get the icon index of the shell link, or 0 if `link` is NULL.
+///
+/// # Safety
+///
+/// `link` must be NULL or a live shell link from this library.
+#[no_mangle]
+pub unsafe extern "C" fn lnk_icon_index(link: *const ShellLink) -> i32 {
+    link.as_ref().map_or(0, |link| link.header().icon_index())
+}
+
+/// Get the ShowCommand of the shell link as stored, or 0 if `link` is NULL.
+///
+/// # Safety
+///
+/// `link` must be NULL or a live shell link from this library.
+#[no_mangle]
+pub unsafe extern "C" fn lnk_show_command(link: *const ShellLink) -> u32 {
+    link.as_ref()
+        .map_or(0, |link| link.header().show_command_raw())
+}
+
+/// Serialize the whole shell link to JSON. Returns NULL on failure.
+///
+/// # Safety
+///
+/// `link` must be NULL or a live shell link from this library.
+#[no_mangle]
+pub unsafe extern "C" fn lnk_to_json(link: *const ShellLink) -> *mut c_char {
+    let Some(link) = link.as_ref() else {
+        set_last_error("link is NULL");
+        return ptr::null_mut();
+    };
+    match serde_json::to_string(link) {
+        Ok(json) => into_raw_string(&json),
+        Err(e) => {
+            set_last_error(e);
+            ptr::null_mut()
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! File entry items, the shell items of an IDList that stand for a file or
+//! folder on a drive or share. Their own data holds the short (8.3) name,
+//! and, since Windows XP, a [`FILE_ENTRY_BLOCK`] extension block holds the
+//! long name.
+//!
+//! Windows writes both names when the item is made, so a long name that
+//! the short name could not have been made from suggests the file was
+//! renamed, or the item edited, afterwards.
+//!
+//! [`FILE_ENTRY_BLOCK`]: crate::extensionblock::FILE_ENTRY_BLOCK
+
+use byteorder::{ByteOrder, LE};
+
+use crate::extensionblock::{ExtensionBlockContents, FileEntryExtension};
+use crate::linktarget::ItemID;
+use crate::{strings, DosDateTime};
+
+/// The class types of file entry items are `0x30` to `0x3f`.
+const FILE_ENTRY_ITEM: u8 = 0x30;
+/// The flag of the class type of a file entry item for a folder.
+const FILE_ENTRY_DIRECTORY: u8 = 0x01;
+/// The flag of the class type of a file entry item whose short name is
+/// stored in UTF-16.
+const FILE_ENTRY_UNICODE: u8 = 0x04;
+
+/// The size of the class type, file size, modification time and file
+/// attributes that precede the short name.
+const HEADER_SIZE: usize = 12;
+
+/// A file entry item, for a file or folder.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct FileEntryItem {
+    class_type: u8,
+    file_size: u32,
+    modified: DosDateTime,
+    attributes: u16,
+    short_name: String,
+    extension: Option<FileEntryExtension>,
+}
+
+impl FileEntryItem {
+    /// The class type of the item, from `0x30` to `0x3f`.
+    pub fn class_type(&self) -> u8 {
+        self.class_type
+    }
+
+    /// Whether the item is for a folder.
+    pub fn is_directory(&self) -> bool {
+        self.class_type & FILE_ENTRY_DIRECTORY != 0
+    }
+
+    /// The size of the file, in bytes, or its low 32 bits for larger files.
+    /// Zero for folders.
+    pub fn file_size(&self) -> u32 {
+        self.file_size
+    }
+
+    /// When the file was last modified, in local time.
+    pub fn modified(&self) -> DosDateTime {
+        self.modified
+    }
+
+    /// The file attributes, as the low 16 bits of
+    /// [`FileAttributeFlags`](crate::FileAttributeFlags).
+    pub fn attributes(&self) -> u16 {
+        self.attributes
+    }
+
+    /// The name held by the item itself: the short (8.3) name, such as
+    /// `PROGRA~1`, or the long name where it is already a valid 8.3 name,
+    /// and in items written before Windows XP.
+    pub fn short_name(&self) -> &String {
+        &self.short_name
+    }
+
+    /// The long name, from the item's extension block, if it has one.
+    pub fn long_name(&self) -> Option<&String> {
+        self.extension.as_ref().map(FileEntryExtension::long_name)
+    }
+
+    /// The name of the file or folder: the long name, or else the short
+    /// name.
+    pub fn name(&self) -> &String {
+        self.long_name().unwrap_or(&self.short_name)
+    }
+
+    /// The item's extension block, holding the long name.
+    pub fn extension(&self) -> &Option<FileEntryExtension> {
+        &self.extension
+    }
+}
+
+impl ItemID {
+    /// The item as a file entry item, or `None` if it is another kind of
+    /// item or too short to be one.
+    pub fn file_entry(&self) -> Option<FileEntryItem> {
+        let data = self.data();
+        let class_type = *data.first()?;
+        if class_type & 0xf0 != FILE_ENTRY_ITEM || data.len() < HEADER_SIZE {
+            return None;
+        }
+        let unicode = class_type & FILE_ENTRY_UNICODE != 0;
+        let size = strings::nul_terminated_size(data, HEADER_SIZE, unicode)?;
+        let name = &data[HEADER_SIZE..HEADER_SIZE + size - if unicode { 2 } else { 1 }];
+        let short_name = match unicode {
+            true => strings::decode_utf16le(name),
+            false => strings::decode_exact(encoding_rs::WINDOWS_1252, name),
+        };
+        let extension =
+            self.extension_blocks()
+                .into_iter()
+                .find_map(|block| match block.contents() {
+                    ExtensionBlockContents::FileEntry(extension) => Some(extension.clone()),
+                    _ => None,
+                });
+        Some(FileEntryItem {
+            class_type,
+            file_size: LE::read_u32(&data[2..]),
+            modified: LE::read_u32(&data[6..]).into(),
+            attributes: LE::read_u16(&data[10..]),
+            short_name,
+            extension,
+        })
+    }
+}
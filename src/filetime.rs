@@ -1,11 +1,28 @@
 use std::fmt;
+use std::time::{Duration as StdDuration, SystemTime, UNIX_EPOCH};
 
-use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+
+/// The number of 100-nanosecond intervals in a second.
+const INTERVALS_PER_SECOND: u64 = 10_000_000;
+
+/// The number of seconds between the FILETIME epoch (1601-01-01) and the
+/// UNIX epoch (1970-01-01).
+const SECONDS_TO_UNIX_EPOCH: u64 = 11_644_473_600;
+
+/// The number of seconds in a day.
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// The number of days between 0000-03-01, the start of the proleptic
+/// Gregorian calendar as counted by [`civil_from_days`], and the FILETIME
+/// epoch.
+const DAYS_TO_FILETIME_EPOCH: i64 = 584_694;
 
 /// The FILETIME structure is a 64-bit value that represents the number of
 /// 100-nanosecond intervals that have elapsed since January 1, 1601,
 /// Coordinated Universal Time (UTC).
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct FileTime {
     low_date_time: u32,
     high_date_time: u32,
@@ -13,51 +30,749 @@ pub struct FileTime {
 
 impl fmt::Debug for FileTime {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.datetime())
+        if self.is_valid() {
+            write!(f, "{}", UtcTime::from(*self))
+        } else {
+            write!(f, "invalid FILETIME (0x{:016x})", self.raw())
+        }
+    }
+}
+
+impl fmt::Display for FileTime {
+    /// Write the time in the form `2008-09-12 20:27:17.101 UTC`, or `not
+    /// set` or `invalid (0x...)` when there is no valid time.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_set() {
+            write!(f, "{} UTC", UtcTime::from(*self))
+        } else if self.is_zero() {
+            f.write_str("not set")
+        } else {
+            write!(f, "invalid (0x{:016x})", self.raw())
+        }
+    }
+}
+
+impl Default for FileTime {
+    /// A zero `FileTime`, which the specification uses to indicate that no
+    /// time is set.
+    fn default() -> Self {
+        Self::from_raw(0)
+    }
+}
+
+impl PartialOrd for FileTime {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FileTime {
+    /// Order by the raw value, so earlier times come first.
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.raw().cmp(&other.raw())
     }
 }
 
 impl FileTime {
+    #[cfg(feature = "chrono")]
     fn epoch() -> NaiveDateTime {
         let epoch_date = NaiveDate::from_ymd_opt(1601, 1, 1).unwrap();
         let epoch_time = NaiveTime::from_hms_opt(0, 0, 0).unwrap();
         NaiveDateTime::new(epoch_date, epoch_time)
     }
 
-    /// Convert the `FileTime` object to a [[]]
+    /// Create a new `FileTime` from the raw number of 100-nanosecond
+    /// intervals since January 1, 1601 (UTC).
+    pub const fn from_raw(value: u64) -> Self {
+        Self {
+            low_date_time: (value & 0xFFFF_FFFF) as u32,
+            high_date_time: (value >> 32) as u32,
+        }
+    }
+
+    /// The raw number of 100-nanosecond intervals since January 1, 1601
+    /// (UTC).
+    pub fn raw(&self) -> u64 {
+        Self::into(*self)
+    }
+
+    /// Whether this `FileTime` is zero, which the specification uses to
+    /// indicate that no time is set.
+    pub fn is_zero(&self) -> bool {
+        self.raw() == 0
+    }
+
+    /// Convert the `FileTime` object to a [`DateTime<Utc>`].
+    #[cfg(feature = "chrono")]
+    pub fn datetime_utc(&self) -> DateTime<Utc> {
+        self.datetime().and_utc()
+    }
+
+    /// Whether this `FileTime` is within the range Windows accepts. Values
+    /// with the most significant bit set are rejected by the Windows time
+    /// conversion functions, and usually indicate a corrupt or tampered
+    /// timestamp. The raw value of an invalid `FileTime` is always kept.
+    pub fn is_valid(&self) -> bool {
+        self.raw() <= i64::MAX as u64
+    }
+
+    /// Whether this `FileTime` holds a time: it is neither zero nor
+    /// invalid.
+    pub(crate) fn is_set(&self) -> bool {
+        !self.is_zero() && self.is_valid()
+    }
+
+    /// Create a `FileTime` from a UNIX timestamp: the seconds since
+    /// 1970-01-01 (UTC), which may be negative, and the nanoseconds after
+    /// them. Returns `None` if the time is before the FILETIME epoch, is
+    /// past the last valid FILETIME, or `nanoseconds` is a second or more.
+    /// Nanoseconds finer than the 100 nanoseconds a FILETIME holds are
+    /// truncated.
+    pub fn from_unix_time(seconds: i64, nanoseconds: u32) -> Option<Self> {
+        if nanoseconds >= 1_000_000_000 {
+            return None;
+        }
+        let seconds = i128::from(seconds) + i128::from(SECONDS_TO_UNIX_EPOCH);
+        let intervals = seconds * i128::from(INTERVALS_PER_SECOND) + i128::from(nanoseconds / 100);
+        let time = Self::from_raw(u64::try_from(intervals).ok()?);
+        time.is_valid().then_some(time)
+    }
+
+    /// The UNIX timestamp of this time: the seconds since 1970-01-01 (UTC),
+    /// negative before then, and the nanoseconds after them. Returns `None`
+    /// if the value is not valid. The inverse of
+    /// [`FileTime::from_unix_time`].
+    pub fn unix_time(&self) -> Option<(i64, u32)> {
+        if !self.is_valid() {
+            return None;
+        }
+        let seconds = (self.raw() / INTERVALS_PER_SECOND) as i64 - SECONDS_TO_UNIX_EPOCH as i64;
+        let nanoseconds = (self.raw() % INTERVALS_PER_SECOND * 100) as u32;
+        Some((seconds, nanoseconds))
+    }
+
+    /// Format the time as an RFC 3339 string, such as
+    /// `2008-09-12T20:27:17.101+00:00`, or `None` if the value is zero (so
+    /// no time is set) or is not valid. The fraction of a second is written
+    /// only when it is not zero, with as many digits as it needs.
+    pub fn to_rfc3339(&self) -> Option<String> {
+        self.is_set().then(|| UtcTime::from(*self).to_rfc3339())
+    }
+
+    /// Parse an RFC 3339 string, such as `2008-09-12T20:27:17.101Z`, or
+    /// `None` if it is not one or is before the FILETIME epoch. Fractions
+    /// of a second finer than 100 nanoseconds are truncated.
+    pub fn parse_rfc3339(value: &str) -> Option<Self> {
+        UtcTime::parse_rfc3339(value)
+    }
+
+    /// Convert the `FileTime` object to a [`DateTime<Utc>`], or `None` if
+    /// the value is zero (so no time is set) or is not valid.
+    #[cfg(feature = "chrono")]
+    pub fn datetime_opt(&self) -> Option<DateTime<Utc>> {
+        self.is_set().then(|| self.datetime_utc())
+    }
+
+    /// Convert the `FileTime` object to a [`NaiveDateTime`] in UTC.
+    #[cfg(feature = "chrono")]
     pub fn datetime(&self) -> NaiveDateTime {
         let hundred_nanos_after_epoch: u64 = Self::into(*self);
-        Self::epoch() + Duration::microseconds((hundred_nanos_after_epoch as f64 / 10f64) as i64)
+        let seconds = hundred_nanos_after_epoch / INTERVALS_PER_SECOND;
+        let nanos = (hundred_nanos_after_epoch % INTERVALS_PER_SECOND) * 100;
+        Self::epoch() + Duration::seconds(seconds as i64) + Duration::nanoseconds(nanos as i64)
     }
 
     /// Create a new `FileTime` object representing now.
     pub fn now() -> Self {
-        Self::from(chrono::Local::now().naive_local())
+        #[cfg(feature = "testing")]
+        crate::testing::CLOCK_READS.with(|reads| reads.set(reads.get() + 1));
+        Self::from(SystemTime::now())
     }
 }
 
+#[cfg(feature = "chrono")]
 impl From<NaiveDateTime> for FileTime {
+    /// Convert a UTC date and time. Times before the FILETIME epoch are
+    /// clamped to the epoch.
     fn from(value: NaiveDateTime) -> Self {
         let duration = value - Self::epoch();
-        let val = duration.num_microseconds().unwrap() * 10;
-        let val = val as u64;
-        Self::from(val)
+        let seconds = duration.num_seconds();
+        let nanos = (duration - Duration::seconds(seconds))
+            .num_nanoseconds()
+            .unwrap_or(0);
+        let intervals =
+            i128::from(seconds) * i128::from(INTERVALS_PER_SECOND) + i128::from(nanos / 100);
+        Self::from_raw(intervals.clamp(0, i128::from(u64::MAX)) as u64)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<DateTime<Utc>> for FileTime {
+    fn from(value: DateTime<Utc>) -> Self {
+        Self::from(value.naive_utc())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<FileTime> for DateTime<Utc> {
+    fn from(value: FileTime) -> Self {
+        value.datetime_utc()
+    }
+}
+
+impl From<SystemTime> for FileTime {
+    /// Convert a system time. Times before the FILETIME epoch are clamped to
+    /// the epoch.
+    fn from(value: SystemTime) -> Self {
+        let unix_intervals = SECONDS_TO_UNIX_EPOCH * INTERVALS_PER_SECOND;
+        let intervals = match value.duration_since(UNIX_EPOCH) {
+            Ok(after) => unix_intervals.saturating_add(duration_to_intervals(after)),
+            Err(e) => unix_intervals.saturating_sub(duration_to_intervals(e.duration())),
+        };
+        Self::from_raw(intervals)
+    }
+}
+
+impl From<FileTime> for SystemTime {
+    fn from(value: FileTime) -> Self {
+        let intervals: u64 = value.into();
+        let unix_intervals = SECONDS_TO_UNIX_EPOCH * INTERVALS_PER_SECOND;
+        if intervals >= unix_intervals {
+            UNIX_EPOCH + intervals_to_duration(intervals - unix_intervals)
+        } else {
+            UNIX_EPOCH
+                .checked_sub(intervals_to_duration(unix_intervals - intervals))
+                .unwrap_or(UNIX_EPOCH)
+        }
+    }
+}
+
+fn duration_to_intervals(duration: StdDuration) -> u64 {
+    let intervals = u128::from(duration.as_secs()) * u128::from(INTERVALS_PER_SECOND)
+        + u128::from(duration.subsec_nanos() / 100);
+    intervals.min(u128::from(u64::MAX)) as u64
+}
+
+fn intervals_to_duration(intervals: u64) -> StdDuration {
+    StdDuration::new(
+        intervals / INTERVALS_PER_SECOND,
+        ((intervals % INTERVALS_PER_SECOND) * 100) as u32,
+    )
+}
+
+/// A valid `FileTime` as a date and time of day in UTC, in the proleptic
+/// Gregorian calendar, for formatting and parsing without chrono.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct UtcTime {
+    year: i64,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+    second: u32,
+    nanosecond: u32,
+}
+
+impl From<FileTime> for UtcTime {
+    fn from(value: FileTime) -> Self {
+        let intervals = value.raw();
+        let seconds = intervals / INTERVALS_PER_SECOND;
+        let nanosecond = ((intervals % INTERVALS_PER_SECOND) * 100) as u32;
+        let days = (seconds / SECONDS_PER_DAY) as i64 + DAYS_TO_FILETIME_EPOCH;
+        let (year, month, day) = civil_from_days(days);
+        let time = (seconds % SECONDS_PER_DAY) as u32;
+        Self {
+            year,
+            month,
+            day,
+            hour: time / 3600,
+            minute: time / 60 % 60,
+            second: time % 60,
+            nanosecond,
+        }
+    }
+}
+
+impl UtcTime {
+    /// The `FileTime` of this time, or `None` if it is before the FILETIME
+    /// epoch or too late to be held.
+    fn to_filetime(self) -> Option<FileTime> {
+        let days = days_from_civil(self.year, self.month, self.day) - DAYS_TO_FILETIME_EPOCH;
+        let seconds = i128::from(days) * i128::from(SECONDS_PER_DAY)
+            + i128::from(self.hour * 3600 + self.minute * 60 + self.second);
+        let intervals =
+            seconds * i128::from(INTERVALS_PER_SECOND) + i128::from(self.nanosecond / 100);
+        u64::try_from(intervals).ok().map(FileTime::from_raw)
+    }
+
+    fn to_rfc3339(self) -> String {
+        format!(
+            "{}T{:02}:{:02}:{:02}{}+00:00",
+            Date(self),
+            self.hour,
+            self.minute,
+            self.second,
+            Fraction(self.nanosecond)
+        )
+    }
+
+    /// Parse `YYYY-MM-DDTHH:MM:SS`, with an optional fraction of a second,
+    /// followed by `Z` or an offset from UTC such as `+01:00`.
+    fn parse_rfc3339(value: &str) -> Option<FileTime> {
+        let bytes = value.as_bytes();
+        let number = |range: std::ops::Range<usize>| digits(bytes.get(range)?);
+        let separators = [(4, b'-'), (7, b'-'), (13, b':'), (16, b':')];
+        if separators.iter().any(|&(i, c)| bytes.get(i) != Some(&c))
+            || !matches!(bytes.get(10), Some(b'T' | b't' | b' '))
+        {
+            return None;
+        }
+        let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+        let (hour, minute, second) = (number(11..13)?, number(14..16)?, number(17..19)?);
+        let days_in_month = days_in_month(i64::from(year), month)?;
+        // Allow for a leap second, the 61st second of a minute.
+        if day == 0 || day > days_in_month || hour > 23 || minute > 59 || second > 60 {
+            return None;
+        }
+
+        let mut rest = &bytes[19..];
+        let mut nanosecond = 0;
+        if let [b'.', fraction @ ..] = rest {
+            let length = fraction.iter().take_while(|d| d.is_ascii_digit()).count();
+            if length == 0 {
+                return None;
+            }
+            let used = length.min(9);
+            nanosecond = digits(&fraction[..used])? * 10u32.pow(9 - used as u32);
+            rest = &fraction[length..];
+        }
+        let offset = match rest {
+            [b'Z' | b'z'] => 0,
+            [sign @ (b'+' | b'-'), h1, h2, b':', m1, m2] => {
+                let (hours, minutes) = (digits(&[*h1, *h2])?, digits(&[*m1, *m2])?);
+                if hours > 23 || minutes > 59 {
+                    return None;
+                }
+                let seconds = i128::from(hours * 3600 + minutes * 60);
+                if *sign == b'-' {
+                    -seconds
+                } else {
+                    seconds
+                }
+            }
+            _ => return None,
+        };
+
+        let local = UtcTime {
+            year: i64::from(year),
+            month,
+            day,
+            hour,
+            minute,
+            second: second.min(59),
+            nanosecond,
+        }
+        .to_filetime()?;
+        let seconds = i128::from(second == 60) - offset;
+        let raw = i128::from(local.raw()) + seconds * i128::from(INTERVALS_PER_SECOND);
+        u64::try_from(raw).ok().map(FileTime::from_raw)
+    }
+}
+
+/// The number of days in the month `month` of the year `year`, or `None` if
+/// `month` is not from 1 to 12.
+fn days_in_month(year: i64, month: u32) -> Option<u32> {
+    Some(match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => return None,
+    })
+}
+
+/// The value of a string of ASCII digits, or `None` if it is empty or holds
+/// anything else.
+fn digits(digits: &[u8]) -> Option<u32> {
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    Some(digits.iter().fold(0, |n, d| n * 10 + u32::from(d - b'0')))
+}
+
+impl fmt::Display for UtcTime {
+    /// Write the time in the form `2008-09-12 20:27:17.101`, as chrono
+    /// does.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:02}:{:02}:{:02}{}",
+            Date(*self),
+            self.hour,
+            self.minute,
+            self.second,
+            Fraction(self.nanosecond)
+        )
+    }
+}
+
+/// The date of a [`UtcTime`], in the form `2008-09-12`, with a sign before
+/// years past 9999.
+struct Date(UtcTime);
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let UtcTime {
+            year, month, day, ..
+        } = self.0;
+        if (0..=9999).contains(&year) {
+            write!(f, "{:04}-{:02}-{:02}", year, month, day)
+        } else {
+            write!(f, "{:+05}-{:02}-{:02}", year, month, day)
+        }
     }
 }
 
+/// A fraction of a second, written as nothing when it is zero and otherwise
+/// as milliseconds, microseconds or nanoseconds, whichever is exact.
+struct Fraction(u32);
+
+impl fmt::Display for Fraction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            0 => Ok(()),
+            n if n % 1_000_000 == 0 => write!(f, ".{:03}", n / 1_000_000),
+            n if n % 1_000 == 0 => write!(f, ".{:06}", n / 1_000),
+            n => write!(f, ".{:09}", n),
+        }
+    }
+}
+
+/// The year, month and day of the day `days` after 0000-03-01.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    // The algorithm of Howard Hinnant's `civil_from_days`, with days counted
+    // from 0000-03-01 rather than the UNIX epoch, so they are never negative
+    // for a FILETIME.
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u32;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    } as u32;
+    let year = era * 400 + year_of_era + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// The number of days from 0000-03-01 to the given date, the inverse of
+/// [`civil_from_days`].
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_from_march = i64::from((month + 9) % 12);
+    let day_of_year = (153 * month_from_march + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era
+}
+
 impl From<u64> for FileTime {
     fn from(value: u64) -> Self {
-        let low_date_time = (value & 0xFFFF_FFFF) as u32;
-        let high_date_time = ((value >> 32) & 0xFFFF_FFFF) as u32;
-        Self {
-            low_date_time,
-            high_date_time,
+        Self::from_raw(value)
+    }
+}
+
+impl From<FileTime> for u64 {
+    fn from(val: FileTime) -> Self {
+        u64::from(val.low_date_time) + (u64::from(val.high_date_time) << 32)
+    }
+}
+
+/// A date and time in MS-DOS format, as the file entry items of an IDList
+/// hold them: the date in the low 16 bits and the time of day in the high
+/// 16 bits. The time is local time, in an unknown time zone, and has a
+/// resolution of two seconds.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+#[cfg_attr(
+    feature = "schemars",
+    derive(schemars::JsonSchema),
+    schemars(transparent)
+)]
+pub struct DosDateTime(u32);
+
+impl DosDateTime {
+    /// Create a `DosDateTime` from its raw value, the date in the low 16
+    /// bits and the time in the high 16 bits.
+    pub const fn from_raw(value: u32) -> Self {
+        Self(value)
+    }
+
+    /// The raw value.
+    pub fn raw(&self) -> u32 {
+        self.0
+    }
+
+    /// Whether a time is set, as it is not when the value is zero.
+    pub fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The year, from 1980 to 2107.
+    pub fn year(&self) -> u32 {
+        1980 + (self.0 >> 9 & 0x7f)
+    }
+
+    /// The month, which is from 1 to 12 when the value is valid.
+    pub fn month(&self) -> u32 {
+        self.0 >> 5 & 0xf
+    }
+
+    /// The day of the month, which is from 1 when the value is valid.
+    pub fn day(&self) -> u32 {
+        self.0 & 0x1f
+    }
+
+    /// The hour, which is below 24 when the value is valid.
+    pub fn hour(&self) -> u32 {
+        self.0 >> 27
+    }
+
+    /// The minute, which is below 60 when the value is valid.
+    pub fn minute(&self) -> u32 {
+        self.0 >> 21 & 0x3f
+    }
+
+    /// The second, always even, which is below 60 when the value is valid.
+    pub fn second(&self) -> u32 {
+        (self.0 >> 16 & 0x1f) * 2
+    }
+
+    /// Whether the value is a real date and time.
+    pub fn is_valid(&self) -> bool {
+        days_in_month(i64::from(self.year()), self.month())
+            .is_some_and(|days| (1..=days).contains(&self.day()))
+            && self.hour() < 24
+            && self.minute() < 60
+            && self.second() < 60
+    }
+
+    /// The time as a `FileTime`, taking the local time it holds to be UTC,
+    /// or `None` if it is zero or not valid.
+    pub fn to_filetime(&self) -> Option<FileTime> {
+        if self.is_zero() || !self.is_valid() {
+            return None;
+        }
+        UtcTime {
+            year: i64::from(self.year()),
+            month: self.month(),
+            day: self.day(),
+            hour: self.hour(),
+            minute: self.minute(),
+            second: self.second(),
+            nanosecond: 0,
         }
+        .to_filetime()
+    }
+
+    /// The local date and time, or `None` if the value is zero or not
+    /// valid.
+    #[cfg(feature = "chrono")]
+    pub fn datetime(&self) -> Option<NaiveDateTime> {
+        self.to_filetime().map(|time| time.datetime())
+    }
+}
+
+impl From<u32> for DosDateTime {
+    fn from(value: u32) -> Self {
+        Self::from_raw(value)
+    }
+}
+
+impl fmt::Debug for DosDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DosDateTime({}, 0x{:08x})", self, self.0)
     }
 }
 
-impl Into<u64> for FileTime {
-    fn into(self) -> u64 {
-        u64::from(self.low_date_time) + (u64::from(self.high_date_time) << 32)
+impl fmt::Display for DosDateTime {
+    /// Write the time in the form `2008-09-12 20:27:18`, with no time zone,
+    /// or as `not set` or `invalid (0x…)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.to_filetime() {
+            Some(time) => {
+                let time = UtcTime::from(time);
+                write!(
+                    f,
+                    "{} {:02}:{:02}:{:02}",
+                    Date(time),
+                    time.hour,
+                    time.minute,
+                    time.second
+                )
+            }
+            None if self.is_zero() => f.write_str("not set"),
+            None => write!(f, "invalid (0x{:08x})", self.0),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for FileTime {
+    /// Human-readable formats get an RFC 3339 string (or `null` when no time
+    /// is set), others the raw 64-bit value. To pick a representation
+    /// explicitly, use [`serde_rfc3339`] or [`serde_raw`].
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serde_rfc3339::serialize(self, serializer)
+        } else {
+            serde_raw::serialize(self, serializer)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FileTime {
+    /// Accepts any representation produced by the `Serialize` implementation
+    /// or the [`serde_rfc3339`] and [`serde_raw`] helpers.
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(FileTimeVisitor)
+        } else {
+            deserializer.deserialize_u64(FileTimeVisitor)
+        }
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for FileTime {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "FileTime".into()
+    }
+
+    fn schema_id() -> std::borrow::Cow<'static, str> {
+        concat!(module_path!(), "::FileTime").into()
+    }
+
+    /// The human-readable representation: an RFC 3339 string, `null` when no
+    /// time is set, or the raw 64-bit value when it is not a valid time.
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "anyOf": [
+                { "type": "string", "format": "date-time" },
+                { "type": "integer", "format": "uint64", "minimum": 0 },
+                { "type": "null" },
+            ],
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+struct FileTimeVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for FileTimeVisitor {
+    type Value = FileTime;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "an RFC 3339 date and time, a raw FILETIME value, or null"
+        )
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<FileTime, E> {
+        Ok(FileTime::from_raw(value))
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<FileTime, E> {
+        if value < 0 {
+            return Err(E::invalid_value(
+                serde::de::Unexpected::Signed(value),
+                &self,
+            ));
+        }
+        Ok(FileTime::from_raw(value as u64))
+    }
+
+    fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<FileTime, E> {
+        FileTime::parse_rfc3339(value)
+            .ok_or_else(|| E::invalid_value(serde::de::Unexpected::Str(value), &self))
+    }
+
+    fn visit_none<E: serde::de::Error>(self) -> Result<FileTime, E> {
+        Ok(FileTime::from_raw(0))
+    }
+
+    fn visit_unit<E: serde::de::Error>(self) -> Result<FileTime, E> {
+        Ok(FileTime::from_raw(0))
+    }
+
+    fn visit_some<D: serde::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<FileTime, D::Error> {
+        serde::Deserialize::deserialize(deserializer)
+    }
+}
+
+/// Serialize a [`FileTime`] as an RFC 3339 string, or `null` when no time is
+/// set. For use with `#[serde(with = "...")]`.
+#[cfg(feature = "serde")]
+pub mod serde_rfc3339 {
+    use super::FileTime;
+
+    /// Serialize a [`FileTime`] as an RFC 3339 string. Invalid values are
+    /// serialized as their raw 64-bit value.
+    pub fn serialize<S: serde::Serializer>(
+        value: &FileTime,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        if !value.is_valid() {
+            return serializer.serialize_u64(value.raw());
+        }
+        match value.to_rfc3339() {
+            Some(rfc3339) => serializer.serialize_some(&rfc3339),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    /// Deserialize a [`FileTime`] from an RFC 3339 string, `null`, or a raw
+    /// 64-bit value.
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<FileTime, D::Error> {
+        deserializer.deserialize_any(super::FileTimeVisitor)
+    }
+}
+
+/// Serialize a [`FileTime`] as its raw 64-bit value. For use with
+/// `#[serde(with = "...")]`.
+#[cfg(feature = "serde")]
+pub mod serde_raw {
+    use super::FileTime;
+
+    /// Serialize a [`FileTime`] as its raw 64-bit value.
+    pub fn serialize<S: serde::Serializer>(
+        value: &FileTime,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(value.raw())
+    }
+
+    /// Deserialize a [`FileTime`] from its raw 64-bit value.
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<FileTime, D::Error> {
+        deserializer.deserialize_u64(super::FileTimeVisitor)
     }
 }
@@ -0,0 +1,198 @@
+//! Serialization and deserialization of the bitflags types, shared by every flags structure.
+//!
+//! Flags are serialized as their raw value. The [`serde_names`] module serializes them instead
+//! as an object holding both the raw value and the names of the set flags, for example
+//! `{"bits": 129, "names": ["HAS_LINK_TARGET_ID_LIST", "IS_UNICODE"]}`, for use with
+//! `#[serde(with = "...")]` on the fields that want it; [`serde_bits`] names the default.
+//!
+//! With the `schemars` feature, the flags types describe their representation as a JSON schema,
+//! listing the names of the flags defined by the specification among those accepted when
+//! deserializing.
+
+use crate::extradata::console_data::{FillAttributeFlags, FontFamilyFlags};
+use crate::linkinfo::{CommonNetworkRelativeLinkFlags, LinkInfoFlags};
+use crate::{FileAttributeFlags, HotkeyModifiers, LinkFlags};
+
+macro_rules! impl_serde_flags {
+    ($($flags:ty),* $(,)?) => {
+        $(
+            impl serde::Serialize for $flags {
+                /// Flags are serialized as their raw value, including any bits that are not
+                /// defined by the specification.
+                fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serde_bits::serialize(self, serializer)
+                }
+            }
+
+            impl<'de> serde::Deserialize<'de> for $flags {
+                /// Human-readable formats accept either representation, others only the raw
+                /// value.
+                fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    if deserializer.is_human_readable() {
+                        serde_names::deserialize(deserializer)
+                    } else {
+                        serde_bits::deserialize(deserializer)
+                    }
+                }
+            }
+
+            #[cfg(feature = "schemars")]
+            impl schemars::JsonSchema for $flags {
+                fn schema_name() -> std::borrow::Cow<'static, str> {
+                    stringify!($flags).into()
+                }
+
+                fn schema_id() -> std::borrow::Cow<'static, str> {
+                    concat!(module_path!(), "::", stringify!($flags)).into()
+                }
+
+                fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+                    json_schema::<$flags>(generator)
+                }
+            }
+        )*
+    };
+}
+
+impl_serde_flags!(
+    LinkFlags,
+    FileAttributeFlags,
+    HotkeyModifiers,
+    LinkInfoFlags,
+    CommonNetworkRelativeLinkFlags,
+    FillAttributeFlags,
+    FontFamilyFlags,
+);
+
+/// The schema of flags as they are serialized, the raw value, or of any representation accepted
+/// when describing deserialization.
+#[cfg(feature = "schemars")]
+fn json_schema<F>(generator: &mut schemars::SchemaGenerator) -> schemars::Schema
+where
+    F: bitflags::Flags,
+    F::Bits: schemars::JsonSchema,
+{
+    use schemars::json_schema;
+
+    let bits = generator.subschema_for::<F::Bits>();
+    let names: Vec<&str> = F::FLAGS
+        .iter()
+        .map(|flag| flag.name())
+        .filter(|name| !name.is_empty())
+        .collect();
+    let names = json_schema!({
+        "type": "array",
+        "items": { "enum": names },
+    });
+    if generator.contract().is_deserialize() {
+        json_schema!({
+            "anyOf": [
+                bits,
+                names,
+                {
+                    "type": "object",
+                    "properties": { "bits": bits, "names": names },
+                    "minProperties": 1,
+                },
+            ],
+        })
+    } else {
+        bits
+    }
+}
+
+/// Serialize flags as their raw value, including any bits that are not defined by the
+/// specification. For use with `#[serde(with = "...")]`.
+pub mod serde_bits {
+    use bitflags::Flags;
+    use serde::{Deserialize, Serialize};
+
+    /// Serialize flags as their raw value.
+    pub fn serialize<F, S>(flags: &F, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        F: Flags,
+        F::Bits: Serialize,
+        S: serde::Serializer,
+    {
+        flags.bits().serialize(serializer)
+    }
+
+    /// Deserialize flags from their raw value, keeping any unknown bits.
+    pub fn deserialize<'de, F, D>(deserializer: D) -> Result<F, D::Error>
+    where
+        F: Flags,
+        F::Bits: Deserialize<'de>,
+        D: serde::Deserializer<'de>,
+    {
+        F::Bits::deserialize(deserializer).map(F::from_bits_retain)
+    }
+}
+
+/// Serialize flags as an object holding the raw value and the names of the set flags, for
+/// example `{"bits": 129, "names": ["HAS_LINK_TARGET_ID_LIST", "IS_UNICODE"]}`. For use with
+/// `#[serde(with = "...")]`.
+pub mod serde_names {
+    use bitflags::Flags;
+    use serde::de::Error as _;
+    use serde::ser::SerializeStruct;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr<B> {
+        Bits(B),
+        Names(Vec<String>),
+        Object {
+            bits: Option<B>,
+            names: Option<Vec<String>>,
+        },
+    }
+
+    /// Serialize flags as their raw value and the names of the set flags. Bits that are not
+    /// defined by the specification only appear in the raw value.
+    pub fn serialize<F, S>(flags: &F, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        F: Flags,
+        F::Bits: Serialize,
+        S: serde::Serializer,
+    {
+        let names: Vec<&str> = flags.iter_names().map(|(name, _)| name).collect();
+        let mut state = serializer.serialize_struct("Flags", 2)?;
+        state.serialize_field("bits", &flags.bits())?;
+        state.serialize_field("names", &names)?;
+        state.end()
+    }
+
+    /// Deserialize flags from a raw value, an array of flag names, or an object holding either
+    /// or both. When both are given the raw value is used, so unknown bits are kept.
+    pub fn deserialize<'de, F, D>(deserializer: D) -> Result<F, D::Error>
+    where
+        F: Flags,
+        F::Bits: Deserialize<'de>,
+        D: serde::Deserializer<'de>,
+    {
+        match Repr::<F::Bits>::deserialize(deserializer)? {
+            Repr::Bits(bits)
+            | Repr::Object {
+                bits: Some(bits), ..
+            } => Ok(F::from_bits_retain(bits)),
+            Repr::Names(names)
+            | Repr::Object {
+                bits: None,
+                names: Some(names),
+            } => from_names(&names),
+            Repr::Object {
+                bits: None,
+                names: None,
+            } => Err(D::Error::missing_field("bits")),
+        }
+    }
+
+    fn from_names<F: Flags, E: serde::de::Error>(names: &[String]) -> Result<F, E> {
+        names.iter().try_fold(F::empty(), |flags, name| {
+            F::from_name(name)
+                .map(|flag| flags.union(flag))
+                .ok_or_else(|| E::custom(format_args!("unknown flag name {:?}", name)))
+        })
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use bitflags::Flags;
+
+use crate::{ExtraData, FileTime, HotkeyKey, ShellLink};
+
+/// A map from dotted keys to values, being built by
+/// [`ShellLink::to_flat_map`].
+#[derive(Default)]
+struct FlatMap(BTreeMap<String, String>);
+
+impl FlatMap {
+    /// Add a value, unless one is already set for the key, so that the first
+    /// of two ExtraData blocks of the same type is kept.
+    fn insert(&mut self, key: &str, value: impl Display) {
+        self.0
+            .entry(key.to_string())
+            .or_insert_with(|| value.to_string());
+    }
+
+    fn optional(&mut self, key: &str, value: &Option<String>) {
+        if let Some(value) = value {
+            self.insert(key, value);
+        }
+    }
+
+    /// Add a time as an RFC 3339 string, or its raw value when it is not
+    /// valid. Times that are not set are left out.
+    fn time(&mut self, key: &str, time: FileTime) {
+        if !time.is_valid() {
+            self.insert(key, time.raw());
+        } else if let Some(rfc3339) = time.to_rfc3339() {
+            self.insert(key, rfc3339);
+        }
+    }
+
+    /// Add the names of the flags set, separated by ` | `.
+    fn flags<F: Flags>(&mut self, key: &str, flags: &F) {
+        let names: Vec<&str> = flags.iter_names().map(|(name, _)| name).collect();
+        self.insert(key, names.join(" | "));
+    }
+}
+
+impl ShellLink {
+    /// Flatten the shell link into a map from dotted keys, such as
+    /// `header.write_time`, `stringdata.arguments` and
+    /// `extra.tracker.machine_id`, to values written as strings, for
+    /// detection and enrichment pipelines that do not walk nested data.
+    ///
+    /// Times are written in RFC 3339 form, flags as their names separated
+    /// by ` | `, and fields that are not present are left out. Of the
+    /// strings stored both as ANSI and as Unicode, the Unicode one is used
+    /// when present.
+    pub fn to_flat_map(&self) -> BTreeMap<String, String> {
+        let mut map = FlatMap::default();
+
+        let header = self.header();
+        map.flags("header.link_flags", header.link_flags());
+        map.flags("header.file_attributes", header.file_attributes());
+        map.time("header.creation_time", header.creation_time());
+        map.time("header.access_time", header.access_time());
+        map.time("header.write_time", header.write_time());
+        map.insert("header.file_size", header.file_size());
+        map.insert("header.icon_index", header.icon_index());
+        map.insert(
+            "header.show_command",
+            format!("{:?}", header.show_command()),
+        );
+        if !matches!(header.hotkey().key(), HotkeyKey::NoKeyAssigned) {
+            map.insert("header.hotkey", header.hotkey());
+        }
+
+        map.optional("target.path", &self.target_path());
+        map.optional("target.file_name", &self.target_file_name());
+        map.optional("target.extension", &self.target_extension());
+        map.insert("target.location", format!("{:?}", self.target_location()));
+        map.optional("target.url", &self.target_url());
+        map.optional("target.app_user_model_id", &self.app_user_model_id());
+        if let Some(id_list) = self.link_target_id_list() {
+            map.insert("idlist.item_count", id_list.id_list().len());
+        }
+
+        if let Some(link_info) = self.link_info() {
+            if let Some(volume) = link_info.volume_id() {
+                map.insert(
+                    "linkinfo.volume.drive_type",
+                    format!("{:?}", volume.drive_type()),
+                );
+                let serial = volume.drive_serial_number();
+                map.insert(
+                    "linkinfo.volume.serial_number",
+                    format!("{:04X}-{:04X}", serial >> 16, serial & 0xffff),
+                );
+                map.insert("linkinfo.volume.label", volume.volume_label());
+            }
+            map.optional(
+                "linkinfo.local_base_path",
+                &link_info
+                    .local_base_path_unicode()
+                    .clone()
+                    .or_else(|| link_info.local_base_path().clone()),
+            );
+            map.insert(
+                "linkinfo.common_path_suffix",
+                link_info
+                    .common_path_suffix_unicode()
+                    .as_ref()
+                    .unwrap_or(link_info.common_path_suffix()),
+            );
+            if let Some(network) = link_info.common_network_relative_link() {
+                map.insert("linkinfo.network.net_name", network.net_name());
+                if let Some(device) = network.device_name().filter(|d| !d.is_empty()) {
+                    map.insert("linkinfo.network.device_name", device);
+                }
+                if let Some(provider) = network.network_provider_type() {
+                    map.insert("linkinfo.network.provider_type", format!("{:?}", provider));
+                }
+            }
+        }
+
+        map.optional("stringdata.name", self.name());
+        map.optional("stringdata.relative_path", self.relative_path());
+        map.optional("stringdata.working_dir", self.working_dir());
+        map.optional("stringdata.arguments", self.arguments());
+        map.optional("stringdata.icon_location", self.icon_location());
+
+        for block in self.extra_data() {
+            match block {
+                ExtraData::ConsoleProps(props) => {
+                    map.insert("extra.console.face_name", props.face_name());
+                    map.insert("extra.console.font_size", props.font_size());
+                }
+                ExtraData::ConsoleFeProps(props) => {
+                    map.insert("extra.console_fe.code_page", props.code_page());
+                }
+                ExtraData::DarwinProps(props) => map.insert(
+                    "extra.darwin.data",
+                    props
+                        .darwin_data_unicode()
+                        .as_ref()
+                        .unwrap_or(props.darwin_data_ansi()),
+                ),
+                ExtraData::EnvironmentProps(props) => map.insert(
+                    "extra.environment.target",
+                    props
+                        .target_unicode()
+                        .as_ref()
+                        .unwrap_or(props.target_ansi()),
+                ),
+                ExtraData::IconEnvironmentProps(props) => map.insert(
+                    "extra.icon_environment.target",
+                    props
+                        .target_unicode()
+                        .as_ref()
+                        .unwrap_or(props.target_ansi()),
+                ),
+                ExtraData::KnownFolderProps(props) => {
+                    map.insert("extra.known_folder.id", props.known_folder_id());
+                }
+                ExtraData::PropertyStoreProps(props) => {
+                    map.insert("extra.property_store.size", props.property_store().len());
+                }
+                ExtraData::ShimProps(props) => {
+                    map.insert("extra.shim.layer_name", props.layer_name());
+                }
+                ExtraData::SpecialFolderProps(props) => {
+                    map.insert("extra.special_folder.id", props.special_folder_id());
+                }
+                ExtraData::TrackerProps(tracker) => {
+                    map.insert("extra.tracker.machine_id", tracker.machine_id());
+                    map.insert("extra.tracker.droid_volume", tracker.droid()[0]);
+                    map.insert("extra.tracker.droid_file", tracker.droid()[1]);
+                    map.insert("extra.tracker.birth_droid_volume", tracker.droid_birth()[0]);
+                    map.insert("extra.tracker.birth_droid_file", tracker.droid_birth()[1]);
+                    if let Some(info) = tracker.droid_info() {
+                        map.insert("extra.tracker.mac_address", info.mac_address());
+                        map.time("extra.tracker.droid_time", info.timestamp());
+                    }
+                }
+                ExtraData::VistaAndAboveIdListProps(props) => {
+                    map.insert("extra.vista_id_list.item_count", props.id_list().len());
+                }
+            }
+        }
+        map.0
+    }
+}
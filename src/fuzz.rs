@@ -0,0 +1,121 @@
+//! Support for generating structures with [`arbitrary`], for fuzzing and property-based testing.
+//!
+//! The implementations generate values that a shell link written by Windows could hold: strings
+//! without NULL characters that fit the fields they are stored in, flags with only the defined
+//! bits set, and sizes and flags that match the data present. The structures with such
+//! invariants implement [`Arbitrary`] next to their definitions, using the helpers here.
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::extradata::console_data::{FillAttributeFlags, FontFamilyFlags};
+use crate::linkinfo::{CommonNetworkRelativeLinkFlags, LinkInfoFlags};
+use crate::{
+    FileAttributeFlags, FileTime, Guid, HotkeyFlags, HotkeyKey, HotkeyModifiers, LinkFlags,
+};
+
+/// The most characters a string in the StringData section can hold, as its size is stored in 16
+/// bits.
+pub(crate) const STRING_DATA_MAX: usize = u16::MAX as usize;
+
+/// Generate an ANSI string of at most `MAX` characters. Code pages differ beyond ASCII, so only
+/// printable ASCII characters are generated.
+pub(crate) fn ansi_string<const MAX: usize>(u: &mut Unstructured) -> Result<String> {
+    let bytes: Vec<u8> = u.arbitrary()?;
+    Ok(bytes
+        .into_iter()
+        .take(MAX)
+        .map(|b| char::from(b' ' + b % 95))
+        .collect())
+}
+
+/// Generate a Unicode string without NULL characters of at most `MAX` UTF-16 code units.
+pub(crate) fn unicode_string<const MAX: usize>(u: &mut Unstructured) -> Result<String> {
+    let s: String = u.arbitrary()?;
+    let mut len = 0;
+    Ok(s.chars()
+        .filter(|&c| c != '\0')
+        .take_while(|c| {
+            len += c.len_utf16();
+            len <= MAX
+        })
+        .collect())
+}
+
+/// Generate an optional Unicode string without NULL characters of at most `MAX` UTF-16 code
+/// units.
+pub(crate) fn optional_unicode_string<const MAX: usize>(
+    u: &mut Unstructured,
+) -> Result<Option<String>> {
+    if u.arbitrary()? {
+        unicode_string::<MAX>(u).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Generate an optional Unicode string that is not empty, as for the fixed-size Unicode fields
+/// of ExtraData blocks, which are read as absent when empty.
+pub(crate) fn optional_fixed_unicode_string<const MAX: usize>(
+    u: &mut Unstructured,
+) -> Result<Option<String>> {
+    Ok(optional_unicode_string::<MAX>(u)?.filter(|s| !s.is_empty()))
+}
+
+/// The size, in bytes, of `s` stored as a NULL-terminated ANSI string.
+pub(crate) fn ansi_size(s: &str) -> u32 {
+    s.len() as u32 + 1
+}
+
+/// The size, in bytes, of `s` stored as a NULL-terminated Unicode string.
+pub(crate) fn unicode_size(s: &str) -> u32 {
+    (s.encode_utf16().count() as u32 + 1) * 2
+}
+
+macro_rules! impl_arbitrary_flags {
+    ($($flags:ty),* $(,)?) => {
+        $(
+            impl<'a> Arbitrary<'a> for $flags {
+                /// Only flags defined by the specification are set.
+                fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+                    Ok(Self::from_bits_truncate(u.arbitrary()?))
+                }
+            }
+        )*
+    };
+}
+
+impl_arbitrary_flags!(
+    LinkFlags,
+    FileAttributeFlags,
+    HotkeyModifiers,
+    LinkInfoFlags,
+    CommonNetworkRelativeLinkFlags,
+    FillAttributeFlags,
+    FontFamilyFlags,
+);
+
+impl<'a> Arbitrary<'a> for Guid {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self::from_u128(u.arbitrary()?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for FileTime {
+    /// Any value is generated, including zero (no time set) and values that are not valid.
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self::from_raw(u.arbitrary()?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for HotkeyKey {
+    /// Virtual key codes with a name are always generated as that variant.
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self::from(u8::arbitrary(u)?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for HotkeyFlags {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self::new(u.arbitrary()?, u.arbitrary()?))
+    }
+}
@@ -0,0 +1,289 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::FileTime;
+
+/// A GUID, stored in packet representation ([MS-DTYP] section 2.3.4.2) in
+/// shell links.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Guid {
+    data1: u32,
+    data2: u16,
+    data3: u16,
+    data4: [u8; 8],
+}
+
+impl Guid {
+    /// The nil GUID, `{00000000-0000-0000-0000-000000000000}`.
+    pub const NIL: Guid = Guid::from_fields(0, 0, 0, [0; 8]);
+
+    /// Create a GUID from its fields, in the order they are written in the
+    /// registry format.
+    pub const fn from_fields(data1: u32, data2: u16, data3: u16, data4: [u8; 8]) -> Self {
+        Self {
+            data1,
+            data2,
+            data3,
+            data4,
+        }
+    }
+
+    /// Create a GUID from a 128-bit value whose hexadecimal digits read the
+    /// same as the registry format, for example
+    /// `Guid::from_u128(0x00021401_0000_0000_c000_000000000046)`.
+    pub const fn from_u128(value: u128) -> Self {
+        let d = value.to_be_bytes();
+        Self::from_fields(
+            (value >> 96) as u32,
+            (value >> 80) as u16,
+            (value >> 64) as u16,
+            [d[8], d[9], d[10], d[11], d[12], d[13], d[14], d[15]],
+        )
+    }
+
+    /// Create a GUID from its 16-byte packet representation, as it is
+    /// stored in a shell link.
+    pub const fn from_bytes_le(b: [u8; 16]) -> Self {
+        Self::from_fields(
+            u32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            u16::from_le_bytes([b[4], b[5]]),
+            u16::from_le_bytes([b[6], b[7]]),
+            [b[8], b[9], b[10], b[11], b[12], b[13], b[14], b[15]],
+        )
+    }
+
+    /// The 16-byte packet representation of this GUID, as it is stored in a
+    /// shell link.
+    pub const fn to_bytes_le(&self) -> [u8; 16] {
+        let d1 = self.data1.to_le_bytes();
+        let d2 = self.data2.to_le_bytes();
+        let d3 = self.data3.to_le_bytes();
+        let d4 = self.data4;
+        [
+            d1[0], d1[1], d1[2], d1[3], d2[0], d2[1], d3[0], d3[1], d4[0], d4[1], d4[2], d4[3],
+            d4[4], d4[5], d4[6], d4[7],
+        ]
+    }
+
+    /// The 128-bit value whose hexadecimal digits read the same as the
+    /// registry format.
+    pub const fn as_u128(&self) -> u128 {
+        ((self.data1 as u128) << 96)
+            | ((self.data2 as u128) << 80)
+            | ((self.data3 as u128) << 64)
+            | (u64::from_be_bytes(self.data4) as u128)
+    }
+
+    /// The fields of this GUID, in the order they are written in the
+    /// registry format.
+    pub const fn as_fields(&self) -> (u32, u16, u16, &[u8; 8]) {
+        (self.data1, self.data2, self.data3, &self.data4)
+    }
+
+    /// Whether this is the nil GUID.
+    pub const fn is_nil(&self) -> bool {
+        self.as_u128() == 0
+    }
+
+    /// The version of this GUID, from the top four bits of `data3`. Version
+    /// 1 GUIDs, as used for object droids, are made from a timestamp and the
+    /// MAC address of the machine that made them.
+    pub const fn version(&self) -> u8 {
+        (self.data3 >> 12) as u8
+    }
+
+    /// When this GUID was made, if it is a version 1 GUID, or `None`
+    /// otherwise. The Link Tracking service makes object droids this way, so
+    /// this is usually when the target was first tracked.
+    pub fn timestamp(&self) -> Option<FileTime> {
+        if self.version() != 1 {
+            return None;
+        }
+        let intervals = (u64::from(self.data3 & 0x0fff) << 48)
+            | (u64::from(self.data2) << 32)
+            | u64::from(self.data1);
+        intervals
+            .checked_sub(GREGORIAN_TO_FILETIME_EPOCH)
+            .map(FileTime::from_raw)
+    }
+
+    /// The clock sequence of this GUID, if it is a version 1 GUID, or
+    /// `None` otherwise. It is chosen at random, and changed when the clock
+    /// of the machine that made the GUID goes backwards.
+    pub const fn clock_sequence(&self) -> Option<u16> {
+        if self.version() != 1 {
+            return None;
+        }
+        Some(u16::from_be_bytes([self.data4[0], self.data4[1]]) & 0x3fff)
+    }
+
+    /// The node of this GUID, if it is a version 1 GUID, or `None`
+    /// otherwise. This is usually the MAC address of a network adapter of
+    /// the machine that made the GUID.
+    pub const fn node(&self) -> Option<[u8; 6]> {
+        if self.version() != 1 {
+            return None;
+        }
+        let d4 = &self.data4;
+        Some([d4[2], d4[3], d4[4], d4[5], d4[6], d4[7]])
+    }
+}
+
+/// The number of 100-nanosecond intervals between the epoch of version 1
+/// GUIDs (1582-10-15) and the FILETIME epoch (1601-01-01).
+const GREGORIAN_TO_FILETIME_EPOCH: u64 = 5_748_192_000_000_000;
+
+impl Guid {
+    /// Read a GUID in packet representation from the start of `data`, which
+    /// the caller has checked holds at least 16 bytes.
+    pub(crate) fn read(data: &[u8]) -> Self {
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&data[..16]);
+        Self::from_bytes_le(bytes)
+    }
+}
+
+impl TryFrom<&[u8]> for Guid {
+    type Error = crate::Error;
+
+    /// Read a GUID in packet representation from the start of `data`.
+    /// Fails if `data` is shorter than 16 bytes.
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        if data.len() < 16 {
+            return Err(crate::Error::UnexpectedEof {
+                structure: "GUID",
+                offset: 0,
+                needed: 16,
+                available: data.len(),
+            });
+        }
+        Ok(Self::read(data))
+    }
+}
+
+impl fmt::Display for Guid {
+    /// Write the GUID in registry format, for example
+    /// `{00021401-0000-0000-C000-000000000046}`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let d4 = &self.data4;
+        write!(
+            f,
+            "{{{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}}}",
+            self.data1,
+            self.data2,
+            self.data3,
+            d4[0],
+            d4[1],
+            d4[2],
+            d4[3],
+            d4[4],
+            d4[5],
+            d4[6],
+            d4[7]
+        )
+    }
+}
+
+impl fmt::Debug for Guid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Guid {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Guid {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Guid {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "Guid".into()
+    }
+
+    fn schema_id() -> std::borrow::Cow<'static, str> {
+        concat!(module_path!(), "::Guid").into()
+    }
+
+    /// A string in registry format. Braces are optional and case is ignored
+    /// when deserializing.
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "pattern": "^\\{?[0-9A-Fa-f]{8}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{12}\\}?$",
+        })
+    }
+}
+
+impl FromStr for Guid {
+    type Err = ParseGuidError;
+
+    /// Parse a GUID in registry format. The braces are optional, and case
+    /// is ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseGuidError(s.to_string());
+        let inner = match s.strip_prefix('{') {
+            Some(rest) => rest.strip_suffix('}').ok_or_else(err)?,
+            None => s,
+        };
+        let groups: Vec<&str> = inner.split('-').collect();
+        let lengths: Vec<usize> = groups.iter().map(|g| g.len()).collect();
+        if lengths != [8, 4, 4, 4, 12] || !inner.chars().all(|c| c == '-' || c.is_ascii_hexdigit())
+        {
+            return Err(err());
+        }
+        u128::from_str_radix(&groups.concat(), 16)
+            .map(Self::from_u128)
+            .map_err(|_| err())
+    }
+}
+
+/// The error returned when a GUID cannot be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseGuidError(String);
+
+impl fmt::Display for ParseGuidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid GUID: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseGuidError {}
+
+#[cfg(feature = "uuid")]
+impl From<uuid::Uuid> for Guid {
+    fn from(value: uuid::Uuid) -> Self {
+        Self::from_u128(value.as_u128())
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl From<Guid> for uuid::Uuid {
+    fn from(value: Guid) -> Self {
+        uuid::Uuid::from_u128(value.as_u128())
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl PartialEq<uuid::Uuid> for Guid {
+    fn eq(&self, other: &uuid::Uuid) -> bool {
+        self.as_u128() == other.as_u128()
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl PartialEq<Guid> for uuid::Uuid {
+    fn eq(&self, other: &Guid) -> bool {
+        self.as_u128() == other.as_u128()
+    }
+}
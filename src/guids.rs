@@ -0,0 +1,125 @@
+//! Well-known GUIDs found in shell links: the class identifier of shell
+//! links, the CLSIDs of the shell folders that LinkTargetIDLists start with,
+//! the IDs of common known folders, as held by a KnownFolderDataBlock, and
+//! the FormatIDs of common property sets, as held by a
+//! PropertyStoreDataBlock.
+//!
+//! The names are those used by the Windows SDK. [`name`] looks a GUID up by
+//! value:
+//!
+//! ```rust
+//! use lnk::guids;
+//!
+//! let id = "{FDD39AD0-238F-46AF-ADB4-6C85480369C7}".parse().unwrap();
+//! assert_eq!(id, guids::FOLDERID_DOCUMENTS);
+//! assert_eq!(guids::name(id), Some("FOLDERID_DOCUMENTS"));
+//! ```
+
+use crate::Guid;
+
+macro_rules! guids {
+    ($($(#[doc = $doc:literal])* $name:ident = $value:literal;)*) => {
+        $(
+            $(#[doc = $doc])*
+            pub const $name: Guid = Guid::from_u128($value);
+        )*
+
+        /// Every GUID in this module, with its name.
+        const NAMES: &[(Guid, &str)] = &[$(($name, stringify!($name))),*];
+    };
+}
+
+guids! {
+    /// The class identifier that every ShellLinkHeader holds.
+    CLSID_SHELL_LINK = 0x00021401_0000_0000_c000_000000000046;
+
+    /// The Computer folder, which the items of a path on a drive start with.
+    CLSID_MY_COMPUTER = 0x20d04fe0_3aea_1069_a2d8_08002b30309d;
+    /// The Network folder, which the items of a target on the network start
+    /// with.
+    CLSID_NETWORK_PLACES = 0x208d2c60_3aea_1069_a2d7_08002b30309d;
+    /// The Internet folder, which the items of a URL start with.
+    CLSID_INTERNET = 0x871c5380_42a0_1069_a2ea_08002b30309d;
+    /// The Applications folder, whose items are the installed apps.
+    CLSID_APPS_FOLDER = 0x4234d49b_0245_4df3_b780_3893943456e1;
+    /// The Control Panel.
+    CLSID_CONTROL_PANEL = 0x21ec2020_3aea_1069_a2dd_08002b30309d;
+    /// The Recycle Bin.
+    CLSID_RECYCLE_BIN = 0x645ff040_5081_101b_9f08_00aa002f954e;
+    /// The user's files, the folder named after the user on the desktop.
+    CLSID_USERS_FILES = 0x59031a47_3f72_44a7_89c5_5595fe6b30ee;
+
+    /// The user's desktop.
+    FOLDERID_DESKTOP = 0xb4bfcc3a_db2c_424c_b029_7fe99a87c641;
+    /// The user's Documents folder.
+    FOLDERID_DOCUMENTS = 0xfdd39ad0_238f_46af_adb4_6c85480369c7;
+    /// The user's Downloads folder.
+    FOLDERID_DOWNLOADS = 0x374de290_123f_4565_9164_39c4925e467b;
+    /// The user's Music folder.
+    FOLDERID_MUSIC = 0x4bd8d571_6d19_48d3_be97_422220080e43;
+    /// The user's Pictures folder.
+    FOLDERID_PICTURES = 0x33e28130_4e1e_4676_835a_98395c3bc3bb;
+    /// The user's Videos folder.
+    FOLDERID_VIDEOS = 0x18989b1d_99b5_455b_841c_ab7c74e4ddfc;
+    /// The user's profile, `%USERPROFILE%`.
+    FOLDERID_PROFILE = 0x5e6c858f_0e22_4760_9afe_ea3317b67173;
+    /// The user's roaming application data, `%APPDATA%`.
+    FOLDERID_ROAMING_APP_DATA = 0x3eb685db_65f9_4cf6_a03a_e3ef65729f3d;
+    /// The user's local application data, `%LOCALAPPDATA%`.
+    FOLDERID_LOCAL_APP_DATA = 0xf1b32785_6fba_4fcf_9d55_7b8e7f157091;
+    /// The user's Recent Items folder.
+    FOLDERID_RECENT = 0xae50c081_ebd2_438a_8655_8a092e34987a;
+    /// The user's Start menu.
+    FOLDERID_START_MENU = 0x625b53c3_ab48_4ec1_ba1f_a1ef4146fc19;
+    /// The Programs folder of the user's Start menu.
+    FOLDERID_PROGRAMS = 0xa77f5d77_2e2b_44c3_a6a2_aba601054a51;
+    /// The user's Startup folder, whose items are started when the user
+    /// logs on.
+    FOLDERID_STARTUP = 0xb97d20bb_f46a_4c97_ba10_5e3608430854;
+    /// The Startup folder shared by every user.
+    FOLDERID_COMMON_STARTUP = 0x82a5ea35_d9cd_47c5_9629_e15d2f714e6e;
+    /// The Programs folder of the Start menu shared by every user.
+    FOLDERID_COMMON_PROGRAMS = 0x0139d44e_6afe_49f2_8690_3dafcae6ffb8;
+    /// The Public folder, `%PUBLIC%`.
+    FOLDERID_PUBLIC = 0xdfdf76a2_c82a_4d63_906a_5644ac457385;
+    /// The desktop shared by every user.
+    FOLDERID_PUBLIC_DESKTOP = 0xc4aa340d_f20f_4863_afef_f87ef2e6ba25;
+    /// The application data shared by every user, `%ProgramData%`.
+    FOLDERID_PROGRAM_DATA = 0x62ab5d82_fdc1_4dc3_a9dd_070d1d495d97;
+    /// The Program Files folder.
+    FOLDERID_PROGRAM_FILES = 0x905e63b6_c1bf_494e_b29c_65b732d3d21a;
+    /// The Program Files folder for 32-bit programs on 64-bit Windows.
+    FOLDERID_PROGRAM_FILES_X86 = 0x7c5a40ef_a0fb_4bfc_874a_c0f2e0b9fa8e;
+    /// The Windows folder, `%windir%`.
+    FOLDERID_WINDOWS = 0xf38bf404_1d43_42f2_9305_67de0b28fc23;
+    /// The System32 folder.
+    FOLDERID_SYSTEM = 0x1ac14e77_02e7_4e5d_b744_2eb1ae5198b7;
+    /// The folder of 32-bit system files on 64-bit Windows, SysWOW64.
+    FOLDERID_SYSTEM_X86 = 0xd65231b0_b2f1_4857_a4ce_a8e7c6ea7d27;
+
+    /// The properties of files in the shell's storage property set, such as
+    /// `System.DateModified`.
+    FMTID_STORAGE = 0xb725f130_47ef_101a_a5f1_02608c9eebac;
+    /// The summary information property set of documents, such as
+    /// `System.Title` and `System.Author`.
+    FMTID_SUMMARY_INFORMATION = 0xf29f85e0_4ff9_1068_ab91_08002b27b3d9;
+    /// The document summary information property set.
+    FMTID_DOC_SUMMARY_INFORMATION = 0xd5cdd502_2e9c_101b_9397_08002b2cf9ae;
+    /// Property sets whose properties are named by strings rather than
+    /// integers.
+    FMTID_USER_DEFINED_PROPERTIES = 0xd5cdd505_2e9c_101b_9397_08002b2cf9ae;
+    /// The System.AppUserModel properties, such as `System.AppUserModel.ID`.
+    FMTID_APP_USER_MODEL = 0x9f4c2855_9f79_4b39_a8d0_e1d42de1d5f3;
+    /// The properties of shell links, such as
+    /// `System.Link.TargetParsingPath`.
+    FMTID_LINK = 0xb9b4b3fc_2b51_4a42_b5d8_324146afcf25;
+}
+
+/// The name of a GUID in this module, such as `"FOLDERID_DOCUMENTS"`, or
+/// `None` if it is not one of them.
+pub fn name(guid: Guid) -> Option<&'static str> {
+    NAMES
+        .iter()
+        .find(|(known, _)| *known == guid)
+        .map(|(_, name)| *name)
+}
@@ -0,0 +1,219 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use byteorder::{ByteOrder, LE};
+use sha2::{Digest, Sha256};
+
+use crate::borrowed::Sections;
+use crate::{Error, ParseContext, ParseOptions};
+
+/// SHA-256 hashes of the raw bytes of each structure of a shell link, for
+/// finding shell links that share structures, such as those made by the
+/// same tool or on the same machine.
+///
+/// The hashes are of the bytes as stored, so they only change when the
+/// stored bytes do, whatever a later version of this crate makes of them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct StructureHashes {
+    file: Sha256Hash,
+    shell_link: Sha256Hash,
+    header: Sha256Hash,
+    link_target_id_list: Option<Sha256Hash>,
+    link_info: Option<Sha256Hash>,
+    extra_data: Vec<ExtraDataHash>,
+}
+
+impl StructureHashes {
+    /// Hash the file at `path`, which holds a shell link at its start.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::from_slice(&std::fs::read(path)?)
+    }
+
+    /// Hash `data`, which holds a shell link at its start.
+    pub fn from_slice(data: &[u8]) -> Result<Self, Error> {
+        Self::from_slice_with_options(data, &ParseOptions::default())
+    }
+
+    /// Hash `data`, which holds a shell link at its start, finding its
+    /// structures with the given options.
+    pub fn from_slice_with_options(data: &[u8], options: &ParseOptions) -> Result<Self, Error> {
+        let ctx = ParseContext::new(options);
+        let (_, sections) = Sections::locate(data, &ctx)?;
+        let hash = |range: std::ops::Range<usize>| Sha256Hash::of(&data[range]);
+
+        let mut extra_data = Vec::new();
+        let mut offset = sections.extra_data.start;
+        while offset < sections.extra_data.end {
+            let size = LE::read_u32(&data[offset..]) as usize;
+            extra_data.push(ExtraDataHash {
+                signature: LE::read_u32(&data[offset + 4..]),
+                offset,
+                hash: hash(offset..offset + size),
+            });
+            offset += size;
+        }
+
+        Ok(Self {
+            file: Sha256Hash::of(data),
+            shell_link: hash(0..sections.size),
+            header: hash(0..0x4c),
+            link_target_id_list: sections.link_target_id_list.map(hash),
+            link_info: sections.link_info.map(hash),
+            extra_data,
+        })
+    }
+
+    /// The hash of the whole data, including anything after the shell link.
+    pub fn file(&self) -> &Sha256Hash {
+        &self.file
+    }
+
+    /// The hash of the shell link, up to and including its TerminalBlock.
+    pub fn shell_link(&self) -> &Sha256Hash {
+        &self.shell_link
+    }
+
+    /// The hash of the ShellLinkHeader.
+    pub fn header(&self) -> &Sha256Hash {
+        &self.header
+    }
+
+    /// The hash of the LinkTargetIDList, including its size, if present.
+    pub fn link_target_id_list(&self) -> &Option<Sha256Hash> {
+        &self.link_target_id_list
+    }
+
+    /// The hash of the LinkInfo, if present.
+    pub fn link_info(&self) -> &Option<Sha256Hash> {
+        &self.link_info
+    }
+
+    /// The hashes of the ExtraData blocks, in the order they are stored.
+    pub fn extra_data(&self) -> &Vec<ExtraDataHash> {
+        &self.extra_data
+    }
+}
+
+/// The hash of an ExtraData block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ExtraDataHash {
+    signature: u32,
+    offset: usize,
+    hash: Sha256Hash,
+}
+
+impl ExtraDataHash {
+    /// The BlockSignature of the block, which tells its type.
+    pub fn signature(&self) -> u32 {
+        self.signature
+    }
+
+    /// The offset of the block from the start of the shell link.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The hash of the block, including its BlockSize and BlockSignature.
+    pub fn hash(&self) -> &Sha256Hash {
+        &self.hash
+    }
+}
+
+/// A SHA-256 hash.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Sha256Hash([u8; 32]);
+
+impl Sha256Hash {
+    /// Hash `data`.
+    pub fn of(data: &[u8]) -> Self {
+        Self(Sha256::digest(data).into())
+    }
+
+    /// The 32 bytes of the hash.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl fmt::Display for Sha256Hash {
+    /// Write the hash as 64 lower case hexadecimal digits.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for b in self.0 {
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Sha256Hash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl FromStr for Sha256Hash {
+    type Err = ParseHashError;
+
+    /// Parse a hash from 64 hexadecimal digits, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseHashError(s.to_string());
+        if s.len() != 64 || !s.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(err());
+        }
+        let mut bytes = [0u8; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).map_err(|_| err())?;
+        }
+        Ok(Self(bytes))
+    }
+}
+
+/// The error returned when a hash cannot be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseHashError(String);
+
+impl fmt::Display for ParseHashError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid SHA-256 hash: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseHashError {}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Sha256Hash {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Sha256Hash {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Sha256Hash {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "Sha256Hash".into()
+    }
+
+    fn schema_id() -> std::borrow::Cow<'static, str> {
+        concat!(module_path!(), "::Sha256Hash").into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "pattern": "^[0-9A-Fa-f]{64}$",
+        })
+    }
+}
@@ -1,18 +1,28 @@
+#[allow(unused)]
+use crate::logging::{debug, error, info, trace, warn};
 use bitflags::bitflags;
 use byteorder::{ByteOrder, LE};
 use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
 
 use std::convert::TryFrom;
+use std::fmt;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
 
-use crate::FileTime;
+use crate::{FileTime, Guid, ParseContext};
 
-const CLSID: u128 = 0x460000000000_00c0_0000_0000_00021401;
+/// The class identifier (CLSID) that every shell link header MUST contain.
+pub(crate) const CLSID: Guid = crate::guids::CLSID_SHELL_LINK;
 
 /// A ShellLinkHeader structure (section 2.1), which contains identification
 /// information, timestamps, and flags that specify the presence of optional
 /// structures.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ShellLinkHeader {
     /// A LinkFlags structure (section 2.1.1) that specifies information about the shell link and
     /// the presence of optional portions of the structure.
@@ -41,14 +51,24 @@ pub struct ShellLinkHeader {
     /// A 32-bit unsigned integer that specifies the expected window state of an application
     /// launched by the link.
     show_command: ShowCommand,
+    /// The show command value as stored in the file, which may be a value other than those
+    /// represented by [`ShowCommand`].
+    show_command_raw: u32,
     /// A HotkeyFlags structure (section 2.1.3) that specifies the keystrokes used to launch the
     /// application referenced by the shortcut key. This value is assigned to the application after
     /// it is launched, so that pressing the key activates that application.
     hotkey: HotkeyFlags,
+    /// A value that MUST be zero.
+    reserved1: u16,
+    /// A value that MUST be zero.
+    reserved2: u32,
+    /// A value that MUST be zero.
+    reserved3: u32,
 }
 
 impl ShellLinkHeader {
-    /// Get the link flags
+    /// Get the link flags. Bits that are not defined by the specification are kept, and can be
+    /// read with [`LinkFlags::bits`].
     pub fn link_flags(&self) -> &LinkFlags {
         &self.link_flags
     }
@@ -63,7 +83,8 @@ impl ShellLinkHeader {
         self.link_flags.set(link_flags, value);
     }
 
-    /// Get the file attributes
+    /// Get the file attributes. Attributes that are not known to this crate are kept, and can
+    /// be read with [`FileAttributeFlags::bits`].
     pub fn file_attributes(&self) -> &FileAttributeFlags {
         &self.file_attributes
     }
@@ -78,9 +99,10 @@ impl ShellLinkHeader {
         self.creation_time
     }
 
-    /// Set the file creation time
-    pub fn set_creation_time(&mut self, creation_time: FileTime) {
-        self.creation_time = creation_time;
+    /// Set the file creation time. Accepts a [`FileTime`], or anything that
+    /// converts to one, such as a `SystemTime` or `DateTime<Utc>`.
+    pub fn set_creation_time<T: Into<FileTime>>(&mut self, creation_time: T) {
+        self.creation_time = creation_time.into();
     }
 
     /// Get the file access time
@@ -88,9 +110,10 @@ impl ShellLinkHeader {
         self.access_time
     }
 
-    /// Set the file access time
-    pub fn set_access_time(&mut self, access_time: FileTime) {
-        self.access_time = access_time;
+    /// Set the file access time. Accepts a [`FileTime`], or anything that
+    /// converts to one, such as a `SystemTime` or `DateTime<Utc>`.
+    pub fn set_access_time<T: Into<FileTime>>(&mut self, access_time: T) {
+        self.access_time = access_time.into();
     }
 
     /// Get the file write time
@@ -98,9 +121,10 @@ impl ShellLinkHeader {
         self.write_time
     }
 
-    /// Set the file write time
-    pub fn set_write_time(&mut self, write_time: FileTime) {
-        self.write_time = write_time;
+    /// Set the file write time. Accepts a [`FileTime`], or anything that
+    /// converts to one, such as a `SystemTime` or `DateTime<Utc>`.
+    pub fn set_write_time<T: Into<FileTime>>(&mut self, write_time: T) {
+        self.write_time = write_time.into();
     }
 
     /// The file size, or at least the least significant 32-bits of the
@@ -130,9 +154,17 @@ impl ShellLinkHeader {
         &self.show_command
     }
 
+    /// Get the show command value as stored in the file. All values other than those in
+    /// [`ShowCommand`] are treated as [`ShowCommand::ShowNormal`], but are kept here so that
+    /// they can be inspected and written back unchanged.
+    pub fn show_command_raw(&self) -> u32 {
+        self.show_command_raw
+    }
+
     /// Set the shortcut show command
     pub fn set_show_command(&mut self, show_command: ShowCommand) {
         self.show_command = show_command;
+        self.show_command_raw = show_command as u32;
     }
 
     /// Get the hotkey flags
@@ -144,43 +176,106 @@ impl ShellLinkHeader {
     pub fn hotkey_mut(&mut self) -> &mut HotkeyFlags {
         &mut self.hotkey
     }
+
+    /// Get the first reserved value, a 16-bit value that MUST be zero
+    pub fn reserved1(&self) -> u16 {
+        self.reserved1
+    }
+
+    /// Get the second reserved value, a 32-bit value that MUST be zero
+    pub fn reserved2(&self) -> u32 {
+        self.reserved2
+    }
+
+    /// Get the third reserved value, a 32-bit value that MUST be zero
+    pub fn reserved3(&self) -> u32 {
+        self.reserved3
+    }
+
+    /// Check that all of the reserved values are zero, as the specification requires. Links
+    /// with non-zero reserved values were not written by Windows, and may have been tampered
+    /// with.
+    pub fn reserved_are_zero(&self) -> bool {
+        self.reserved1 == 0 && self.reserved2 == 0 && self.reserved3 == 0
+    }
 }
 
 impl Default for ShellLinkHeader {
-    /// Create a new, blank, ShellLinkHeader
+    /// Create a new, blank, ShellLinkHeader with its times set to now
     fn default() -> Self {
         Self {
-            link_flags: LinkFlags::IS_UNICODE,
-            file_attributes: FileAttributeFlags::FILE_ATTRIBUTE_NORMAL,
             creation_time: FileTime::now(),
             access_time: FileTime::now(),
             write_time: FileTime::now(),
+            ..Self::blank()
+        }
+    }
+}
+
+impl ShellLinkHeader {
+    /// A blank ShellLinkHeader with no times set. Unlike [`Default`], this
+    /// does not read the clock, which is not available on every target
+    /// (such as `wasm32-unknown-unknown`).
+    pub(crate) fn blank() -> Self {
+        let unset = FileTime::default();
+        Self {
+            link_flags: LinkFlags::IS_UNICODE,
+            file_attributes: FileAttributeFlags::FILE_ATTRIBUTE_NORMAL,
+            creation_time: unset,
+            access_time: unset,
+            write_time: unset,
             file_size: 0,
             icon_index: 0,
             show_command: ShowCommand::ShowNormal,
+            show_command_raw: ShowCommand::ShowNormal as u32,
             hotkey: HotkeyFlags::new(HotkeyKey::NoKeyAssigned, HotkeyModifiers::NO_MODIFIER),
+            reserved1: 0,
+            reserved2: 0,
+            reserved3: 0,
         }
     }
 }
 
-impl Into<[u8; 0x4c]> for ShellLinkHeader {
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for ShellLinkHeader {
+    /// Generate a header with only defined flags set, a ShowCommand that is
+    /// one of the defined values and the reserved fields zero.
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let show_command: ShowCommand = u.arbitrary()?;
+        Ok(Self {
+            link_flags: u.arbitrary()?,
+            file_attributes: u.arbitrary()?,
+            creation_time: u.arbitrary()?,
+            access_time: u.arbitrary()?,
+            write_time: u.arbitrary()?,
+            file_size: u.arbitrary()?,
+            icon_index: u.arbitrary()?,
+            show_command,
+            show_command_raw: show_command as u32,
+            hotkey: u.arbitrary()?,
+            ..Self::blank()
+        })
+    }
+}
+
+impl From<ShellLinkHeader> for [u8; 0x4c] {
     /// Write the data in this header to a `[u8]` for writing to the output file.
-    fn into(self) -> [u8; 0x4c] {
+    fn from(val: ShellLinkHeader) -> Self {
         let mut header_data = [0u8; 0x4c];
         LE::write_u32(&mut header_data[0..], 0x4c);
-        LE::write_u128(&mut header_data[4..], CLSID);
-        LE::write_u32(&mut header_data[20..], self.link_flags.bits);
-        LE::write_u32(&mut header_data[24..], self.file_attributes.bits);
-        LE::write_u64(&mut header_data[28..], self.creation_time.into());
-        LE::write_u64(&mut header_data[36..], self.access_time.into());
-        LE::write_u64(&mut header_data[44..], self.write_time.into());
-        LE::write_u32(&mut header_data[52..], self.file_size);
-        LE::write_i32(&mut header_data[56..], self.icon_index);
-        LE::write_u32(&mut header_data[60..], self.show_command as u32);
-        LE::write_u16(&mut header_data[64..], self.hotkey.to_flags_u16());
-        LE::write_u16(&mut header_data[66..], 0);
-        LE::write_u32(&mut header_data[68..], 0);
-        LE::write_u32(&mut header_data[72..], 0);
+        header_data[4..20].copy_from_slice(&CLSID.to_bytes_le());
+        LE::write_u32(&mut header_data[20..], val.link_flags.bits());
+        LE::write_u32(&mut header_data[24..], val.file_attributes.bits());
+        LE::write_u64(&mut header_data[28..], val.creation_time.into());
+        LE::write_u64(&mut header_data[36..], val.access_time.into());
+        LE::write_u64(&mut header_data[44..], val.write_time.into());
+        LE::write_u32(&mut header_data[52..], val.file_size);
+        LE::write_i32(&mut header_data[56..], val.icon_index);
+        LE::write_u32(&mut header_data[60..], val.show_command_raw);
+        LE::write_u16(&mut header_data[64..], val.hotkey.to_flags_u16());
+        LE::write_u16(&mut header_data[66..], val.reserved1);
+        LE::write_u32(&mut header_data[68..], val.reserved2);
+        LE::write_u32(&mut header_data[72..], val.reserved3);
         header_data
     }
 }
@@ -191,23 +286,121 @@ impl TryFrom<&[u8]> for ShellLinkHeader {
     /// Read data into this struct from a `[u8]`.
     /// Returns an error when the magic number is not valid.
     fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
-        let mut header = Self::default();
+        Self::parse(data, &ParseContext::default())
+    }
+}
+
+impl ShellLinkHeader {
+    /// Open a shell link and parse only its header, reading no more than the
+    /// 0x4C bytes it occupies. This is much cheaper than
+    /// [`ShellLink::open`](crate::ShellLink::open) when only the flags,
+    /// timestamps or show command are needed.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, crate::Error> {
+        Self::from_reader(File::open(path)?)
+    }
+
+    /// Parse a shell link header from a reader, reading exactly the 0x4C
+    /// bytes it occupies.
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self, crate::Error> {
+        let mut data = Vec::with_capacity(0x4c);
+        reader.take(0x4c).read_to_end(&mut data)?;
+        Self::parse(&data, &ParseContext::default())
+    }
+
+    /// Whether `data` starts with the HeaderSize and LinkCLSID of a shell
+    /// link header. Only the first 20 bytes are looked at, so this is a cheap
+    /// way to filter out files that are not shell links.
+    pub fn has_signature(data: &[u8]) -> bool {
+        data.len() >= 20 && LE::read_u32(data) == 0x4c && Guid::read(&data[4..]) == CLSID
+    }
 
-        if LE::read_u32(&data[0..]) != 0x4c {
+    /// Parse a ShellLinkHeader with the given parsing context.
+    pub(crate) fn parse(data: &[u8], ctx: &ParseContext) -> Result<Self, crate::Error> {
+        let mut header = Self::blank();
+
+        if data.len() < 0x4c {
             return Err(crate::Error::NotAShellLinkError);
         }
-        if LE::read_u128(&data[4..]) != CLSID {
-            return Err(crate::Error::NotAShellLinkError);
+        if !Self::has_signature(data) {
+            if !ctx.options.ignore_signature {
+                return Err(crate::Error::NotAShellLinkError);
+            }
+            let header_size = LE::read_u32(data);
+            if header_size != 0x4c {
+                ctx.nonconformance(
+                    0,
+                    format!(
+                        "The HeaderSize is 0x{:x}, not 0x4c; the header was read as though it were 0x4c",
+                        header_size
+                    ),
+                )?;
+            }
+            let clsid = Guid::read(&data[4..]);
+            if clsid != CLSID {
+                ctx.nonconformance(
+                    4,
+                    format!("The LinkCLSID is {}, not that of a shell link", clsid),
+                )?;
+            }
+        }
+        header.link_flags = LinkFlags::from_bits_retain(LE::read_u32(&data[20..]));
+        if LinkFlags::from_bits(header.link_flags.bits()).is_none() {
+            ctx.nonconformance(
+                20,
+                format!(
+                    "Unknown link flags are set: 0x{:x}",
+                    header.link_flags.bits() & !LinkFlags::all().bits()
+                ),
+            )?;
+        }
+        header.file_attributes = FileAttributeFlags::from_bits_retain(LE::read_u32(&data[24..]));
+        if FileAttributeFlags::from_bits(header.file_attributes.bits()).is_none() {
+            ctx.nonconformance(
+                24,
+                format!(
+                    "Unknown file attributes are set: 0x{:x}",
+                    header.file_attributes.bits() & !FileAttributeFlags::all().bits()
+                ),
+            )?;
         }
-        header.link_flags = LinkFlags::from_bits_truncate(LE::read_u32(&data[20..]));
-        header.file_attributes = FileAttributeFlags::from_bits_truncate(LE::read_u32(&data[24..]));
         header.creation_time = FileTime::from(LE::read_u64(&data[28..]));
         header.access_time = FileTime::from(LE::read_u64(&data[36..]));
         header.write_time = FileTime::from(LE::read_u64(&data[44..]));
+        for (offset, name, time) in [
+            (28, "creation", header.creation_time),
+            (36, "access", header.access_time),
+            (44, "write", header.write_time),
+        ] {
+            if !time.is_valid() {
+                ctx.nonconformance(
+                    offset,
+                    format!("The {} time is not a valid FILETIME: {:?}", name, time),
+                )?;
+            }
+        }
         header.file_size = LE::read_u32(&data[52..]);
         header.icon_index = LE::read_i32(&data[56..]);
-        header.show_command = FromPrimitive::from_u32(LE::read_u32(&data[60..])).unwrap();
+        header.show_command_raw = LE::read_u32(&data[60..]);
+        header.show_command = ShowCommand::from_u32(header.show_command_raw).unwrap_or_else(|| {
+            debug!(
+                "Show command 0x{:x} is treated as ShowNormal",
+                header.show_command_raw
+            );
+            ShowCommand::ShowNormal
+        });
         header.hotkey = HotkeyFlags::from_bits(LE::read_u16(&data[64..]));
+        header.reserved1 = LE::read_u16(&data[66..]);
+        header.reserved2 = LE::read_u32(&data[68..]);
+        header.reserved3 = LE::read_u32(&data[72..]);
+        if !header.reserved_are_zero() {
+            ctx.nonconformance(
+                66,
+                format!(
+                    "The header reserved values are not zero: {:x}, {:x}, {:x}",
+                    header.reserved1, header.reserved2, header.reserved3
+                ),
+            )?;
+        }
 
         Ok(header)
     }
@@ -216,6 +409,7 @@ impl TryFrom<&[u8]> for ShellLinkHeader {
 bitflags! {
     /// The LinkFlags structure defines bits that specify which shell linkstructures are present in
     /// the file format after the ShellLinkHeaderstructure (section 2.1).
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
     pub struct LinkFlags: u32 {
         /// The shell link is saved with an item ID list (IDList). If this bit is set, a
         /// LinkTargetIDList structure (section 2.2) MUST follow the ShellLinkHeader. If this bit
@@ -308,6 +502,7 @@ bitflags! {
     /// target, if the target is a file system item. File attributes can be used if the link target
     /// is not available, or if accessing the target would be inefficient. It is possible for the
     /// target items attributes to be out of sync with this value.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
     pub struct FileAttributeFlags: u32 {
         /// The file or directory is read-only. For a file, if this bit is set, applications can read the file but cannot write to it or delete it. For a directory, if this bit is set, applications cannot delete the directory
         const FILE_ATTRIBUTE_READONLY               = 0b0000_0000_0000_0000_0000_0000_0000_0001;
@@ -343,9 +538,69 @@ bitflags! {
         const FILE_ATTRIBUTE_NOT_CONTENT_INDEXED    = 0b0000_0000_0000_0000_0010_0000_0000_0000;
         /// The file or directory is encrypted. For a file, this means that all data in the file is encrypted. For a directory, this means that encryption is the default for newly created files and subdirectories.
         const FILE_ATTRIBUTE_ENCRYPTED              = 0b0000_0000_0000_0000_0100_0000_0000_0000;
+        /// The directory or user data stream is configured with integrity. This attribute is
+        /// not defined by the Shell Link specification, but is written by newer versions of
+        /// Windows.
+        const FILE_ATTRIBUTE_INTEGRITY_STREAM       = 0b0000_0000_0000_0000_1000_0000_0000_0000;
+        /// This value is reserved for system use.
+        const FILE_ATTRIBUTE_VIRTUAL                = 0b0000_0000_0000_0001_0000_0000_0000_0000;
+        /// The user data stream is not to be read by the background data integrity scanner.
+        /// This attribute is not defined by the Shell Link specification, but is written by
+        /// newer versions of Windows.
+        const FILE_ATTRIBUTE_NO_SCRUB_DATA          = 0b0000_0000_0000_0010_0000_0000_0000_0000;
+        /// The file or directory has no physical representation on the local system, and the
+        /// item is virtual.
+        const FILE_ATTRIBUTE_RECALL_ON_OPEN         = 0b0000_0000_0000_0100_0000_0000_0000_0000;
+        /// The file or directory should be kept fully present locally even when not being
+        /// actively accessed.
+        const FILE_ATTRIBUTE_PINNED                 = 0b0000_0000_0000_1000_0000_0000_0000_0000;
+        /// The file or directory should not be kept fully present locally except when being
+        /// actively accessed.
+        const FILE_ATTRIBUTE_UNPINNED               = 0b0000_0000_0001_0000_0000_0000_0000_0000;
+        /// The file or directory is not fully present locally.
+        const FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS  = 0b0000_0000_0100_0000_0000_0000_0000_0000;
     }
 }
 
+macro_rules! impl_flag_names {
+    ($($flags:ty),* $(,)?) => {
+        $(
+            impl $flags {
+                /// The names of the set flags, in the order of their bits. Bits that are not
+                /// defined by the specification have no name and are left out.
+                pub fn names(&self) -> impl Iterator<Item = &'static str> {
+                    self.iter_names().map(|(name, _)| name)
+                }
+
+                /// The names of every flag defined by the specification, in the order of their
+                /// bits.
+                pub fn all_names() -> impl Iterator<Item = &'static str> {
+                    Self::all().names()
+                }
+
+                /// Look up a flag by its name, ignoring case and underscores, so that both the
+                /// name of the constant, such as `HAS_LINK_INFO`, and the name used in the
+                /// specification, such as `HasLinkInfo`, are accepted.
+                pub fn from_flag_name(name: &str) -> Option<Self> {
+                    let normalize = |name: &str| {
+                        name.chars()
+                            .filter(|c| *c != '_')
+                            .map(|c| c.to_ascii_lowercase())
+                            .collect::<String>()
+                    };
+                    let name = normalize(name);
+                    Self::all()
+                        .iter_names()
+                        .find(|(flag, _)| normalize(flag) == name)
+                        .map(|(_, flag)| flag)
+                }
+            }
+        )*
+    };
+}
+
+impl_flag_names!(LinkFlags, FileAttributeFlags);
+
 /// The HotkeyFlags structure specifies input generated by a combination of keyboard keys being
 /// pressed.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
@@ -365,13 +620,13 @@ impl HotkeyFlags {
 
     /// Convert these HotkeyFlags to the u16 representation for saving.
     fn to_flags_u16(self) -> u16 {
-        self.low_byte as u16 + ((self.high_byte.bits as u16) << 8)
+        u16::from(u8::from(self.low_byte)) + ((self.high_byte.bits() as u16) << 8)
     }
 
     /// Convert a u16 representation back into a set of HotkeyFlags.
-    fn from_bits(bits: u16) -> Self {
+    pub(crate) fn from_bits(bits: u16) -> Self {
         Self {
-            low_byte: FromPrimitive::from_u16(bits & 0b1111_1111).unwrap(),
+            low_byte: HotkeyKey::from((bits & 0b1111_1111) as u8),
             high_byte: HotkeyModifiers::from_bits_truncate((bits >> 8) as u8),
         }
     }
@@ -397,79 +652,298 @@ impl HotkeyFlags {
     }
 }
 
-#[allow(missing_docs)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, FromPrimitive)]
-/// An 8-bit unsigned integer that specifies a virtual key code that corresponds to a key on the
-/// keyboard.
-pub enum HotkeyKey {
+const HOTKEY_MODIFIER_NAMES: [(HotkeyModifiers, &str); 4] = [
+    (HotkeyModifiers::HOTKEYF_CONTROL, "Ctrl"),
+    (HotkeyModifiers::HOTKEYF_SHIFT, "Shift"),
+    (HotkeyModifiers::HOTKEYF_ALT, "Alt"),
+    (HotkeyModifiers::HOTKEYF_EXT, "Ext"),
+];
+
+impl fmt::Display for HotkeyFlags {
+    /// Write the hotkey in the form `Ctrl+Alt+F5`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in HOTKEY_MODIFIER_NAMES {
+            if self.high_byte.contains(modifier) {
+                write!(f, "{}+", name)?;
+            }
+        }
+        write!(f, "{}", self.low_byte)
+    }
+}
+
+impl FromStr for HotkeyFlags {
+    type Err = ParseHotkeyError;
+
+    /// Parse a hotkey in the form `Ctrl+Alt+F5`, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts: Vec<&str> = s.split('+').map(str::trim).collect();
+        let key = parts.pop().unwrap_or_default().parse()?;
+        let mut modifiers = HotkeyModifiers::NO_MODIFIER;
+        for part in parts {
+            let (modifier, _) = HOTKEY_MODIFIER_NAMES
+                .iter()
+                .find(|(_, name)| name.eq_ignore_ascii_case(part))
+                .ok_or_else(|| ParseHotkeyError(s.to_string()))?;
+            modifiers |= *modifier;
+        }
+        Ok(Self::new(key, modifiers))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for HotkeyFlags {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for HotkeyFlags {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for HotkeyFlags {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "HotkeyFlags".into()
+    }
+
+    fn schema_id() -> std::borrow::Cow<'static, str> {
+        concat!(module_path!(), "::HotkeyFlags").into()
+    }
+
+    /// A string in the form `Ctrl+Alt+F5`.
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "examples": ["Ctrl+Alt+F5"],
+        })
+    }
+}
+
+macro_rules! hotkey_keys {
+    ($($name:ident = $value:expr,)*) => {
+        #[allow(missing_docs)]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+        /// An 8-bit unsigned integer that specifies a virtual key code that corresponds to a key
+        /// on the keyboard.
+        pub enum HotkeyKey {
+            $($name,)*
+            /// A virtual key code that does not correspond to any of the other variants.
+            Unknown(u8),
+        }
+
+        impl From<u8> for HotkeyKey {
+            fn from(value: u8) -> Self {
+                match value {
+                    $($value => Self::$name,)*
+                    _ => Self::Unknown(value),
+                }
+            }
+        }
+
+        impl From<HotkeyKey> for u8 {
+            fn from(key: HotkeyKey) -> Self {
+                match key {
+                    $(HotkeyKey::$name => $value,)*
+                    HotkeyKey::Unknown(value) => value,
+                }
+            }
+        }
+    };
+}
+
+hotkey_keys! {
     NoKeyAssigned = 0x00,
+    Backspace = 0x08,
+    Tab = 0x09,
+    Clear = 0x0c,
+    Return = 0x0d,
+    Pause = 0x13,
+    CapsLock = 0x14,
+    Escape = 0x1b,
+    Space = 0x20,
+    PageUp = 0x21,
+    PageDown = 0x22,
+    End = 0x23,
+    Home = 0x24,
+    Left = 0x25,
+    Up = 0x26,
+    Right = 0x27,
+    Down = 0x28,
+    Select = 0x29,
+    Print = 0x2a,
+    Execute = 0x2b,
+    PrintScreen = 0x2c,
+    Insert = 0x2d,
+    Delete = 0x2e,
+    Help = 0x2f,
     Key0 = 0x30,
-    Key1,
-    Key2,
-    Key3,
-    Key4,
-    Key5,
-    Key6,
-    Key7,
-    Key8,
-    Key9,
+    Key1 = 0x31,
+    Key2 = 0x32,
+    Key3 = 0x33,
+    Key4 = 0x34,
+    Key5 = 0x35,
+    Key6 = 0x36,
+    Key7 = 0x37,
+    Key8 = 0x38,
+    Key9 = 0x39,
     KeyA = 0x41,
-    KeyB,
-    KeyC,
-    KeyD,
-    KeyE,
-    KeyF,
-    KeyG,
-    KeyH,
-    KeyI,
-    KeyJ,
-    KeyK,
-    KeyL,
-    KeyM,
-    KeyN,
-    KeyO,
-    KeyP,
-    KeyQ,
-    KeyR,
-    KeyS,
-    KeyT,
-    KeyU,
-    KeyV,
-    KeyW,
-    KeyX,
-    KeyY,
-    KeyZ,
+    KeyB = 0x42,
+    KeyC = 0x43,
+    KeyD = 0x44,
+    KeyE = 0x45,
+    KeyF = 0x46,
+    KeyG = 0x47,
+    KeyH = 0x48,
+    KeyI = 0x49,
+    KeyJ = 0x4a,
+    KeyK = 0x4b,
+    KeyL = 0x4c,
+    KeyM = 0x4d,
+    KeyN = 0x4e,
+    KeyO = 0x4f,
+    KeyP = 0x50,
+    KeyQ = 0x51,
+    KeyR = 0x52,
+    KeyS = 0x53,
+    KeyT = 0x54,
+    KeyU = 0x55,
+    KeyV = 0x56,
+    KeyW = 0x57,
+    KeyX = 0x58,
+    KeyY = 0x59,
+    KeyZ = 0x5a,
+    Numpad0 = 0x60,
+    Numpad1 = 0x61,
+    Numpad2 = 0x62,
+    Numpad3 = 0x63,
+    Numpad4 = 0x64,
+    Numpad5 = 0x65,
+    Numpad6 = 0x66,
+    Numpad7 = 0x67,
+    Numpad8 = 0x68,
+    Numpad9 = 0x69,
+    Multiply = 0x6a,
+    Add = 0x6b,
+    Separator = 0x6c,
+    Subtract = 0x6d,
+    Decimal = 0x6e,
+    Divide = 0x6f,
     F1 = 0x70,
-    F2,
-    F3,
-    F4,
-    F5,
-    F6,
-    F7,
-    F8,
-    F9,
-    F10,
-    F11,
-    F12,
-    F13,
-    F14,
-    F15,
-    F16,
-    F17,
-    F18,
-    F19,
-    F20,
-    F21,
-    F22,
-    F23,
-    F24,
+    F2 = 0x71,
+    F3 = 0x72,
+    F4 = 0x73,
+    F5 = 0x74,
+    F6 = 0x75,
+    F7 = 0x76,
+    F8 = 0x77,
+    F9 = 0x78,
+    F10 = 0x79,
+    F11 = 0x7a,
+    F12 = 0x7b,
+    F13 = 0x7c,
+    F14 = 0x7d,
+    F15 = 0x7e,
+    F16 = 0x7f,
+    F17 = 0x80,
+    F18 = 0x81,
+    F19 = 0x82,
+    F20 = 0x83,
+    F21 = 0x84,
+    F22 = 0x85,
+    F23 = 0x86,
+    F24 = 0x87,
     NumLock = 0x90,
-    ScrollLock,
+    ScrollLock = 0x91,
+    BrowserBack = 0xa6,
+    BrowserForward = 0xa7,
+    BrowserRefresh = 0xa8,
+    BrowserStop = 0xa9,
+    BrowserSearch = 0xaa,
+    BrowserFavorites = 0xab,
+    BrowserHome = 0xac,
+    VolumeMute = 0xad,
+    VolumeDown = 0xae,
+    VolumeUp = 0xaf,
+    MediaNextTrack = 0xb0,
+    MediaPrevTrack = 0xb1,
+    MediaStop = 0xb2,
+    MediaPlayPause = 0xb3,
+    LaunchMail = 0xb4,
+    LaunchMediaSelect = 0xb5,
+    LaunchApp1 = 0xb6,
+    LaunchApp2 = 0xb7,
+    Oem1 = 0xba,
+    OemPlus = 0xbb,
+    OemComma = 0xbc,
+    OemMinus = 0xbd,
+    OemPeriod = 0xbe,
+    Oem2 = 0xbf,
+    Oem3 = 0xc0,
+    Oem4 = 0xdb,
+    Oem5 = 0xdc,
+    Oem6 = 0xdd,
+    Oem7 = 0xde,
+    Oem8 = 0xdf,
+    Oem102 = 0xe2,
+}
+
+impl fmt::Display for HotkeyKey {
+    /// Write the name of the key, as printed on the keyboard where possible.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoKeyAssigned => write!(f, "None"),
+            Self::Unknown(value) => write!(f, "0x{:02X}", value),
+            key => {
+                let name = format!("{:?}", key);
+                match name.strip_prefix("Key") {
+                    Some(printed) => write!(f, "{}", printed),
+                    None => write!(f, "{}", name),
+                }
+            }
+        }
+    }
 }
 
+impl FromStr for HotkeyKey {
+    type Err = ParseHotkeyError;
+
+    /// Parse a key name as produced by the `Display` implementation, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            return u8::from_str_radix(hex, 16)
+                .map(Self::from)
+                .map_err(|_| ParseHotkeyError(s.to_string()));
+        }
+        (0..=u8::MAX)
+            .map(Self::from)
+            .find(|key| key.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ParseHotkeyError(s.to_string()))
+    }
+}
+
+/// The error returned when a hotkey or hotkey key name cannot be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseHotkeyError(String);
+
+impl fmt::Display for ParseHotkeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid hotkey: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseHotkeyError {}
+
 bitflags! {
     /// An 8-bit unsigned integer that specifies bits that correspond to modifier keys on the
     /// keyboard.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
     pub struct HotkeyModifiers: u8 {
         /// No modifier key is being used.
         const NO_MODIFIER       = 0x00;
@@ -479,11 +953,18 @@ bitflags! {
         const HOTKEYF_CONTROL   = 0x02;
         /// The "ALT" key on the keyboard.
         const HOTKEYF_ALT       = 0x04;
+        /// The key is an extended key, such as a key on the navigation cluster or numeric
+        /// keypad of an enhanced keyboard.
+        const HOTKEYF_EXT       = 0x08;
     }
 }
 
-/// The expected window state of an application launched by the link.
+/// The expected window state of an application launched by the link. All values other than
+/// these MUST be treated as `ShowNormal`.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, FromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ShowCommand {
     /// The application is open and its window is open in a normal fashion.
     ShowNormal = 0x01,
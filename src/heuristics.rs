@@ -0,0 +1,198 @@
+use crate::analysis::{Finding, Indicator, Severity};
+use crate::ShellLink;
+
+/// The shortest run of base64 characters in the command line that is
+/// reported as a blob.
+const BASE64_THRESHOLD: usize = 64;
+
+/// The most characters of a decoded command given in a finding.
+const PREVIEW_LENGTH: usize = 200;
+
+/// Programs signed by Microsoft that run code given on their command line,
+/// with what in their arguments shows that they are being used to. These
+/// are the MITRE ATT&CK techniques T1218.005, T1218.011 and T1218.010.
+const PROXY_EXECUTION: &[(&str, &[&str])] = &[
+    (
+        "mshta",
+        &[
+            "http:",
+            "https:",
+            "javascript:",
+            "vbscript:",
+            "about:",
+            ".hta",
+        ],
+    ),
+    (
+        "rundll32",
+        &[
+            "javascript:",
+            "url.dll",
+            "shellexec_rundll",
+            "advpack.dll",
+            "ieadvpack.dll",
+            "zipfldr.dll",
+            "pcwutl.dll",
+            "\\\\",
+        ],
+    ),
+    (
+        "regsvr32",
+        &["scrobj.dll", "/i:http", "-i:http", "/i:\\\\", "-i:\\\\"],
+    ),
+];
+
+/// The schemes of URLs reported in the command line.
+const URL_SCHEMES: &[&str] = &["http://", "https://", "ftp://"];
+
+impl ShellLink {
+    /// Look for command lines used by malicious shell links in the target
+    /// and arguments: PowerShell given an encoded command, signed Windows
+    /// programs used to run scripts, URLs and long base64 blobs. These are
+    /// among the findings of [`ShellLink::analyze`].
+    pub fn command_line_threats(&self) -> Vec<Finding> {
+        let mut command_line = self.target_candidates().join(" ");
+        if let Some(arguments) = self.arguments() {
+            command_line.push(' ');
+            command_line.push_str(arguments);
+        }
+        let lower = command_line.to_lowercase();
+        let mut findings = Vec::new();
+
+        let tokens: Vec<&str> = command_line
+            .split(|c: char| c.is_whitespace() || "\"'(),;".contains(c))
+            .filter(|token| !token.is_empty())
+            .collect();
+        let mut encoded = None;
+        if lower.contains("powershell") || lower.contains("pwsh") {
+            encoded = tokens
+                .windows(2)
+                .find(|pair| is_encoded_command_parameter(pair[0]))
+                .map(|pair| pair[1]);
+        }
+        if let Some(encoded) = encoded {
+            let description = match decode_command(encoded) {
+                Some(command) => format!(
+                    "PowerShell is given an encoded command, which decodes to {:?}",
+                    command.chars().take(PREVIEW_LENGTH).collect::<String>()
+                ),
+                None => format!(
+                    "PowerShell is given an encoded command, {:?}, which is not valid",
+                    encoded
+                ),
+            };
+            findings.push(Finding::new(
+                Severity::High,
+                Indicator::EncodedPowerShell,
+                description,
+            ));
+        }
+
+        for (program, markers) in PROXY_EXECUTION {
+            if !lower.contains(program) {
+                continue;
+            }
+            if let Some(marker) = markers.iter().find(|marker| lower.contains(*marker)) {
+                findings.push(Finding::new(
+                    Severity::High,
+                    Indicator::ProxyExecution,
+                    format!(
+                        "{}.exe is run with {:?} in its command line, a way to run code through a signed Windows program",
+                        program, marker
+                    ),
+                ));
+            }
+        }
+
+        let urls: Vec<&str> = tokens
+            .iter()
+            .filter_map(|token| {
+                let start = URL_SCHEMES
+                    .iter()
+                    .filter_map(|scheme| token.to_lowercase().find(scheme))
+                    .min()?;
+                Some(&token[start..])
+            })
+            .collect();
+        if !urls.is_empty() {
+            findings.push(Finding::new(
+                Severity::Medium,
+                Indicator::EmbeddedUrl,
+                format!("the command line holds the URLs {}", urls.join(", ")),
+            ));
+        }
+
+        for token in tokens {
+            if Some(token) != encoded && is_base64_blob(token) {
+                findings.push(Finding::new(
+                    Severity::Medium,
+                    Indicator::Base64Blob,
+                    format!(
+                        "the command line holds {} characters of base64, starting {:?}",
+                        token.len(),
+                        &token[..16]
+                    ),
+                ));
+            }
+        }
+        findings
+    }
+}
+
+/// Whether a token is PowerShell's `-EncodedCommand` parameter, which can be
+/// shortened to any prefix of it.
+fn is_encoded_command_parameter(token: &str) -> bool {
+    let Some(name) = token.strip_prefix(['-', '/']) else {
+        return false;
+    };
+    !name.is_empty() && "encodedcommand".starts_with(&name.to_lowercase())
+}
+
+/// Whether a token is a long run of base64 characters, mixing upper and
+/// lower case letters and digits as encoded data does.
+fn is_base64_blob(token: &str) -> bool {
+    token.len() >= BASE64_THRESHOLD
+        && token
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"+/=".contains(&b))
+        && token.bytes().any(|b| b.is_ascii_uppercase())
+        && token.bytes().any(|b| b.is_ascii_lowercase())
+        && token.bytes().any(|b| b.is_ascii_digit())
+}
+
+/// Decode a command given to `-EncodedCommand`: UTF-16 text in base64.
+fn decode_command(encoded: &str) -> Option<String> {
+    let bytes = decode_base64(encoded)?;
+    if bytes.len() % 2 != 0 {
+        return None;
+    }
+    let units: Vec<u16> = bytes
+        .chunks(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    String::from_utf16(&units).ok()
+}
+
+/// Decode standard base64, with or without padding.
+fn decode_base64(encoded: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(encoded.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for b in encoded.trim_end_matches('=').bytes() {
+        let value = match b {
+            b'A'..=b'Z' => b - b'A',
+            b'a'..=b'z' => b - b'a' + 26,
+            b'0'..=b'9' => b - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        buffer = buffer << 6 | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Some(bytes)
+}
@@ -0,0 +1,250 @@
+#[allow(unused)]
+use crate::logging::{debug, error, info, trace, warn};
+
+use byteorder::{ByteOrder, LE};
+use std::fs::File;
+use std::io::{Read, Seek};
+use std::path::Path;
+
+use crate::{strings, Error, FileTime, Guid, ParseContext, ParseOptions, ParseWarning, ShellLink};
+
+/// The name of the stream holding the list of destinations.
+const DEST_LIST: &str = "DestList";
+
+/// An automatic jump list (`*.automaticDestinations-ms`), as kept by Windows
+/// for each application in
+/// `%APPDATA%\Microsoft\Windows\Recent\AutomaticDestinations`.
+///
+/// A jump list is an OLE compound file holding one stream per destination,
+/// named with the destination's entry number in hexadecimal, each of which
+/// is a shell link. The `DestList` stream records, for each entry, where its
+/// target was and when and how it was used.
+#[derive(Debug)]
+pub struct JumpList {
+    entries: Vec<JumpListEntry>,
+    warnings: Vec<ParseWarning>,
+}
+
+impl JumpList {
+    /// Open and parse a jump list.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::open_with_options(path, &ParseOptions::default())
+    }
+
+    /// Open and parse a jump list, parsing the shell links in it with the
+    /// given options.
+    pub fn open_with_options<P: AsRef<Path>>(
+        path: P,
+        options: &ParseOptions,
+    ) -> Result<Self, Error> {
+        debug!("Opening jump list {:?}", path.as_ref());
+        Self::from_reader_with_options(File::open(path)?, options)
+    }
+
+    /// Parse a jump list from a reader.
+    pub fn from_reader<R: Read + Seek>(reader: R) -> Result<Self, Error> {
+        Self::from_reader_with_options(reader, &ParseOptions::default())
+    }
+
+    /// Parse a jump list from a reader, parsing the shell links in it with
+    /// the given options.
+    ///
+    /// Only a reader that does not hold a compound file is an error. A shell
+    /// link that cannot be parsed is kept as an entry holding the error, and
+    /// a `DestList` that cannot be parsed is reported as a warning, or as an
+    /// error in strict mode.
+    pub fn from_reader_with_options<R: Read + Seek>(
+        reader: R,
+        options: &ParseOptions,
+    ) -> Result<Self, Error> {
+        let mut file = cfb::CompoundFile::open(reader)?;
+        let ctx = ParseContext::new(options);
+
+        let mut destinations = Vec::new();
+        if file.is_stream(DEST_LIST) {
+            let mut data = Vec::new();
+            file.open_stream(DEST_LIST)?
+                .take(options.max_input_size as u64)
+                .read_to_end(&mut data)?;
+            ctx.enter(DEST_LIST, 0);
+            destinations = parse_dest_list(&data, &ctx)?;
+        }
+
+        let mut names: Vec<String> = file
+            .read_root_storage()
+            .filter(|entry| entry.is_stream() && entry.name() != DEST_LIST)
+            .map(|entry| entry.name().to_string())
+            .collect();
+        // Keep the order of the DestList, which is most recently used first.
+        names.sort_by_key(|name| {
+            u32::from_str_radix(name, 16)
+                .ok()
+                .map_or(usize::MAX, |number| {
+                    destinations
+                        .iter()
+                        .position(|destination: &Destination| destination.entry_number == number)
+                        .unwrap_or(usize::MAX)
+                })
+        });
+
+        let mut entries = Vec::with_capacity(names.len());
+        for name in names {
+            trace!("Parsing jump list stream {:?}.", name);
+            let link = file
+                .open_stream(&name)
+                .map_err(Error::from)
+                .and_then(|stream| ShellLink::from_reader_with_options(stream, options));
+            let destination = u32::from_str_radix(&name, 16).ok().and_then(|number| {
+                destinations
+                    .iter()
+                    .position(|destination| destination.entry_number == number)
+                    .map(|i| destinations.swap_remove(i))
+            });
+            entries.push(JumpListEntry {
+                stream_name: name,
+                link,
+                destination,
+            });
+        }
+
+        Ok(Self {
+            entries,
+            warnings: ctx.into_warnings(),
+        })
+    }
+
+    /// Get the entries of the jump list, most recently used first.
+    pub fn entries(&self) -> &[JumpListEntry] {
+        &self.entries
+    }
+
+    /// Get the problems found while parsing the `DestList` that did not stop
+    /// the parse.
+    pub fn warnings(&self) -> &[ParseWarning] {
+        &self.warnings
+    }
+}
+
+/// A shell link held in a jump list, along with what the `DestList` records
+/// about it.
+#[derive(Debug)]
+pub struct JumpListEntry {
+    stream_name: String,
+    link: Result<ShellLink, Error>,
+    destination: Option<Destination>,
+}
+
+impl JumpListEntry {
+    /// The name of the stream holding the shell link, which is its entry
+    /// number in hexadecimal.
+    pub fn stream_name(&self) -> &str {
+        &self.stream_name
+    }
+
+    /// The shell link, or the error parsing it.
+    pub fn link(&self) -> Result<&ShellLink, &Error> {
+        self.link.as_ref()
+    }
+
+    /// What the `DestList` records about the shell link, if it has an entry
+    /// for it.
+    pub fn destination(&self) -> Option<&Destination> {
+        self.destination.as_ref()
+    }
+}
+
+/// An entry of the `DestList` stream of a jump list.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Destination {
+    entry_number: u32,
+    droid: [Guid; 2],
+    droid_birth: [Guid; 2],
+    hostname: String,
+    modification_time: FileTime,
+    pin_position: Option<u32>,
+    path: String,
+}
+
+impl Destination {
+    /// The number of the entry, which names the stream holding its shell
+    /// link.
+    pub fn entry_number(&self) -> u32 {
+        self.entry_number
+    }
+
+    /// The volume and object droids of the target, as in the
+    /// TrackerDataBlock.
+    pub fn droid(&self) -> &[Guid; 2] {
+        &self.droid
+    }
+
+    /// The birth volume and object droids of the target, as in the
+    /// TrackerDataBlock.
+    pub fn droid_birth(&self) -> &[Guid; 2] {
+        &self.droid_birth
+    }
+
+    /// The NetBIOS name of the machine the target was on.
+    pub fn hostname(&self) -> &str {
+        &self.hostname
+    }
+
+    /// When the entry was last used.
+    pub fn modification_time(&self) -> FileTime {
+        self.modification_time
+    }
+
+    /// The position of the entry in the pinned section of the jump list,
+    /// or `None` if it is not pinned.
+    pub fn pin_position(&self) -> Option<u32> {
+        self.pin_position
+    }
+
+    /// The path or URL of the target.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+/// Parse the `DestList` stream. Entries after one that does not fit the data
+/// are dropped with a warning.
+fn parse_dest_list(data: &[u8], ctx: &ParseContext) -> Result<Vec<Destination>, Error> {
+    if data.len() < 32 {
+        ctx.nonconformance(0, "The DestList header is truncated.".to_string())?;
+        return Ok(Vec::new());
+    }
+    let version = LE::read_u32(data);
+    let count = LE::read_u32(&data[4..]) as usize;
+    // Windows 7 wrote version 1. Windows 10 writes version 3 or 4, whose
+    // entries have more fields and end with 4 more bytes after the path.
+    let (header_size, trailer_size) = if version == 1 { (114, 0) } else { (130, 4) };
+
+    let mut destinations = Vec::new();
+    let mut cursor = 32;
+    while destinations.len() < count {
+        let entry = &data[cursor.min(data.len())..];
+        if entry.len() < header_size {
+            ctx.nonconformance(cursor, "A DestList entry is truncated.".to_string())?;
+            break;
+        }
+        let path_size = LE::read_u16(&entry[header_size - 2..]) as usize * 2;
+        if entry.len() < header_size + path_size + trailer_size {
+            ctx.nonconformance(cursor, "A DestList entry path is truncated.".to_string())?;
+            break;
+        }
+        let pin_status = LE::read_i32(&entry[108..]);
+        destinations.push(Destination {
+            entry_number: LE::read_u32(&entry[88..]),
+            droid: [Guid::read(&entry[8..]), Guid::read(&entry[24..])],
+            droid_birth: [Guid::read(&entry[40..]), Guid::read(&entry[56..])],
+            hostname: strings::decode_fixed(&entry[72..88], ctx),
+            modification_time: FileTime::from_raw(LE::read_u64(&entry[100..])),
+            pin_position: u32::try_from(pin_status).ok(),
+            path: strings::decode_utf16le(&entry[header_size..header_size + path_size]),
+        });
+        cursor += header_size + path_size + trailer_size;
+    }
+    Ok(destinations)
+}
@@ -0,0 +1,177 @@
+use std::cell::OnceCell;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use crate::borrowed::Sections;
+use crate::{
+    Error, ExtraData, LinkInfo, LinkTargetIdList, ParseContext, ParseOptions, ParseWarning,
+    ShellLink, ShellLinkHeader, ShellLinkRef,
+};
+
+/// A shell link whose structures are parsed the first time they are asked
+/// for, rather than all at once.
+///
+/// Opening one reads the data and parses the header, but only reads the
+/// sizes of the other structures. Each accessor parses its structure on the
+/// first call and keeps the result, so tools that only need a few fields do
+/// not pay for decoding the rest. Errors in a structure are returned by its
+/// accessor rather than when opening.
+#[derive(Debug)]
+pub struct LazyShellLink {
+    data: Vec<u8>,
+    options: ParseOptions,
+    header: ShellLinkHeader,
+    sections: Sections,
+    warnings: Vec<ParseWarning>,
+    link_target_id_list: OnceCell<Option<LinkTargetIdList>>,
+    link_info: OnceCell<Option<LinkInfo>>,
+    strings: [OnceCell<Option<String>>; 5],
+    extra_data: OnceCell<Vec<ExtraData>>,
+}
+
+impl LazyShellLink {
+    /// Open and lazily parse a shell link.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::open_with_options(path, &ParseOptions::default())
+    }
+
+    /// Open and lazily parse a shell link with the given options.
+    pub fn open_with_options<P: AsRef<Path>>(
+        path: P,
+        options: &ParseOptions,
+    ) -> Result<Self, Error> {
+        let r = BufReader::new(File::open(path)?);
+        Self::from_reader_with_options(r, options)
+    }
+
+    /// Lazily parse a shell link read from `reader` with the given options,
+    /// reading no more than the options allow.
+    pub fn from_reader_with_options<R: Read>(
+        reader: R,
+        options: &ParseOptions,
+    ) -> Result<Self, Error> {
+        let data = ShellLink::read_data(reader, options)?;
+        Self::from_vec_with_options(data, options)
+    }
+
+    /// Lazily parse a shell link from the start of `data` with the given
+    /// options.
+    pub fn from_vec_with_options(mut data: Vec<u8>, options: &ParseOptions) -> Result<Self, Error> {
+        let ctx = ParseContext::new(options);
+        let (header, sections) = Sections::locate(&data, &ctx)?;
+        data.truncate(sections.size);
+        Ok(Self {
+            data,
+            options: options.clone(),
+            header,
+            sections,
+            warnings: ctx.into_warnings(),
+            link_target_id_list: OnceCell::new(),
+            link_info: OnceCell::new(),
+            strings: Default::default(),
+            extra_data: OnceCell::new(),
+        })
+    }
+
+    /// Get a view of the shell link that borrows from its data.
+    pub fn as_shell_link_ref(&self) -> ShellLinkRef<'_> {
+        ShellLinkRef::from_parts(&self.data, &self.options, &self.header, &self.sections)
+    }
+
+    /// Get the header of the shell link
+    pub fn header(&self) -> &ShellLinkHeader {
+        &self.header
+    }
+
+    /// Get the link target ID List, parsing it on the first call.
+    pub fn link_target_id_list(&self) -> Result<&Option<LinkTargetIdList>, Error> {
+        get_or_try_init(&self.link_target_id_list, || {
+            self.as_shell_link_ref().link_target_id_list()
+        })
+    }
+
+    /// Get the link info structure, parsing it on the first call.
+    pub fn link_info(&self) -> Result<&Option<LinkInfo>, Error> {
+        get_or_try_init(&self.link_info, || {
+            self.as_shell_link_ref()
+                .link_info()
+                .map(|link_info| link_info.to_link_info())
+                .transpose()
+        })
+    }
+
+    fn string(&self, index: usize) -> &Option<String> {
+        self.strings[index].get_or_init(|| {
+            self.as_shell_link_ref()
+                .string(index)
+                .map(|s| s.decode().into_owned())
+        })
+    }
+
+    /// Get the shell link's name, if set
+    pub fn name(&self) -> &Option<String> {
+        self.string(0)
+    }
+
+    /// Get the shell link's relative path, if set
+    pub fn relative_path(&self) -> &Option<String> {
+        self.string(1)
+    }
+
+    /// Get the shell link's working directory, if set
+    pub fn working_dir(&self) -> &Option<String> {
+        self.string(2)
+    }
+
+    /// Get the shell link's arguments, if set
+    pub fn arguments(&self) -> &Option<String> {
+        self.string(3)
+    }
+
+    /// Get the shell link's icon location, if set
+    pub fn icon_location(&self) -> &Option<String> {
+        self.string(4)
+    }
+
+    /// Get the shell link's extra data blocks, parsing them on the first
+    /// call. Blocks that cannot be parsed are left out if
+    /// [`ParseOptions::recover_extra_data`] is set.
+    pub fn extra_data(&self) -> Result<&Vec<ExtraData>, Error> {
+        get_or_try_init(&self.extra_data, || {
+            let mut blocks = Vec::new();
+            for block in self.as_shell_link_ref().extra_data() {
+                match block {
+                    Ok(block) => blocks.push(block),
+                    Err(e) if e.is_malformed() && self.options.recover_extra_data => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            Ok(blocks)
+        })
+    }
+
+    /// Get the problems found while parsing the header that did not stop the
+    /// parse.
+    pub fn warnings(&self) -> &[ParseWarning] {
+        &self.warnings
+    }
+
+    /// Parse the whole shell link into a [`ShellLink`].
+    pub fn to_shell_link(&self) -> Result<ShellLink, Error> {
+        self.as_shell_link_ref().to_shell_link()
+    }
+}
+
+/// Get the value in `cell`, initializing it with `f` if it is empty and `f`
+/// succeeds.
+fn get_or_try_init<T>(
+    cell: &OnceCell<T>,
+    f: impl FnOnce() -> Result<T, Error>,
+) -> Result<&T, Error> {
+    if let Some(value) = cell.get() {
+        return Ok(value);
+    }
+    let value = f()?;
+    Ok(cell.get_or_init(|| value))
+}
@@ -20,6 +20,14 @@
 //! println!("{:#?}", shortcut);
 //! ```
 //!
+//! For logs, the `Display` implementation gives a summary of a few lines
+//! instead: the target, arguments, working directory, icon, hotkey and
+//! timestamps.
+//! ```
+//! let shortcut = lnk::ShellLink::open("tests/test.lnk").unwrap();
+//! println!("{}", shortcut);
+//! ```
+//!
 //! ## Write Example
 //!
 //! A simple example appears as follows:
@@ -31,22 +39,24 @@
 //!
 //! > **IMPORTANT!**: Writing capability is currently in a very early stage and probably won't work!
 
-use byteorder::{ByteOrder, LE};
 #[allow(unused)]
-use log::{debug, error, info, trace, warn};
+use crate::logging::{debug, error, info, trace, warn};
+use byteorder::{ByteOrder, LE};
 
-use std::convert::TryFrom;
+use std::fmt;
 use std::fs::File;
 #[cfg(feature = "experimental_save")]
 use std::io::BufWriter;
-use std::io::{prelude::*, BufReader};
+use std::io::{prelude::*, BufReader, SeekFrom};
 #[cfg(feature = "experimental_save")]
 use std::path::Path;
 
+mod logging;
+
 mod header;
 pub use header::{
-    FileAttributeFlags, HotkeyFlags, HotkeyKey, HotkeyModifiers, LinkFlags, ShellLinkHeader,
-    ShowCommand,
+    FileAttributeFlags, HotkeyFlags, HotkeyKey, HotkeyModifiers, LinkFlags, ParseHotkeyError,
+    ShellLinkHeader, ShowCommand,
 };
 
 /// The LinkTargetIDList structure specifies the target of the link. The presence of this optional
@@ -55,6 +65,12 @@ pub use header::{
 pub mod linktarget;
 pub use linktarget::LinkTargetIdList;
 
+pub mod extensionblock;
+pub use extensionblock::{ExtensionBlock, ExtensionBlockContents, FileEntryExtension};
+
+pub mod fileentry;
+pub use fileentry::FileEntryItem;
+
 /// The LinkInfo structure specifies information necessary to resolve a
 /// linktarget if it is not found in its original location. This includes
 /// information about the volume that the target was stored on, the mapped
@@ -62,7 +78,7 @@ pub use linktarget::LinkTargetIdList;
 /// if one existed when the linkwas created. For more details about UNC
 /// paths, see [MS-DFSNM] section 2.2.1.4
 pub mod linkinfo;
-pub use linkinfo::LinkInfo;
+pub use linkinfo::{LinkInfo, OffsetDiscrepancy, OffsetProblem};
 
 mod stringdata;
 
@@ -70,18 +86,325 @@ mod stringdata;
 pub mod extradata;
 pub use extradata::ExtraData;
 
-mod filetime;
-pub use filetime::FileTime;
+/// The FILETIME structure, and helpers for choosing how it is serialized.
+pub mod filetime;
+pub use filetime::{DosDateTime, FileTime};
+
+mod guid;
+pub use guid::{Guid, ParseGuidError};
+
+pub mod guids;
+
+mod borrowed;
+pub use borrowed::{LinkInfoRef, ShellLinkRef, StringRef};
+
+mod raw;
+pub use raw::RawShellLink;
+
+mod batch;
+#[cfg(feature = "rayon")]
+pub use batch::parse_dir;
+pub use batch::{ErrorPolicy, OpenedLinks};
+
+mod carve;
+pub use carve::{carve, carve_with_options, Carver};
+
+mod recent;
+#[cfg(windows)]
+pub use recent::recent_items;
+pub use recent::{recent_items_in, RECENT_FOLDERS};
+
+/// Where each structure of a shell link lies in its data, and the bytes
+/// that none account for.
+pub mod extents;
+pub use extents::{Extent, ExtentMap, Field};
+
+#[cfg(feature = "sha2")]
+mod hashes;
+#[cfg(feature = "sha2")]
+pub use hashes::{ExtraDataHash, ParseHashError, Sha256Hash, StructureHashes};
+
+/// Custom jump lists, which hold the shell links of the categories and tasks
+/// an application adds to its jump list.
+pub mod customdestinations;
+pub use customdestinations::CustomDestinations;
+
+/// Automatic jump lists, which hold the shell links an application's jump
+/// list shows.
+#[cfg(feature = "cfb")]
+pub mod jumplist;
+#[cfg(feature = "cfb")]
+pub use jumplist::JumpList;
+
+mod lazy;
+pub use lazy::LazyShellLink;
+
+/// Timestamped events, for building timelines from shell links and the
+/// artifacts that hold them.
+pub mod timeline;
+pub use timeline::{Timeline, TimelineEvent};
+
+mod flat;
+
+mod location;
+pub use location::TargetLocation;
+
+mod appx;
+pub use appx::AppxIdentity;
+
+mod url;
+
+mod windows_path;
+pub use windows_path::{WindowsPath, WindowsPrefix};
+
+/// Removal of identifying details from shell links, so that they can be
+/// shared.
+pub mod redact;
+pub use redact::{redact, RedactOptions};
+
+/// Checks for tricks used by malicious shell links.
+pub mod analysis;
+pub use analysis::{Finding, Indicator, Severity};
+
+#[cfg(feature = "heuristics")]
+mod heuristics;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+#[cfg(feature = "arbitrary")]
+mod fuzz;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(feature = "python")]
+mod python;
+
+#[cfg(all(windows, feature = "com"))]
+pub mod com;
+
+mod options;
+use options::ParseContext;
+pub use options::{ParseOptions, ParseWarning, DEFAULT_MAX_INPUT_SIZE, DEFAULT_MAX_STRUCTURE_SIZE};
 
 mod strings;
 
+#[cfg(feature = "serde")]
+pub mod flags;
+pub use strings::DEFAULT_MAX_STRING_LEN;
+
 /// The error type for shell link parsing errors.
+///
+/// [`Error::domain`] tells which part of the shell link an error comes
+/// from, so that callers can, for example, handle a malformed ExtraData
+/// block differently from data that is not a shell link at all.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
     /// An IO error occurred.
     IoError(std::io::Error),
     /// The parsed file isn't a shell link.
     NotAShellLinkError,
+    /// A NULL-terminated string was not terminated within the maximum
+    /// permitted length, in bytes.
+    StringTooLong(usize),
+    /// The shell link does not conform to the specification, and either
+    /// strict parsing was requested or the problem cannot be recovered from.
+    NonConformant {
+        /// The name of the structure, as named in the specification.
+        structure: &'static str,
+        /// The offset in the shell link, in bytes, of the problem.
+        offset: usize,
+        /// A description of the problem.
+        message: String,
+    },
+    /// A field holds a value that cannot be parsed.
+    UnexpectedValue {
+        /// The name of the structure, as named in the specification.
+        structure: &'static str,
+        /// The offset in the shell link, in bytes, of the field.
+        offset: usize,
+        /// The name of the field, as named in the specification.
+        field: &'static str,
+        /// A description of the values that can be parsed.
+        expected: String,
+        /// The value found.
+        found: String,
+    },
+    /// A size exceeds a limit set in the [`ParseOptions`].
+    LimitExceeded {
+        /// The name of the structure, as named in the specification, or
+        /// `"ShellLink"` for the input as a whole.
+        structure: &'static str,
+        /// The offset in the shell link, in bytes, of the size.
+        offset: usize,
+        /// The limit.
+        limit: usize,
+        /// The size found. For input that is too large, this is the number
+        /// of bytes read before giving up.
+        size: usize,
+    },
+    /// The data ended before the end of a structure.
+    UnexpectedEof {
+        /// The name of the structure, as named in the specification.
+        structure: &'static str,
+        /// The offset in the shell link, in bytes, at which more data was
+        /// needed.
+        offset: usize,
+        /// The number of bytes needed.
+        needed: usize,
+        /// The number of bytes available.
+        available: usize,
+    },
+    /// A string is too long for the fixed-size field it is to be stored in.
+    FieldTooLong {
+        /// The name of the structure, as named in the specification.
+        structure: &'static str,
+        /// The name of the field, as named in the specification.
+        field: &'static str,
+        /// The most characters the field holds, without its NULL
+        /// terminator.
+        limit: usize,
+        /// The length of the string, in characters of the field.
+        length: usize,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::IoError(e) => write!(f, "I/O error: {}", e),
+            Error::NotAShellLinkError => write!(f, "not a shell link"),
+            Error::StringTooLong(max_len) => write!(
+                f,
+                "a NULL-terminated string is longer than {} bytes",
+                max_len
+            ),
+            Error::NonConformant {
+                structure,
+                offset,
+                message,
+            } => write!(f, "{} at offset 0x{:x}: {}", structure, offset, message),
+            Error::UnexpectedValue {
+                structure,
+                offset,
+                field,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{} at offset 0x{:x}: expected {} to be {}, found {}",
+                structure, offset, field, expected, found
+            ),
+            Error::LimitExceeded {
+                structure,
+                offset,
+                limit,
+                size,
+            } => write!(
+                f,
+                "{} at offset 0x{:x}: size of {} bytes exceeds the limit of {} bytes",
+                structure, offset, size, limit
+            ),
+            Error::UnexpectedEof {
+                structure,
+                offset,
+                needed,
+                available,
+            } => write!(
+                f,
+                "{} at offset 0x{:x}: needed {} bytes, but only {} remain",
+                structure, offset, needed, available
+            ),
+            Error::FieldTooLong {
+                structure,
+                field,
+                limit,
+                length,
+            } => write!(
+                f,
+                "{}: {} holds at most {} characters, but the string has {}",
+                structure, field, limit, length
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::IoError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl Error {
+    /// The part of the shell link, or of the work done with it, that this
+    /// error comes from.
+    pub fn domain(&self) -> ErrorDomain {
+        match self {
+            Error::IoError(_) => ErrorDomain::Io,
+            Error::NotAShellLinkError => ErrorDomain::Header,
+            Error::StringTooLong(_) | Error::FieldTooLong { .. } => ErrorDomain::Encoding,
+            Error::NonConformant { structure, .. }
+            | Error::UnexpectedValue { structure, .. }
+            | Error::LimitExceeded { structure, .. }
+            | Error::UnexpectedEof { structure, .. } => ErrorDomain::of(structure),
+        }
+    }
+
+    /// Whether this error is caused by a malformed structure, rather than by
+    /// I/O, a limit, or the data not being a shell link at all.
+    pub(crate) fn is_malformed(&self) -> bool {
+        matches!(
+            self,
+            Error::NonConformant { .. }
+                | Error::UnexpectedValue { .. }
+                | Error::UnexpectedEof { .. }
+        )
+    }
+}
+
+/// The part of a shell link, or of the work done with one, that an
+/// [`Error`] comes from. See [`Error::domain`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorDomain {
+    /// The ShellLinkHeader, or the shell link as a whole, such as data that
+    /// is not a shell link or is too large.
+    Header,
+    /// The LinkTargetIDList.
+    IdList,
+    /// The LinkInfo, or the VolumeID or CommonNetworkRelativeLink in it.
+    LinkInfo,
+    /// The StringData.
+    StringData,
+    /// The ExtraData, or one of the blocks in it.
+    ExtraData,
+    /// A string that is too long to be read, or to be stored in its field.
+    Encoding,
+    /// Reading or writing the data.
+    Io,
+    /// A jump list, rather than one of the shell links in it.
+    JumpList,
+}
+
+impl ErrorDomain {
+    /// The domain of the structure named `structure`, as named in the
+    /// specification.
+    fn of(structure: &str) -> Self {
+        match structure {
+            "ShellLinkHeader" | "ShellLink" => ErrorDomain::Header,
+            "LinkTargetIDList" => ErrorDomain::IdList,
+            "LinkInfo" | "VolumeID" | "CommonNetworkRelativeLink" => ErrorDomain::LinkInfo,
+            "StringData" => ErrorDomain::StringData,
+            "CustomDestinations" | "Category" | "DestList" => ErrorDomain::JumpList,
+            // Every other structure is an ExtraData block.
+            _ => ErrorDomain::ExtraData,
+        }
+    }
 }
 
 impl From<std::io::Error> for Error {
@@ -92,6 +415,8 @@ impl From<std::io::Error> for Error {
 
 /// A shell link
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ShellLink {
     shell_link_header: header::ShellLinkHeader,
     linktarget_id_list: Option<linktarget::LinkTargetIdList>,
@@ -101,7 +426,40 @@ pub struct ShellLink {
     working_dir: Option<String>,
     command_line_arguments: Option<String>,
     icon_location: Option<String>,
+    #[cfg_attr(feature = "serde", serde(rename = "extra_data"))]
     _extra_data: Vec<extradata::ExtraData>,
+    trailing_data: Option<TrailingData>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    warnings: Vec<ParseWarning>,
+}
+
+/// Data found after the TerminalBlock that ends a shell link, for example a
+/// payload appended to it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct TrailingData {
+    offset: usize,
+    size: usize,
+    data: Option<Vec<u8>>,
+}
+
+impl TrailingData {
+    /// The offset, in bytes, of the trailing data from the start of the
+    /// shell link.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The size, in bytes, of the trailing data.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// The trailing data, if [`ParseOptions::keep_trailing_data`] was set.
+    pub fn data(&self) -> Option<&[u8]> {
+        self.data.as_deref()
+    }
 }
 
 impl Default for ShellLink {
@@ -111,6 +469,17 @@ impl Default for ShellLink {
     fn default() -> Self {
         Self {
             shell_link_header: header::ShellLinkHeader::default(),
+            ..Self::blank()
+        }
+    }
+}
+
+impl ShellLink {
+    /// A blank ShellLink whose header has no times set. Parsing builds on
+    /// this rather than [`Default`], which reads the clock.
+    fn blank() -> Self {
+        Self {
+            shell_link_header: header::ShellLinkHeader::blank(),
             linktarget_id_list: None,
             link_info: None,
             name_string: None,
@@ -119,10 +488,93 @@ impl Default for ShellLink {
             command_line_arguments: None,
             icon_location: None,
             _extra_data: vec![],
+            trailing_data: None,
+            warnings: vec![],
         }
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for ShellLink {
+    /// Generate a shell link whose LinkFlags match the structures, strings
+    /// and ExtraData blocks present. Strings are Unicode, and there is at
+    /// most one ExtraData block of each kind, as Windows writes them.
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        use fuzz::{optional_unicode_string, STRING_DATA_MAX};
+
+        let mut link = Self {
+            shell_link_header: u.arbitrary()?,
+            linktarget_id_list: u.arbitrary()?,
+            link_info: u.arbitrary()?,
+            name_string: optional_unicode_string::<STRING_DATA_MAX>(u)?,
+            relative_path: optional_unicode_string::<STRING_DATA_MAX>(u)?,
+            working_dir: optional_unicode_string::<STRING_DATA_MAX>(u)?,
+            command_line_arguments: optional_unicode_string::<STRING_DATA_MAX>(u)?,
+            icon_location: optional_unicode_string::<STRING_DATA_MAX>(u)?,
+            _extra_data: u.arbitrary()?,
+            trailing_data: None,
+            warnings: vec![],
+        };
+        let mut kinds = Vec::new();
+        link._extra_data.retain(|block| {
+            let kind = std::mem::discriminant(block);
+            let first = !kinds.contains(&kind);
+            kinds.push(kind);
+            first
+        });
+
+        let has = |block: fn(&ExtraData) -> bool| link._extra_data.iter().any(block);
+        let presence = [
+            (
+                LinkFlags::HAS_LINK_TARGET_ID_LIST,
+                link.linktarget_id_list.is_some(),
+            ),
+            (LinkFlags::HAS_LINK_INFO, link.link_info.is_some()),
+            (LinkFlags::HAS_NAME, link.name_string.is_some()),
+            (LinkFlags::HAS_RELATIVE_PATH, link.relative_path.is_some()),
+            (LinkFlags::HAS_WORKING_DIR, link.working_dir.is_some()),
+            (
+                LinkFlags::HAS_ARGUMENTS,
+                link.command_line_arguments.is_some(),
+            ),
+            (LinkFlags::HAS_ICON_LOCATION, link.icon_location.is_some()),
+            (LinkFlags::IS_UNICODE, true),
+            (LinkFlags::FORCE_NO_LINK_INFO, link.link_info.is_none()),
+            (
+                LinkFlags::HAS_EXP_STRING,
+                has(|b| matches!(b, ExtraData::EnvironmentProps(_))),
+            ),
+            (
+                LinkFlags::HAS_DARWIN_ID,
+                has(|b| matches!(b, ExtraData::DarwinProps(_))),
+            ),
+            (
+                LinkFlags::HAS_EXP_ICON,
+                has(|b| matches!(b, ExtraData::IconEnvironmentProps(_))),
+            ),
+            (
+                LinkFlags::RUN_WITH_SHIM_LAYER,
+                has(|b| matches!(b, ExtraData::ShimProps(_))),
+            ),
+        ];
+        for (flag, present) in presence {
+            link.shell_link_header.update_link_flags(flag, present);
+        }
+        Ok(link)
+    }
+}
+
+impl TryFrom<&[u8]> for ShellLink {
+    type Error = Error;
+
+    /// Parse a shell link from the start of `data`, ignoring any data after
+    /// it. Use [`ShellLink::from_slice`] to also get the number of bytes it
+    /// occupies.
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_slice(data).map(|(link, _)| link)
+    }
+}
+
 impl ShellLink {
     #[cfg(feature = "experimental_save")]
     /// Create a new ShellLink pointing to a location, with otherwise default settings.
@@ -253,42 +705,275 @@ impl ShellLink {
         Ok(())
     }
 
-    /// Open and parse a shell link
+    /// Open and parse a shell link. The file is read into memory in one go,
+    /// up to [`ParseOptions::max_input_size`], and parsed from there.
     pub fn open<P: AsRef<std::path::Path>>(path: P) -> Result<Self, Error> {
+        Self::open_with_options(path, &ParseOptions::default())
+    }
+
+    /// Open and parse a shell link, refusing NULL-terminated strings longer
+    /// than `max_string_len` bytes.
+    #[deprecated(note = "use `open_with_options` and `ParseOptions::max_string_len`")]
+    pub fn open_with_max_string_len<P: AsRef<std::path::Path>>(
+        path: P,
+        max_string_len: usize,
+    ) -> Result<Self, Error> {
+        Self::open_with_options(
+            path,
+            &ParseOptions {
+                max_string_len,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Open and parse a shell link with the given options.
+    pub fn open_with_options<P: AsRef<std::path::Path>>(
+        path: P,
+        options: &ParseOptions,
+    ) -> Result<Self, Error> {
         debug!("Opening {:?}", path.as_ref());
-        let mut r = BufReader::new(File::open(path)?);
-        let mut data = vec![];
+        let r = BufReader::new(File::open(path)?);
         trace!("Reading file.");
-        r.read_to_end(&mut data)?;
+        let data = Self::read_data(r, options)?;
+
+        Self::from_slice_with_options(&data, options).map(|(link, _)| link)
+    }
+
+    /// Open and parse a shell link by mapping the file into memory, rather
+    /// than reading it. All parsing options are set to their default values.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while it is being parsed,
+    /// as the parser reads it directly from the mapped pages. See
+    /// [`memmap2::Mmap::map`].
+    #[cfg(feature = "memmap2")]
+    pub unsafe fn open_mmap<P: AsRef<std::path::Path>>(path: P) -> Result<Self, Error> {
+        Self::open_mmap_with_options(path, &ParseOptions::default())
+    }
+
+    /// Open and parse a shell link by mapping the file into memory, with the
+    /// given options.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while it is being parsed.
+    /// See [`ShellLink::open_mmap`].
+    #[cfg(feature = "memmap2")]
+    pub unsafe fn open_mmap_with_options<P: AsRef<std::path::Path>>(
+        path: P,
+        options: &ParseOptions,
+    ) -> Result<Self, Error> {
+        debug!("Mapping {:?}", path.as_ref());
+        let file = File::open(path)?;
+        let map = memmap2::Mmap::map(&file)?;
+        if !options.accepts_signature(&map) {
+            return Err(Error::NotAShellLinkError);
+        }
+        let limit = options.max_input_size;
+        if map.len() > limit && !options.header_only {
+            return Err(Error::LimitExceeded {
+                structure: "ShellLink",
+                offset: 0,
+                limit,
+                size: map.len(),
+            });
+        }
+        Self::from_slice_with_options(&map, options).map(|(link, _)| link)
+    }
+
+    /// Open and parse a shell link without blocking the async runtime. The
+    /// file is read with [`tokio::fs`] and the bytes are then parsed in
+    /// place, which is quick enough not to need a blocking task. All parsing
+    /// options are set to their default values.
+    #[cfg(feature = "tokio")]
+    pub async fn open_async<P: AsRef<std::path::Path>>(path: P) -> Result<Self, Error> {
+        Self::open_async_with_options(path, &ParseOptions::default()).await
+    }
+
+    /// Open and parse a shell link without blocking the async runtime, with
+    /// the given options. See [`ShellLink::open_async`].
+    #[cfg(feature = "tokio")]
+    pub async fn open_async_with_options<P: AsRef<std::path::Path>>(
+        path: P,
+        options: &ParseOptions,
+    ) -> Result<Self, Error> {
+        use tokio::io::AsyncReadExt;
+
+        debug!("Opening {:?}", path.as_ref());
+        let mut file = tokio::fs::File::open(path).await?;
+        trace!("Reading file.");
+        let mut data = vec![];
+        (&mut file).take(0x4c).read_to_end(&mut data).await?;
+        if !options.accepts_signature(&data) {
+            return Err(Error::NotAShellLinkError);
+        }
+        if !options.header_only {
+            let limit = options.max_input_size;
+            file.take(limit.saturating_add(1).saturating_sub(data.len()) as u64)
+                .read_to_end(&mut data)
+                .await?;
+            if data.len() > limit {
+                return Err(Error::LimitExceeded {
+                    structure: "ShellLink",
+                    offset: 0,
+                    limit,
+                    size: data.len(),
+                });
+            }
+        }
+
+        Self::from_slice_with_options(&data, options).map(|(link, _)| link)
+    }
+
+    /// Parse a shell link from a reader, starting at its current position.
+    /// On success, the reader is left just after the end of the shell link.
+    pub fn from_reader<R: Read + Seek>(reader: R) -> Result<Self, Error> {
+        Self::from_reader_with_options(reader, &ParseOptions::default())
+    }
+
+    /// Parse a shell link from a reader with the given options, starting at
+    /// its current position. On success, the reader is left just after the
+    /// end of the shell link.
+    pub fn from_reader_with_options<R: Read + Seek>(
+        mut reader: R,
+        options: &ParseOptions,
+    ) -> Result<Self, Error> {
+        let start = reader.stream_position()?;
+        Self::parse_at(reader, start, options).map(|(link, _)| link)
+    }
+
+    /// Parse a shell link that starts `offset` bytes into a reader, such as
+    /// one embedded in an email, OLE document or installer, with the given
+    /// options, returning it along with the number of bytes it occupies. No
+    /// more than [`ParseOptions::max_input_size`] bytes are read, however
+    /// large the reader. On success, the reader is left just after the end
+    /// of the shell link.
+    pub fn parse_at<R: Read + Seek>(
+        mut reader: R,
+        offset: u64,
+        options: &ParseOptions,
+    ) -> Result<(Self, usize), Error> {
+        trace!("Reading from 0x{:x}.", offset);
+        reader.seek(SeekFrom::Start(offset))?;
+        // Whatever follows the first `max_input_size` bytes is not part of
+        // the shell link, so it is not read, rather than being an error.
+        let data = Self::read_prefix(&mut reader, options, options.max_input_size)?;
+
+        let (link, consumed) = Self::from_slice_with_options(&data, options)?;
+        reader.seek(SeekFrom::Start(offset + consumed as u64))?;
+        Ok((link, consumed))
+    }
+
+    /// Read the data the options ask to be parsed: the whole of the reader,
+    /// up to the maximum input size, or just the header.
+    fn read_data<R: Read>(reader: R, options: &ParseOptions) -> Result<Vec<u8>, Error> {
+        let limit = options.max_input_size;
+        let data = Self::read_prefix(reader, options, limit.saturating_add(1))?;
+        if data.len() > limit {
+            return Err(Error::LimitExceeded {
+                structure: "ShellLink",
+                offset: 0,
+                limit,
+                size: data.len(),
+            });
+        }
+        Ok(data)
+    }
+
+    /// Read the data the options ask to be parsed, no more than `size`
+    /// bytes of the reader, or just the header.
+    fn read_prefix<R: Read>(
+        mut reader: R,
+        options: &ParseOptions,
+        size: usize,
+    ) -> Result<Vec<u8>, Error> {
+        let mut data = vec![];
+        reader.by_ref().take(0x4c).read_to_end(&mut data)?;
+        if !options.accepts_signature(&data) {
+            return Err(Error::NotAShellLinkError);
+        }
+        if options.header_only {
+            return Ok(data);
+        }
+        reader
+            .take(size.saturating_sub(data.len()) as u64)
+            .read_to_end(&mut data)?;
+        Ok(data)
+    }
+
+    /// Parse a shell link from the start of `data`, returning it along with
+    /// the number of bytes it occupies.
+    pub fn from_slice(data: &[u8]) -> Result<(Self, usize), Error> {
+        Self::from_slice_with_options(data, &ParseOptions::default())
+    }
+
+    /// Parse a shell link from the start of `data` with the given options,
+    /// returning it along with the number of bytes it occupies.
+    pub fn from_slice_with_options(
+        data: &[u8],
+        options: &ParseOptions,
+    ) -> Result<(Self, usize), Error> {
+        let ctx = ParseContext::new(options);
 
         trace!("Parsing shell header.");
         if data.len() < 0x4c {
             return Err(Error::NotAShellLinkError);
         }
-        let shell_link_header = header::ShellLinkHeader::try_from(&data[0..0x4c])?;
+        ctx.enter("ShellLinkHeader", 0);
+        let shell_link_header = header::ShellLinkHeader::parse(&data[0..0x4c], &ctx)?;
         debug!("Shell header: {:#?}", shell_link_header);
 
         let mut cursor = 0x4c;
+        if options.header_only {
+            let link = Self {
+                shell_link_header,
+                warnings: ctx.into_warnings(),
+                ..Self::blank()
+            };
+            return Ok((link, cursor));
+        }
 
         let mut linktarget_id_list = None;
         let link_flags = *shell_link_header.link_flags();
         if link_flags.contains(LinkFlags::HAS_LINK_TARGET_ID_LIST) {
             debug!("A LinkTargetIDList is marked as present. Parsing now.");
             debug!("Cursor position: 0x{:x}", cursor);
-            let list = linktarget::LinkTargetIdList::from(&data[cursor..]);
-            debug!("{:?}", list);
-            cursor += list.size as usize + 2; // add LinkTargetSize size
-            linktarget_id_list = Some(list);
+            ctx.enter("LinkTargetIDList", cursor);
+            match linktarget::LinkTargetIdList::parse(&data[cursor..], &ctx) {
+                Ok(list) => {
+                    debug!("{:?}", list);
+                    cursor += list.size as usize + 2; // add LinkTargetSize size
+                    linktarget_id_list = Some(list);
+                }
+                Err(e) => {
+                    let declared = data
+                        .get(cursor..cursor + 2)
+                        .map(|size| LE::read_u16(size) as usize + 2);
+                    cursor += Self::resynchronize(&data[cursor..], declared, e, &ctx)?;
+                }
+            }
         }
 
         let mut link_info = None;
         if link_flags.contains(LinkFlags::HAS_LINK_INFO) {
             debug!("LinkInfo is marked as present. Parsing now.");
             debug!("Cursor position: 0x{:x}", cursor);
-            let info = linkinfo::LinkInfo::from(&data[cursor..]);
-            debug!("{:?}", info);
-            cursor += info.size as usize;
-            link_info = Some(info);
+            ctx.enter("LinkInfo", cursor);
+            match linkinfo::LinkInfo::parse(&data[cursor..], &ctx) {
+                Ok(info) => {
+                    debug!("{:?}", info);
+                    cursor += info.size as usize;
+                    link_info = Some(info);
+                }
+                Err(e) => {
+                    let declared = data
+                        .get(cursor..cursor + 4)
+                        .map(|size| LE::read_u32(size) as usize);
+                    cursor += Self::resynchronize(&data[cursor..], declared, e, &ctx)?;
+                }
+            }
         }
 
         let mut name_string = None;
@@ -300,7 +985,8 @@ impl ShellLink {
         if link_flags.contains(LinkFlags::HAS_NAME) {
             debug!("Name is marked as present. Parsing now.");
             debug!("Cursor position: 0x{:x}", cursor);
-            let (len, data) = stringdata::parse_string(&data[cursor..], link_flags);
+            ctx.enter("StringData", cursor);
+            let (len, data) = stringdata::parse_string(&data[cursor..], link_flags, &ctx)?;
             name_string = Some(data);
             cursor += len; // add len bytes
         }
@@ -308,7 +994,8 @@ impl ShellLink {
         if link_flags.contains(LinkFlags::HAS_RELATIVE_PATH) {
             debug!("Relative path is marked as present. Parsing now.");
             debug!("Cursor position: 0x{:x}", cursor);
-            let (len, data) = stringdata::parse_string(&data[cursor..], link_flags);
+            ctx.enter("StringData", cursor);
+            let (len, data) = stringdata::parse_string(&data[cursor..], link_flags, &ctx)?;
             relative_path = Some(data);
             cursor += len; // add len bytes
         }
@@ -316,7 +1003,8 @@ impl ShellLink {
         if link_flags.contains(LinkFlags::HAS_WORKING_DIR) {
             debug!("Working dir is marked as present. Parsing now.");
             debug!("Cursor position: 0x{:x}", cursor);
-            let (len, data) = stringdata::parse_string(&data[cursor..], link_flags);
+            ctx.enter("StringData", cursor);
+            let (len, data) = stringdata::parse_string(&data[cursor..], link_flags, &ctx)?;
             working_dir = Some(data);
             cursor += len; // add len bytes
         }
@@ -324,7 +1012,8 @@ impl ShellLink {
         if link_flags.contains(LinkFlags::HAS_ARGUMENTS) {
             debug!("Arguments are marked as present. Parsing now.");
             debug!("Cursor position: 0x{:x}", cursor);
-            let (len, data) = stringdata::parse_string(&data[cursor..], link_flags);
+            ctx.enter("StringData", cursor);
+            let (len, data) = stringdata::parse_string(&data[cursor..], link_flags, &ctx)?;
             command_line_arguments = Some(data);
             cursor += len; // add len bytes
         }
@@ -332,7 +1021,8 @@ impl ShellLink {
         if link_flags.contains(LinkFlags::HAS_ICON_LOCATION) {
             debug!("Icon Location is marked as present. Parsing now.");
             debug!("Cursor position: 0x{:x}", cursor);
-            let (len, data) = stringdata::parse_string(&data[cursor..], link_flags);
+            ctx.enter("StringData", cursor);
+            let (len, data) = stringdata::parse_string(&data[cursor..], link_flags, &ctx)?;
             icon_location = Some(data);
             cursor += len; // add len bytes
         }
@@ -340,23 +1030,52 @@ impl ShellLink {
         let mut extra_data = Vec::new();
 
         loop {
+            ctx.enter("ExtraData", cursor);
             if data[cursor..].len() < 4 {
-                warn!("The ExtraData length is invalid.");
+                ctx.nonconformance(0, "The ExtraData length is invalid.".to_string())?;
                 break; // Probably an error?
             }
             debug!("Parsing ExtraData");
             debug!("Cursor position: 0x{:x}", cursor);
             let query = LE::read_u32(&data[cursor..]);
             if query < 0x04 {
+                cursor += 4; // add the TerminalBlock
                 break;
             }
-            extra_data.push(extradata::ExtraData::from(&data[cursor..]));
-            cursor += query as usize;
+            let size = query as usize;
+            match extradata::ExtraData::parse(&data[cursor..], &ctx) {
+                Ok(block) => extra_data.push(block),
+                Err(e) if e.is_malformed() && options.recover_extra_data => {
+                    // If the block's size is sound, only the block itself is
+                    // skipped; otherwise there is no telling where the next
+                    // one starts.
+                    if size < 8 || size > data.len() - cursor {
+                        ctx.nonconformance(0, format!("Skipping the remaining ExtraData: {}", e))?;
+                        break;
+                    }
+                    ctx.nonconformance(0, format!("Skipping the block: {}", e))?;
+                }
+                Err(e) => return Err(e),
+            }
+            cursor += size;
         }
 
-        let _remaining_data = &data[cursor..];
+        let mut trailing_data = None;
+        if cursor < data.len() {
+            let remaining_data = &data[cursor..];
+            info!(
+                "Found 0x{:x} bytes of trailing data at 0x{:x}",
+                remaining_data.len(),
+                cursor
+            );
+            trailing_data = Some(TrailingData {
+                offset: cursor,
+                size: remaining_data.len(),
+                data: options.keep_trailing_data.then(|| remaining_data.to_vec()),
+            });
+        }
 
-        Ok(Self {
+        let link = Self {
             shell_link_header,
             linktarget_id_list,
             link_info,
@@ -366,7 +1085,31 @@ impl ShellLink {
             command_line_arguments,
             icon_location,
             _extra_data: extra_data,
-        })
+            trailing_data,
+            warnings: ctx.into_warnings(),
+        };
+        Ok((link, cursor))
+    }
+
+    /// Recover from `error` in the structure at the start of `data`, which
+    /// declares that it occupies `declared` bytes, by skipping over it. Fails
+    /// with `error` if resynchronization is disabled, the problem is not
+    /// with the structure itself, or the declared size does not fit `data`.
+    fn resynchronize(
+        data: &[u8],
+        declared: Option<usize>,
+        error: Error,
+        ctx: &ParseContext,
+    ) -> Result<usize, Error> {
+        match declared {
+            Some(size)
+                if error.is_malformed() && ctx.options.resynchronize && size <= data.len() =>
+            {
+                ctx.nonconformance(0, format!("Skipping the structure: {}", error))?;
+                Ok(size)
+            }
+            _ => Err(error),
+        }
     }
 
     /// Get the header of the shell link
@@ -374,7 +1117,6 @@ impl ShellLink {
         &self.shell_link_header
     }
 
-    #[cfg(feature = "experimental_save")]
     /// Get a mutable instance of the shell link's header
     pub fn header_mut(&mut self) -> &mut ShellLinkHeader {
         &mut self.shell_link_header
@@ -395,7 +1137,6 @@ impl ShellLink {
         &self.name_string
     }
 
-    #[cfg(feature = "experimental_save")]
     /// Set the shell link's name
     pub fn set_name(&mut self, name: Option<String>) {
         self.header_mut()
@@ -408,7 +1149,6 @@ impl ShellLink {
         &self.relative_path
     }
 
-    #[cfg(feature = "experimental_save")]
     /// Set the shell link's relative path
     pub fn set_relative_path(&mut self, relative_path: Option<String>) {
         self.header_mut()
@@ -421,7 +1161,6 @@ impl ShellLink {
         &self.working_dir
     }
 
-    #[cfg(feature = "experimental_save")]
     /// Set the shell link's working directory
     pub fn set_working_dir(&mut self, working_dir: Option<String>) {
         self.header_mut()
@@ -434,7 +1173,6 @@ impl ShellLink {
         &self.command_line_arguments
     }
 
-    #[cfg(feature = "experimental_save")]
     /// Set the shell link's arguments
     pub fn set_arguments(&mut self, arguments: Option<String>) {
         self.header_mut()
@@ -447,11 +1185,179 @@ impl ShellLink {
         &self.icon_location
     }
 
-    #[cfg(feature = "experimental_save")]
     /// Set the shell link's icon location
     pub fn set_icon_location(&mut self, icon_location: Option<String>) {
         self.header_mut()
             .update_link_flags(LinkFlags::HAS_ICON_LOCATION, icon_location.is_some());
         self.icon_location = icon_location;
     }
+
+    /// Get the shell link's extra data blocks
+    pub fn extra_data(&self) -> &Vec<ExtraData> {
+        &self._extra_data
+    }
+
+    /// Get the data found after the end of the shell link, if any. When
+    /// parsing from a reader or slice, this is everything after the shell
+    /// link in the input.
+    pub fn trailing_data(&self) -> Option<&TrailingData> {
+        self.trailing_data.as_ref()
+    }
+
+    /// Get the problems found while parsing the shell link that did not stop
+    /// the parse. Each describes a structure that was skipped or read in a
+    /// degraded form.
+    pub fn warnings(&self) -> &[ParseWarning] {
+        &self.warnings
+    }
+
+    /// Check the offsets declared in the link info structure against where
+    /// the data they point to was found. See [`LinkInfo::validate_offsets`].
+    pub fn validate_offsets(&self) -> Vec<OffsetDiscrepancy> {
+        self.link_info
+            .as_ref()
+            .map(LinkInfo::validate_offsets)
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for ShellLink {
+    /// Write a summary of the shell link, one field per line in the form
+    /// `Target:      C:\test\a.txt`. The target, a path or else a URL, and
+    /// the timestamps are always written, the arguments, working directory,
+    /// icon and hotkey only when they are set.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = self.header();
+        match self.target_path().or_else(|| self.target_url()) {
+            Some(target) => write!(f, "{:<12} {}", "Target:", target)?,
+            None => write!(f, "{:<12} unknown", "Target:")?,
+        }
+        if let Some(arguments) = self.arguments() {
+            write!(f, "\n{:<12} {}", "Arguments:", arguments)?;
+        }
+        if let Some(working_dir) = self.working_dir() {
+            write!(f, "\n{:<12} {}", "Working dir:", working_dir)?;
+        }
+        if let Some(icon) = self.icon_location() {
+            write!(f, "\n{:<12} {},{}", "Icon:", icon, header.icon_index())?;
+        }
+        if !matches!(header.hotkey().key(), HotkeyKey::NoKeyAssigned) {
+            write!(f, "\n{:<12} {}", "Hotkey:", header.hotkey())?;
+        }
+        for (name, time) in [
+            ("Created:", header.creation_time()),
+            ("Modified:", header.write_time()),
+            ("Accessed:", header.access_time()),
+        ] {
+            write!(f, "\n{:<12} {}", name, time)?;
+        }
+        Ok(())
+    }
+}
+
+impl ShellLink {
+    /// The full path of the target, as the rest of this crate resolves it:
+    /// the LocalBasePath and CommonPathSuffix of the LinkInfo, or else the
+    /// NetName of its CommonNetworkRelativeLink and the CommonPathSuffix,
+    /// preferring the Unicode strings, or else the target of the
+    /// EnvironmentVariableDataBlock, with its variables unexpanded.
+    ///
+    /// `None` when the target is only given by the LinkTargetIDList, or not
+    /// at all.
+    pub fn target_path(&self) -> Option<String> {
+        if let Some(link_info) = &self.link_info {
+            let suffix = link_info
+                .common_path_suffix_unicode()
+                .as_ref()
+                .unwrap_or(link_info.common_path_suffix());
+            if let Some(base) = link_info
+                .local_base_path_unicode()
+                .as_ref()
+                .or(link_info.local_base_path().as_ref())
+            {
+                return Some(format!("{}{}", base, suffix));
+            }
+            if let Some(network) = link_info.common_network_relative_link() {
+                let share = network.net_name();
+                return Some(if suffix.is_empty() {
+                    share.clone()
+                } else {
+                    format!("{}\\{}", share, suffix)
+                });
+            }
+        }
+        self._extra_data.iter().find_map(|block| match block {
+            ExtraData::EnvironmentProps(props) => Some(
+                props
+                    .target_unicode()
+                    .clone()
+                    .unwrap_or_else(|| props.target_ansi().clone()),
+            ),
+            _ => None,
+        })
+    }
+
+    /// The file name of the target, the last component of its path: from
+    /// the LinkInfo or EnvironmentVariableDataBlock, or else from the
+    /// relative path. `None` when no path is stored, or it ends in a
+    /// separator, as the root of a drive does.
+    pub fn target_file_name(&self) -> Option<String> {
+        let path = self.target_path().or_else(|| self.relative_path.clone())?;
+        let name = path.rsplit(['\\', '/']).next().unwrap_or_default();
+        (!name.is_empty()).then(|| name.to_string())
+    }
+
+    /// The extension of the target's file name, without the dot and in the
+    /// case it is stored in, such as `pdf` for `report.pdf`. `None` when the
+    /// file name has no extension, or begins with its only dot.
+    pub fn target_extension(&self) -> Option<String> {
+        let name = self.target_file_name()?;
+        let (stem, extension) = name.rsplit_once('.')?;
+        (!stem.is_empty() && !extension.is_empty()).then(|| extension.to_string())
+    }
+
+    /// The full path of the target, from the LinkInfo or else from the
+    /// EnvironmentVariableDataBlock, as a [`WindowsPath`].
+    pub fn target_windows_path(&self) -> Option<WindowsPath> {
+        self.target_path().map(WindowsPath::from)
+    }
+
+    /// The shell link's working directory, if set, as a [`WindowsPath`].
+    pub fn working_dir_path(&self) -> Option<WindowsPath> {
+        self.working_dir.as_deref().map(WindowsPath::from)
+    }
+
+    /// The shell link's icon location, if set, as a [`WindowsPath`]. The
+    /// index of the icon in the file is [`ShellLinkHeader::icon_index`].
+    pub fn icon_location_path(&self) -> Option<WindowsPath> {
+        self.icon_location.as_deref().map(WindowsPath::from)
+    }
+
+    /// The icon the shell shows for the link: the path of the file holding
+    /// it, and the index of the icon in that file. As in Windows, the path in
+    /// the IconEnvironmentDataBlock is used when the `HAS_EXP_ICON` flag is
+    /// set, with its environment variables left unexpanded, and the icon
+    /// location otherwise. `None` when neither is set, so that the shell
+    /// shows the icon of the target.
+    pub fn icon(&self) -> Option<(String, i32)> {
+        let index = self.header().icon_index();
+        if self.header().link_flags().contains(LinkFlags::HAS_EXP_ICON) {
+            let path = self._extra_data.iter().find_map(|block| match block {
+                ExtraData::IconEnvironmentProps(props) => props
+                    .target_unicode()
+                    .clone()
+                    .filter(|path| !path.is_empty())
+                    .or_else(|| Some(props.target_ansi().clone()))
+                    .filter(|path| !path.is_empty()),
+                _ => None,
+            });
+            if let Some(path) = path {
+                return Some((path, index));
+            }
+        }
+        self.icon_location
+            .clone()
+            .filter(|path| !path.is_empty())
+            .map(|path| (path, index))
+    }
 }
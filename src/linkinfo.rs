@@ -3,7 +3,11 @@ use byteorder::{ByteOrder, LE};
 use num_derive::{FromPrimitive, ToPrimitive};
 use num_traits::FromPrimitive;
 
-use crate::strings;
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::{strings, ParseContext};
 
 /// The LinkInfo structure specifies information necessary to resolve a
 /// linktarget if it is not found in its original location. This includes
@@ -12,12 +16,15 @@ use crate::strings;
 /// if one existed when the linkwas created. For more details about UNC
 /// paths, see [MS-DFSNM] section 2.2.1.4
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct LinkInfo {
     /// The parsed struct size
     pub size: u32,
     /// Flags that specify whether the VolumeID, LocalBasePath,
     /// LocalBasePathUnicode, and CommonNetworkRelativeLinkfields are present
     /// in this structure.
+    #[cfg_attr(feature = "serde", serde(rename = "link_info_flags"))]
     _link_info_flags: LinkInfoFlags,
     /// An optional VolumeID structure (section 2.3.1) that specifies
     /// information about the volume that the link target was on when the link
@@ -49,6 +56,12 @@ pub struct LinkInfo {
     /// only if the value of the LinkInfoHeaderSize field is greater than or
     /// equal to 0x00000024.
     common_path_suffix_unicode: Option<String>,
+    /// The offsets declared by this structure, as parsed.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Layout::is_empty")
+    )]
+    layout: Layout,
 }
 
 impl LinkInfo {
@@ -94,6 +107,52 @@ impl LinkInfo {
     pub fn common_path_suffix_unicode(&self) -> &Option<String> {
         &self.common_path_suffix_unicode
     }
+
+    /// Remove identifying details from the volume and paths.
+    pub(crate) fn redact(&mut self, redactor: &crate::redact::Redactor) {
+        if let (true, Some(volume_id)) = (redactor.options.volume, &mut self.volume_id) {
+            volume_id.drive_serial_number = 0;
+            volume_id.volume_label.clear();
+        }
+        for s in [
+            &mut self.local_base_path,
+            &mut self.local_base_path_unicode,
+            &mut self.common_path_suffix_unicode,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            redactor.string(s);
+        }
+        redactor.string(&mut self.common_path_suffix);
+        if let Some(network) = &mut self.common_network_relative_link {
+            network.redact(redactor);
+        }
+    }
+
+    /// Check every offset declared by this structure, and by the VolumeID
+    /// and CommonNetworkRelativeLink structures in it, against where the data
+    /// it points to was found. Windows lays the data out one item after
+    /// another straight after the header, so any gap, overlap or stray
+    /// offset is reported.
+    ///
+    /// Only structures parsed from a shell link, or deserialized from one,
+    /// have offsets to check; for any other, nothing is reported.
+    ///
+    /// When serialized, each structure also holds its `layout`: the offsets
+    /// it declares, each with `matched`, which is false if the offset is one
+    /// reported here.
+    pub fn validate_offsets(&self) -> Vec<OffsetDiscrepancy> {
+        let mut discrepancies = Vec::new();
+        self.layout.check(&mut discrepancies);
+        if let Some(volume_id) = &self.volume_id {
+            volume_id.layout.check(&mut discrepancies);
+        }
+        if let Some(link) = &self.common_network_relative_link {
+            link.layout.check(&mut discrepancies);
+        }
+        discrepancies
+    }
 }
 
 impl Default for LinkInfo {
@@ -107,74 +166,406 @@ impl Default for LinkInfo {
             common_path_suffix: String::new(),
             local_base_path_unicode: None,
             common_path_suffix_unicode: None,
+            layout: Layout::default(),
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for LinkInfo {
+    /// Generate a LinkInfo whose flags and size match the structures and
+    /// strings present, with the optional Unicode strings either all present
+    /// or all absent.
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        use crate::fuzz::{ansi_size, ansi_string, unicode_size, unicode_string};
+
+        let volume_id: Option<VolumeID> = u.arbitrary()?;
+        let common_network_relative_link: Option<CommonNetworkRelativeLink> = u.arbitrary()?;
+        let unicode: bool = u.arbitrary()?;
+        let mut link_info = Self {
+            common_path_suffix: ansi_string::<{ u16::MAX as usize }>(u)?,
+            ..Default::default()
+        };
+
+        let mut size = if unicode { 0x24 } else { 0x1c };
+        if let Some(volume_id) = &volume_id {
+            let local_base_path = ansi_string::<{ u16::MAX as usize }>(u)?;
+            size += volume_id.size() + ansi_size(&local_base_path);
+            if unicode {
+                let local_base_path_unicode = unicode_string::<{ u16::MAX as usize }>(u)?;
+                size += unicode_size(&local_base_path_unicode);
+                link_info.local_base_path_unicode = Some(local_base_path_unicode);
+            }
+            link_info.local_base_path = Some(local_base_path);
+            link_info._link_info_flags |= LinkInfoFlags::VOLUME_ID_AND_LOCAL_BASE_PATH;
         }
+        if let Some(link) = &common_network_relative_link {
+            size += link.size();
+            link_info._link_info_flags |=
+                LinkInfoFlags::COMMON_NETWORK_RELATIVE_LINK_AND_PATH_SUFFIX;
+        }
+        size += ansi_size(&link_info.common_path_suffix);
+        if unicode {
+            let common_path_suffix_unicode = unicode_string::<{ u16::MAX as usize }>(u)?;
+            size += unicode_size(&common_path_suffix_unicode);
+            link_info.common_path_suffix_unicode = Some(common_path_suffix_unicode);
+        }
+
+        link_info.size = size;
+        link_info.volume_id = volume_id;
+        link_info.common_network_relative_link = common_network_relative_link;
+        Ok(link_info)
     }
 }
 
-impl From<&[u8]> for LinkInfo {
-    fn from(data: &[u8]) -> Self {
-        let mut link_info = Self::default();
+impl LinkInfo {
+    /// Parse a LinkInfo structure with the given parsing context.
+    pub(crate) fn parse(data: &[u8], ctx: &ParseContext) -> Result<Self, crate::Error> {
+        ctx.need(data, 0, 0x1c)?;
+        let mut link_info = Self {
+            size: LE::read_u32(data),
+            ..Default::default()
+        };
+        ctx.check_size(0, link_info.size as usize)?;
+        ctx.need(data, 0, link_info.size as usize)?;
 
-        link_info.size = LE::read_u32(data);
         let header_size = LE::read_u32(&data[4..]);
         let extra_offsets_specified = header_size >= 0x24;
         let flags = LinkInfoFlags::from_bits_truncate(LE::read_u32(&data[8..]));
+        link_info._link_info_flags = flags;
         let volume_id_offset = LE::read_u32(&data[12..]) as usize;
         let local_base_path_offset = LE::read_u32(&data[16..]) as usize;
         let common_network_relative_link_offset = LE::read_u32(&data[20..]) as usize;
         let common_path_suffix_offset = LE::read_u32(&data[24..]) as usize;
         let mut local_base_path_offset_unicode = 0;
+        let mut common_path_suffix_offset_unicode = 0;
         if extra_offsets_specified {
+            ctx.need(data, 0, 0x24)?;
             local_base_path_offset_unicode = LE::read_u32(&data[28..]) as usize;
-            let common_path_suffix_offset_unicode = LE::read_u32(&data[32..]) as usize;
+            common_path_suffix_offset_unicode = LE::read_u32(&data[32..]) as usize;
 
             if common_path_suffix_offset_unicode != 0 {
-                link_info.common_path_suffix_unicode = Some(strings::trim_nul_terminated_string(
-                    String::from_utf8_lossy(&data[common_path_suffix_offset_unicode..]).to_string(),
-                ));
+                link_info.common_path_suffix_unicode =
+                    Some(strings::read_nul_terminated_unicode_string(
+                        data,
+                        common_path_suffix_offset_unicode,
+                        ctx,
+                    )?);
             }
         }
         if flags & LinkInfoFlags::VOLUME_ID_AND_LOCAL_BASE_PATH
             == LinkInfoFlags::VOLUME_ID_AND_LOCAL_BASE_PATH
         {
-            assert_ne!(volume_id_offset, 0);
-            assert_ne!(local_base_path_offset, 0);
-            link_info.volume_id = Some(VolumeID::from(&data[volume_id_offset..]));
-            link_info.local_base_path = Some(strings::trim_nul_terminated_string(
-                String::from_utf8_lossy(&data[local_base_path_offset..]).to_string(),
-            ));
+            if volume_id_offset == 0 {
+                return Err(ctx.unexpected_value(12, "VolumeIDOffset", "non-zero", 0));
+            }
+            if local_base_path_offset == 0 {
+                return Err(ctx.unexpected_value(16, "LocalBasePathOffset", "non-zero", 0));
+            }
+            ctx.need(data, volume_id_offset, 1)?;
+            link_info.volume_id = Some(ctx.within("VolumeID", volume_id_offset, || {
+                VolumeID::parse(&data[volume_id_offset..], ctx)
+            })?);
+            link_info.local_base_path = Some(strings::read_nul_terminated_string(
+                data,
+                local_base_path_offset,
+                ctx,
+            )?);
 
             if local_base_path_offset_unicode != 0 {
-                link_info.local_base_path_unicode = Some(strings::trim_nul_terminated_string(
-                    String::from_utf8_lossy(&data[local_base_path_offset_unicode..]).to_string(),
-                ));
+                link_info.local_base_path_unicode =
+                    Some(strings::read_nul_terminated_unicode_string(
+                        data,
+                        local_base_path_offset_unicode,
+                        ctx,
+                    )?);
             }
         }
         if flags & LinkInfoFlags::COMMON_NETWORK_RELATIVE_LINK_AND_PATH_SUFFIX
             == LinkInfoFlags::COMMON_NETWORK_RELATIVE_LINK_AND_PATH_SUFFIX
         {
-            assert_ne!(common_network_relative_link_offset, 0);
-            link_info.common_network_relative_link = Some(CommonNetworkRelativeLink::from(
-                &data[common_network_relative_link_offset..],
-            ));
+            if common_network_relative_link_offset == 0 {
+                return Err(ctx.unexpected_value(
+                    20,
+                    "CommonNetworkRelativeLinkOffset",
+                    "non-zero",
+                    0,
+                ));
+            }
+            ctx.need(data, common_network_relative_link_offset, 1)?;
+            link_info.common_network_relative_link = Some(ctx.within(
+                "CommonNetworkRelativeLink",
+                common_network_relative_link_offset,
+                || {
+                    CommonNetworkRelativeLink::parse(
+                        &data[common_network_relative_link_offset..],
+                        ctx,
+                    )
+                },
+            )?);
         }
-        link_info.common_path_suffix = strings::trim_nul_terminated_string(
-            String::from_utf8_lossy(&data[common_path_suffix_offset..]).to_string(),
+        link_info.common_path_suffix =
+            strings::read_nul_terminated_string(data, common_path_suffix_offset, ctx)?;
+
+        let has_volume_id = flags.contains(LinkInfoFlags::VOLUME_ID_AND_LOCAL_BASE_PATH);
+        let has_network_link =
+            flags.contains(LinkInfoFlags::COMMON_NETWORK_RELATIVE_LINK_AND_PATH_SUFFIX);
+        let mut layout = Layout::new("LinkInfo", link_info.size, header_size);
+        let volume_id_size = link_info.volume_id.as_ref().map(|v| v.layout.size as usize);
+        layout.record(
+            "VolumeIDOffset",
+            volume_id_offset,
+            has_volume_id,
+            volume_id_size,
+        );
+        layout.record(
+            "LocalBasePathOffset",
+            local_base_path_offset,
+            has_volume_id,
+            strings::nul_terminated_size(data, local_base_path_offset, false),
+        );
+        let network_link_size = link_info
+            .common_network_relative_link
+            .as_ref()
+            .map(|l| l.layout.size as usize);
+        layout.record(
+            "CommonNetworkRelativeLinkOffset",
+            common_network_relative_link_offset,
+            has_network_link,
+            network_link_size,
+        );
+        layout.record(
+            "CommonPathSuffixOffset",
+            common_path_suffix_offset,
+            true,
+            strings::nul_terminated_size(data, common_path_suffix_offset, false),
+        );
+        layout.record(
+            "LocalBasePathOffsetUnicode",
+            local_base_path_offset_unicode,
+            has_volume_id,
+            strings::nul_terminated_size(data, local_base_path_offset_unicode, true),
+        );
+        layout.record(
+            "CommonPathSuffixOffsetUnicode",
+            common_path_suffix_offset_unicode,
+            true,
+            strings::nul_terminated_size(data, common_path_suffix_offset_unicode, true),
         );
+        layout.finish();
+        link_info.layout = layout;
 
-        link_info
+        Ok(link_info)
     }
 }
 
-impl Into<Vec<u8>> for LinkInfo {
-    fn into(self) -> Vec<u8> {
+impl TryFrom<&[u8]> for LinkInfo {
+    type Error = crate::Error;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Self::parse(data, &ParseContext::default())
+    }
+}
+
+impl From<LinkInfo> for Vec<u8> {
+    fn from(_val: LinkInfo) -> Self {
         unimplemented!()
     }
 }
 
+/// A declared offset in a LinkInfo structure, or in the VolumeID or
+/// CommonNetworkRelativeLink structure in it, that is not consistent with
+/// where the data it points to was found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OffsetDiscrepancy {
+    structure: Cow<'static, str>,
+    field: Cow<'static, str>,
+    offset: u32,
+    problem: OffsetProblem,
+}
+
+impl OffsetDiscrepancy {
+    /// The name of the structure declaring the offset, as named in the
+    /// specification, for example `"VolumeID"`.
+    pub fn structure(&self) -> &str {
+        &self.structure
+    }
+
+    /// The name of the field holding the offset, as named in the
+    /// specification, for example `"VolumeLabelOffset"`.
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    /// The declared offset, relative to the start of the structure.
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// What is wrong with the offset.
+    pub fn problem(&self) -> &OffsetProblem {
+        &self.problem
+    }
+}
+
+impl fmt::Display for OffsetDiscrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} of 0x{:x} ",
+            self.structure, self.field, self.offset
+        )?;
+        match self.problem {
+            OffsetProblem::OutOfBounds { size } => {
+                write!(f, "points outside the structure's {} bytes", size)
+            }
+            OffsetProblem::Misplaced { expected } => write!(f, "should be 0x{:x}", expected),
+            OffsetProblem::Unexpected => write!(f, "is set, but its flag is not"),
+        }
+    }
+}
+
+/// What is wrong with an [`OffsetDiscrepancy`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OffsetProblem {
+    /// The data the offset points to does not lie entirely within the
+    /// header-less part of the structure, which is `size` bytes long.
+    OutOfBounds {
+        /// The size the structure declares.
+        size: u32,
+    },
+    /// The offset does not point straight after the header or the data
+    /// before it, at `expected`, so there is a gap or an overlap.
+    Misplaced {
+        /// Where the data was expected to start.
+        expected: u32,
+    },
+    /// The offset is not zero, but the flag saying the data it points to is
+    /// present is not set.
+    Unexpected,
+}
+
+/// The offsets declared by a structure in the LinkInfo, and the size of the
+/// data found at each, recorded while parsing so they can be validated.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+struct Layout {
+    /// The name of the structure, as named in the specification.
+    structure: Cow<'static, str>,
+    /// The size the structure declares.
+    size: u32,
+    /// The size of the header, which the data follows.
+    header_size: u32,
+    /// The offsets declared in the header.
+    offsets: Vec<DeclaredOffset>,
+}
+
+/// An offset declared by a structure in the LinkInfo.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+struct DeclaredOffset {
+    /// The name of the field holding the offset, as named in the
+    /// specification.
+    field: Cow<'static, str>,
+    /// The declared offset, relative to the start of the structure.
+    offset: u32,
+    /// Whether the flags say the data the offset points to is present.
+    present: bool,
+    /// The size of the data found at the offset, if it ends within the
+    /// structure.
+    size: Option<usize>,
+    /// Whether the offset is consistent with where the data it points to
+    /// was found.
+    matched: bool,
+}
+
+impl Layout {
+    fn new(structure: &'static str, size: u32, header_size: u32) -> Self {
+        Self {
+            structure: Cow::Borrowed(structure),
+            size,
+            header_size,
+            offsets: Vec::new(),
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    fn record(&mut self, field: &'static str, offset: usize, present: bool, size: Option<usize>) {
+        self.offsets.push(DeclaredOffset {
+            field: Cow::Borrowed(field),
+            offset: offset as u32,
+            present,
+            size,
+            matched: true,
+        });
+    }
+
+    /// Mark the offsets that are not consistent with where their data was
+    /// found, once every offset is recorded.
+    fn finish(&mut self) {
+        let mut discrepancies = Vec::new();
+        self.check(&mut discrepancies);
+        for declared in &mut self.offsets {
+            declared.matched = !discrepancies.iter().any(|d| d.field == declared.field);
+        }
+    }
+
+    fn check(&self, discrepancies: &mut Vec<OffsetDiscrepancy>) {
+        let discrepancy = |field: &Cow<'static, str>, offset, problem| OffsetDiscrepancy {
+            structure: self.structure.clone(),
+            field: field.clone(),
+            offset,
+            problem,
+        };
+        let mut found = Vec::new();
+        for declared in &self.offsets {
+            let (field, offset) = (&declared.field, declared.offset);
+            if offset == 0 {
+                continue;
+            }
+            if !declared.present {
+                discrepancies.push(discrepancy(field, offset, OffsetProblem::Unexpected));
+                continue;
+            }
+            match declared.size {
+                Some(size)
+                    if offset >= self.header_size
+                        && offset as usize + size <= self.size as usize =>
+                {
+                    found.push((offset, size, field))
+                }
+                _ => discrepancies.push(discrepancy(
+                    field,
+                    offset,
+                    OffsetProblem::OutOfBounds { size: self.size },
+                )),
+            }
+        }
+        found.sort();
+        let mut expected = self.header_size;
+        for (offset, size, field) in found {
+            if offset != expected {
+                discrepancies.push(discrepancy(
+                    field,
+                    offset,
+                    OffsetProblem::Misplaced { expected },
+                ));
+            }
+            expected = offset + size as u32;
+        }
+    }
+}
+
 bitflags! {
     /// Flags that specify whether the VolumeID, LocalBasePath, LocalBasePathUnicode,
     /// and CommonNetworkRelativeLink fields are present in this structure.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
     pub struct LinkInfoFlags: u32 {
         /// If set, the VolumeIDand LocalBasePath fields are present, and their
         /// locations are specified by the values of the VolumeIDOffset and
@@ -202,6 +593,8 @@ bitflags! {
 /// target was on when the link was created. This information is useful for
 /// resolving the link if the file is not found in its original location.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct VolumeID {
     /// A 32-bit, unsigned integer that specifies the type of drive the link
     /// target is stored on.
@@ -211,6 +604,12 @@ pub struct VolumeID {
     drive_serial_number: u32,
     /// The label of the volume that the link target is stored on.
     volume_label: String,
+    /// The offsets declared by this structure, as parsed.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Layout::is_empty")
+    )]
+    layout: Layout,
 }
 
 impl VolumeID {
@@ -236,37 +635,97 @@ impl Default for VolumeID {
             drive_type: DriveType::DriveUnknown,
             drive_serial_number: 0,
             volume_label: String::new(),
+            layout: Layout::default(),
         }
     }
 }
 
-impl From<&[u8]> for VolumeID {
-    fn from(data: &[u8]) -> Self {
-        let mut volume_id = VolumeID::default();
+#[cfg(feature = "arbitrary")]
+impl VolumeID {
+    /// The size, in bytes, of the structure as Windows writes it.
+    fn size(&self) -> u32 {
+        0x10 + crate::fuzz::ansi_size(&self.volume_label)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for VolumeID {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {
+            drive_type: u.arbitrary()?,
+            drive_serial_number: u.arbitrary()?,
+            volume_label: crate::fuzz::ansi_string::<32>(u)?,
+            layout: Layout::default(),
+        })
+    }
+}
 
-        let _size = LE::read_u32(data);
-        volume_id.drive_type = DriveType::from_u32(LE::read_u32(&data[4..])).unwrap();
-        volume_id.drive_serial_number = LE::read_u32(&data[8..]);
+impl VolumeID {
+    /// Parse a VolumeID structure with the given parsing context.
+    pub(crate) fn parse(data: &[u8], ctx: &ParseContext) -> Result<Self, crate::Error> {
+        ctx.need(data, 0, 0x10)?;
+        let size = LE::read_u32(data);
+        let drive_type = LE::read_u32(&data[4..]);
+        let drive_type = DriveType::from_u32(drive_type)
+            .ok_or_else(|| ctx.unexpected_value(4, "DriveType", "0 to 6", drive_type))?;
+        let drive_serial_number = LE::read_u32(&data[8..]);
         let mut volume_label_offset = LE::read_u32(&data[12..]) as usize;
-        if volume_label_offset == 0x14 {
-            volume_label_offset /* _unicode */ = LE::read_u32(&data[16..]) as usize;
+        let mut layout = Layout::new("VolumeID", size, 0x10);
+        // A VolumeLabelOffset of 0x14 means the label is stored only in
+        // Unicode, at VolumeLabelOffsetUnicode.
+        let unicode = volume_label_offset == 0x14;
+        if unicode {
+            ctx.need(data, 0, 0x14)?;
+            layout.header_size = 0x14;
+            volume_label_offset = LE::read_u32(&data[16..]) as usize;
+            layout.record(
+                "VolumeLabelOffsetUnicode",
+                volume_label_offset,
+                true,
+                strings::nul_terminated_size(data, volume_label_offset, true),
+            );
+        } else {
+            layout.record(
+                "VolumeLabelOffset",
+                volume_label_offset,
+                true,
+                strings::nul_terminated_size(data, volume_label_offset, false),
+            );
         }
-        volume_id.volume_label = strings::trim_nul_terminated_string(
-            String::from_utf8_lossy(&data[volume_label_offset..]).to_string(),
-        );
+        layout.finish();
+        let volume_label = match unicode {
+            true => strings::read_nul_terminated_unicode_string(data, volume_label_offset, ctx)?,
+            false => strings::read_nul_terminated_string(data, volume_label_offset, ctx)?,
+        };
 
-        volume_id
+        Ok(Self {
+            drive_type,
+            drive_serial_number,
+            volume_label,
+            layout,
+        })
     }
 }
 
-impl Into<Vec<u8>> for VolumeID {
-    fn into(self) -> Vec<u8> {
+impl TryFrom<&[u8]> for VolumeID {
+    type Error = crate::Error;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Self::parse(data, &ParseContext::default())
+    }
+}
+
+impl From<VolumeID> for Vec<u8> {
+    fn from(_val: VolumeID) -> Self {
         unimplemented!()
     }
 }
 
 /// A 32-bit, unsigned integer that specifies the type of drive the link target is stored on.
 #[derive(Clone, Debug, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum DriveType {
     /// The drive type cannot be determined.
     DriveUnknown = 0x00,
@@ -288,6 +747,8 @@ pub enum DriveType {
 /// link target is stored, including the mapped drive letter and the UNC path prefix. For details on
 /// UNC paths, see [MS-DFSNM] section 2.2.1.4.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct CommonNetworkRelativeLink {
     /// Flags that specify the contents of the DeviceNameOffset and
     /// NetProviderType fields.
@@ -301,8 +762,8 @@ pub struct CommonNetworkRelativeLink {
     net_name: String,
     /// A NULL–terminated string, as defined by the system default code
     /// page, which specifies a device; for example, the drive letter
-    /// "D:".
-    device_name: String,
+    /// "D:". This is present only if the ValidDevice flag is set.
+    device_name: Option<String>,
     /// An optional, NULL–terminated, Unicode string that is the
     /// Unicode version of the NetName string. This field MUST be
     /// present if the value of the NetNameOffset field is greater
@@ -313,6 +774,12 @@ pub struct CommonNetworkRelativeLink {
     /// present if the value of the NetNameOffset field is greater than
     /// 0x00000014; otherwise, this field MUST NOT be present.
     device_name_unicode: Option<String>,
+    /// The offsets declared by this structure, as parsed.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Layout::is_empty")
+    )]
+    layout: Layout,
 }
 
 impl Default for CommonNetworkRelativeLink {
@@ -321,20 +788,150 @@ impl Default for CommonNetworkRelativeLink {
             flags: CommonNetworkRelativeLinkFlags::empty(),
             network_provider_type: None,
             net_name: String::new(),
-            device_name: String::new(),
+            device_name: None,
             net_name_unicode: None,
             device_name_unicode: None,
+            layout: Layout::default(),
         }
     }
 }
 
-impl From<&[u8]> for CommonNetworkRelativeLink {
-    fn from(data: &[u8]) -> Self {
-        let mut link = CommonNetworkRelativeLink::default();
+impl CommonNetworkRelativeLink {
+    /// Flags that specify the contents of the DeviceNameOffset and
+    /// NetProviderType fields.
+    pub fn flags(&self) -> &CommonNetworkRelativeLinkFlags {
+        &self.flags
+    }
+    /// The type of network provider, if the ValidNetType flag is set and the
+    /// value is a known one.
+    pub fn network_provider_type(&self) -> &Option<NetworkProviderType> {
+        &self.network_provider_type
+    }
+    /// The server share path; for example, "\\server\share". This is
+    /// the Unicode version if present, otherwise the version in the system
+    /// default code page.
+    pub fn net_name(&self) -> &String {
+        self.net_name_unicode.as_ref().unwrap_or(&self.net_name)
+    }
+    /// The device; for example, the drive letter "D:", if the ValidDevice
+    /// flag is set. This is the Unicode version if present, otherwise the
+    /// version in the system default code page.
+    pub fn device_name(&self) -> Option<&String> {
+        self.device_name_unicode
+            .as_ref()
+            .or(self.device_name.as_ref())
+    }
+    /// A NULL–terminated string, as defined by the system default code
+    /// page, which specifies a server share path; for example,
+    /// "\\server\share".
+    pub fn net_name_ansi(&self) -> &String {
+        &self.net_name
+    }
+    /// A NULL–terminated string, as defined by the system default code
+    /// page, which specifies a device; for example, the drive letter
+    /// "D:", if the ValidDevice flag is set.
+    pub fn device_name_ansi(&self) -> &Option<String> {
+        &self.device_name
+    }
+    /// An optional, NULL–terminated, Unicode string that is the Unicode
+    /// version of the NetName string.
+    pub fn net_name_unicode(&self) -> &Option<String> {
+        &self.net_name_unicode
+    }
+    /// An optional, NULL–terminated, Unicode string that is the Unicode
+    /// version of the DeviceName string.
+    pub fn device_name_unicode(&self) -> &Option<String> {
+        &self.device_name_unicode
+    }
+
+    /// Remove identifying details from the share and device names.
+    pub(crate) fn redact(&mut self, redactor: &crate::redact::Redactor) {
+        redactor.string(&mut self.net_name);
+        for s in [
+            &mut self.device_name,
+            &mut self.net_name_unicode,
+            &mut self.device_name_unicode,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            redactor.string(s);
+        }
+    }
+}
 
+#[cfg(feature = "arbitrary")]
+impl CommonNetworkRelativeLink {
+    /// The size, in bytes, of the structure as Windows writes it.
+    fn size(&self) -> u32 {
+        use crate::fuzz::{ansi_size, unicode_size};
+
+        let has_device = self
+            .flags
+            .contains(CommonNetworkRelativeLinkFlags::VALID_DEVICE);
+        let mut size = 0x14 + ansi_size(&self.net_name);
+        if let Some(device_name) = &self.device_name {
+            size += ansi_size(device_name);
+        }
+        if let Some(net_name_unicode) = &self.net_name_unicode {
+            size += 8 + unicode_size(net_name_unicode);
+        }
+        if let (true, Some(device_name_unicode)) = (has_device, &self.device_name_unicode) {
+            size += unicode_size(device_name_unicode);
+        }
+        size
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for CommonNetworkRelativeLink {
+    /// Generate a CommonNetworkRelativeLink whose flags match the fields
+    /// present, with the Unicode names either both present or both absent.
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        use crate::fuzz::{ansi_string, unicode_string};
+
+        let network_provider_type: Option<NetworkProviderType> = u.arbitrary()?;
+        let device: bool = u.arbitrary()?;
+        let unicode: bool = u.arbitrary()?;
+        let mut link = Self {
+            net_name: ansi_string::<{ u16::MAX as usize }>(u)?,
+            ..Default::default()
+        };
+        if network_provider_type.is_some() {
+            link.flags |= CommonNetworkRelativeLinkFlags::VALID_NET_TYPE;
+        }
+        if device {
+            link.flags |= CommonNetworkRelativeLinkFlags::VALID_DEVICE;
+            link.device_name = Some(ansi_string::<{ u16::MAX as usize }>(u)?);
+        }
+        if unicode {
+            link.net_name_unicode = Some(unicode_string::<{ u16::MAX as usize }>(u)?);
+            if device {
+                link.device_name_unicode = Some(unicode_string::<{ u16::MAX as usize }>(u)?);
+            }
+        }
+        link.network_provider_type = network_provider_type;
+        Ok(link)
+    }
+}
+
+impl CommonNetworkRelativeLink {
+    /// Parse a CommonNetworkRelativeLink structure with the given parsing context.
+    pub(crate) fn parse(data: &[u8], ctx: &ParseContext) -> Result<Self, crate::Error> {
+        ctx.need(data, 0, 0x14)?;
         let size = LE::read_u32(data);
-        assert!(size >= 0x14);
-        link.flags = CommonNetworkRelativeLinkFlags::from_bits_truncate(LE::read_u32(&data[4..]));
+        if size < 0x14 {
+            return Err(ctx.unexpected_value(
+                0,
+                "CommonNetworkRelativeSize",
+                "at least 0x14",
+                format_args!("0x{:x}", size),
+            ));
+        }
+        let mut link = CommonNetworkRelativeLink {
+            flags: CommonNetworkRelativeLinkFlags::from_bits_truncate(LE::read_u32(&data[4..])),
+            ..Default::default()
+        };
         let net_name_offset = LE::read_u32(&data[8..]) as usize;
         let device_name_offset = LE::read_u32(&data[12..]) as usize;
         if link.flags & CommonNetworkRelativeLinkFlags::VALID_NET_TYPE
@@ -342,35 +939,87 @@ impl From<&[u8]> for CommonNetworkRelativeLink {
         {
             link.network_provider_type = NetworkProviderType::from_u32(LE::read_u32(&data[16..]));
         }
-        link.net_name = strings::trim_nul_terminated_string(
-            String::from_utf8_lossy(&data[net_name_offset..]).to_string(),
+        let has_device = link
+            .flags
+            .contains(CommonNetworkRelativeLinkFlags::VALID_DEVICE);
+        link.net_name = strings::read_nul_terminated_string(data, net_name_offset, ctx)?;
+        if has_device {
+            if device_name_offset == 0 {
+                return Err(ctx.unexpected_value(12, "DeviceNameOffset", "non-zero", 0));
+            }
+            link.device_name = Some(strings::read_nul_terminated_string(
+                data,
+                device_name_offset,
+                ctx,
+            )?);
+        }
+        let mut layout = Layout::new("CommonNetworkRelativeLink", size, 0x14);
+        layout.record(
+            "NetNameOffset",
+            net_name_offset,
+            true,
+            strings::nul_terminated_size(data, net_name_offset, false),
         );
-        link.device_name = strings::trim_nul_terminated_string(
-            String::from_utf8_lossy(&data[device_name_offset..]).to_string(),
+        layout.record(
+            "DeviceNameOffset",
+            device_name_offset,
+            has_device,
+            strings::nul_terminated_size(data, device_name_offset, false),
         );
-        if net_name_offset >= 0x14 {
+        if net_name_offset > 0x14 {
+            ctx.need(data, 0, 0x1c)?;
             let net_name_offset_unicode = LE::read_u32(&data[20..]) as usize;
             let device_name_offset_unicode = LE::read_u32(&data[24..]) as usize;
-            link.net_name_unicode = Some(strings::trim_nul_terminated_string(
-                String::from_utf8_lossy(&data[net_name_offset_unicode..]).to_string(),
-            ));
-            link.device_name_unicode = Some(strings::trim_nul_terminated_string(
-                String::from_utf8_lossy(&data[device_name_offset_unicode..]).to_string(),
-            ));
+            layout.header_size = 0x1c;
+            layout.record(
+                "NetNameOffsetUnicode",
+                net_name_offset_unicode,
+                true,
+                strings::nul_terminated_size(data, net_name_offset_unicode, true),
+            );
+            layout.record(
+                "DeviceNameOffsetUnicode",
+                device_name_offset_unicode,
+                has_device,
+                strings::nul_terminated_size(data, device_name_offset_unicode, true),
+            );
+            link.net_name_unicode = Some(strings::read_nul_terminated_unicode_string(
+                data,
+                net_name_offset_unicode,
+                ctx,
+            )?);
+            if has_device && device_name_offset_unicode != 0 {
+                link.device_name_unicode = Some(strings::read_nul_terminated_unicode_string(
+                    data,
+                    device_name_offset_unicode,
+                    ctx,
+                )?);
+            }
         }
+        layout.finish();
+        link.layout = layout;
+
+        Ok(link)
+    }
+}
+
+impl TryFrom<&[u8]> for CommonNetworkRelativeLink {
+    type Error = crate::Error;
 
-        link
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Self::parse(data, &ParseContext::default())
     }
 }
 
-impl Into<Vec<u8>> for CommonNetworkRelativeLink {
-    fn into(self) -> Vec<u8> {
+impl From<CommonNetworkRelativeLink> for Vec<u8> {
+    fn from(_val: CommonNetworkRelativeLink) -> Self {
         unimplemented!()
     }
 }
 
 bitflags! {
     /// Flags that specify the contents of the DeviceNameOffset and NetProviderType fields.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
     pub struct CommonNetworkRelativeLinkFlags: u32 {
         /// If set, the DeviceNameOffset field contains an offset to the device
         /// name. If not set, the DeviceNameOffset field does not contain an
@@ -386,6 +1035,9 @@ bitflags! {
 /// A 32-bit, unsigned integer that specifies the type of network provider.
 #[allow(missing_docs)]
 #[derive(Clone, Debug, FromPrimitive, ToPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum NetworkProviderType {
     Avid = 0x1a0000,
     Docuspace = 0x1b0000,
@@ -1,13 +1,18 @@
+use std::convert::TryFrom;
 use std::fmt;
 
-use byteorder::{ByteOrder, LE};
 #[allow(unused)]
-use log::{debug, error, info, trace, warn};
+use crate::logging::{debug, error, info, trace, warn};
+use byteorder::{ByteOrder, LE};
+
+use crate::ParseContext;
 
 /// The LinkTargetIDList structure specifies the target of the link. The presence of this optional
 /// structure is specified by the HasLinkTargetIDList bit (LinkFlagssection 2.1.1) in the
 /// ShellLinkHeader(section2.1).
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct LinkTargetIdList {
     /// The size, in bytes, of the IDList field.
     pub size: u16,
@@ -24,44 +29,103 @@ impl LinkTargetIdList {
     }
 }
 
-impl Default for LinkTargetIdList {
-    fn default() -> Self {
-        Self {
-            size: 0,
+impl LinkTargetIdList {
+    /// Parse a LinkTargetIDList structure with the given parsing context.
+    pub(crate) fn parse(data: &[u8], ctx: &ParseContext) -> Result<Self, crate::Error> {
+        ctx.need(data, 0, 2)?;
+        let mut id_list = Self {
+            size: LE::read_u16(&data[0..]),
+            ..Default::default()
+        };
+        trace!("ID List size: {}", id_list.size);
+        ctx.check_size(0, id_list.size as usize)?;
+        let end = 2 + id_list.size as usize;
+        ctx.need(data, 0, end)?;
+        id_list.id_list = parse_id_list(&data[..end], 2, ctx)?;
+        Ok(id_list)
+    }
+}
+
+impl LinkTargetIdList {
+    /// Remove identifying details from the items, keeping their sizes.
+    pub(crate) fn redact(&mut self, redactor: &crate::redact::Redactor) {
+        for id in &mut self.id_list {
+            id.redact(redactor);
+        }
+    }
+}
+
+/// Parse the ItemIDs of an IDList that starts `offset` bytes into `data`,
+/// stopping at the TerminalID. The IDList must end within `data`.
+pub(crate) fn parse_id_list(
+    data: &[u8],
+    mut offset: usize,
+    ctx: &ParseContext,
+) -> Result<Vec<ItemID>, crate::Error> {
+    let mut id_list = Vec::new();
+    loop {
+        // Read an ItemID, or the TerminalID that ends the list
+        ctx.need(data, offset, 2)?;
+        let size = LE::read_u16(&data[offset..]) as usize;
+        if size == 0 {
+            break;
+        }
+        if size < 2 || offset + size > data.len() {
+            return Err(ctx.unexpected_value(
+                offset,
+                "ItemIDSize",
+                format_args!("2 to {}", data.len() - offset),
+                size,
+            ));
+        }
+        let id = ItemID {
+            size: size as u16,
+            data: data[offset + 2..offset + size].to_vec(),
+        };
+        debug!("Read {:?}", id);
+        id_list.push(id);
+        offset += size;
+    }
+    Ok(id_list)
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for LinkTargetIdList {
+    /// Generate ItemIDs for as long as they fit, with the size of the IDList
+    /// matching them.
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut id_list = Self {
+            size: 2,
             id_list: Vec::new(),
+        };
+        for item in u.arbitrary_iter::<ItemID>()? {
+            let item = item?;
+            match id_list.size.checked_add(item.size) {
+                Some(size) => id_list.size = size,
+                None => break,
+            }
+            id_list.id_list.push(item);
         }
+        Ok(id_list)
     }
 }
 
-impl From<&[u8]> for LinkTargetIdList {
+impl TryFrom<&[u8]> for LinkTargetIdList {
+    type Error = crate::Error;
+
     /// Read data into this struct from a `[u8]`.
-    fn from(data: &[u8]) -> Self {
-        let mut id_list = Self::default();
-        id_list.size = LE::read_u16(&data[0..]);
-        trace!("ID List size: {}", id_list.size);
-        let mut inner_data = &data[2..(id_list.size as usize)];
-        assert!(inner_data.len() == id_list.size as usize - 2);
-        let mut read_bytes = 2;
-        while read_bytes < id_list.size {
-            // Read an ItemID
-            let id = ItemID::from(inner_data);
-            debug!("Read {:?}", id);
-            let size = id.size;
-            id_list.id_list.push(id);
-            inner_data = &inner_data[(size as usize)..];
-            read_bytes += size;
-        }
-        id_list
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Self::parse(data, &ParseContext::default())
     }
 }
 
-impl Into<Vec<u8>> for LinkTargetIdList {
-    fn into(self) -> Vec<u8> {
+impl From<LinkTargetIdList> for Vec<u8> {
+    fn from(val: LinkTargetIdList) -> Self {
         let mut data = Vec::new();
 
         let size = 2u16;
         LE::write_u16(&mut data[0..2], size);
-        for id in self.id_list {
+        for id in val.id_list {
             let mut other_data = id.into();
             data.append(&mut other_data);
         }
@@ -71,7 +135,9 @@ impl Into<Vec<u8>> for LinkTargetIdList {
 }
 
 /// The stored IDList structure specifies the format of a persisted item ID list.
-#[derive(Clone)]
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ItemID {
     /// A 16-bit, unsigned integer that specifies the size, in bytes, of the ItemID structure,
     /// including the ItemIDSize field.
@@ -85,14 +151,23 @@ impl ItemID {
     pub fn data(&self) -> &Vec<u8> {
         &self.data
     }
+
+    /// Remove identifying details from the data, keeping its size.
+    pub(crate) fn redact(&mut self, redactor: &crate::redact::Redactor) {
+        redactor.bytes(&mut self.data);
+    }
 }
 
-impl Default for ItemID {
-    fn default() -> Self {
-        Self {
-            size: 0,
-            data: Vec::new(),
-        }
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for ItemID {
+    /// Generate an ItemID whose size matches its data.
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut data: Vec<u8> = u.arbitrary()?;
+        data.truncate(u16::MAX as usize - 2);
+        Ok(Self {
+            size: data.len() as u16 + 2,
+            data,
+        })
     }
 }
 
@@ -102,25 +177,32 @@ impl fmt::Debug for ItemID {
     }
 }
 
-impl From<&[u8]> for ItemID {
-    fn from(data: &[u8]) -> Self {
-        let mut id = Self::default();
+impl TryFrom<&[u8]> for ItemID {
+    type Error = crate::Error;
 
-        id.size = LE::read_u16(data);
-        id.data = Vec::from(&data[2..(id.size as usize)]);
+    /// Read an ItemID from the start of `data`.
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        let ctx = ParseContext::default();
+        ctx.need(data, 0, 2)?;
+        let size = LE::read_u16(data);
+        if size < 2 {
+            return Err(ctx.unexpected_value(0, "ItemIDSize", "at least 2", size));
+        }
+        ctx.need(data, 0, size as usize)?;
+        let data = Vec::from(&data[2..(size as usize)]);
 
-        id
+        Ok(Self { size, data })
     }
 }
 
-impl Into<Vec<u8>> for ItemID {
-    fn into(self) -> Vec<u8> {
+impl From<ItemID> for Vec<u8> {
+    fn from(val: ItemID) -> Self {
         let mut data = Vec::new();
 
-        assert_eq!(self.data.len() as u16 + 2, self.size);
+        assert_eq!(val.data.len() as u16 + 2, val.size);
 
-        LE::write_u16(&mut data, self.size);
-        let mut other_data = self.data.clone();
+        LE::write_u16(&mut data, val.size);
+        let mut other_data = val.data.clone();
         data.append(&mut other_data);
 
         data
@@ -0,0 +1,88 @@
+use crate::guids::CLSID_NETWORK_PLACES;
+use crate::linkinfo::DriveType;
+use crate::{ExtraData, Guid, ShellLink};
+
+/// Where the target of a shell link is stored, for sorting large numbers of
+/// shell links. See [`ShellLink::target_location`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum TargetLocation {
+    /// On a fixed drive or RAM disk of the machine the link was made on.
+    LocalFixed,
+    /// On removable media, such as a USB drive or CD-ROM.
+    Removable,
+    /// On a network share.
+    Network,
+    /// At a path made with environment variables, which depends on the
+    /// machine and user that resolve it.
+    Environment,
+    /// Nowhere that can be told from the shell link.
+    Unknown,
+}
+
+impl ShellLink {
+    /// Classify where the target is stored. This is decided by, in order:
+    ///
+    /// - the drive type of the volume in the LinkInfo, or its
+    ///   CommonNetworkRelativeLink;
+    /// - an EnvironmentVariableDataBlock;
+    /// - the items of the LinkTargetIDList, which show whether the target is
+    ///   on the network, but not the type of a drive.
+    pub fn target_location(&self) -> TargetLocation {
+        if let Some(link_info) = self.link_info() {
+            if let Some(volume) = link_info.volume_id() {
+                match volume.drive_type() {
+                    DriveType::DriveFixed | DriveType::DriveRamdisk => {
+                        return TargetLocation::LocalFixed
+                    }
+                    DriveType::DriveRemovable | DriveType::DriveCDRom => {
+                        return TargetLocation::Removable
+                    }
+                    DriveType::DriveRemote => return TargetLocation::Network,
+                    DriveType::DriveUnknown | DriveType::DriveNoRootDir => {}
+                }
+            }
+            if link_info.common_network_relative_link().is_some() {
+                return TargetLocation::Network;
+            }
+        }
+        if self
+            .extra_data()
+            .iter()
+            .any(|block| matches!(block, ExtraData::EnvironmentProps(_)))
+        {
+            return TargetLocation::Environment;
+        }
+        if let Some(id_list) = self.link_target_id_list() {
+            let on_network = id_list.id_list().iter().any(|item| {
+                let data = item.data();
+                match data.first() {
+                    // A root folder item, holding the CLSID of the folder.
+                    Some(0x1f) => data.get(2..18).map(Guid::read) == Some(CLSID_NETWORK_PLACES),
+                    // A network location item, such as a server or share.
+                    Some(class) => class & 0x70 == 0x40,
+                    None => false,
+                }
+            });
+            if on_network {
+                return TargetLocation::Network;
+            }
+        }
+        TargetLocation::Unknown
+    }
+
+    /// Whether the target is on a network share.
+    pub fn is_network_target(&self) -> bool {
+        self.target_location() == TargetLocation::Network
+    }
+
+    /// Whether the target is on a drive of the machine the link was made on,
+    /// fixed or removable.
+    pub fn is_local_target(&self) -> bool {
+        matches!(
+            self.target_location(),
+            TargetLocation::LocalFixed | TargetLocation::Removable
+        )
+    }
+}
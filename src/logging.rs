@@ -0,0 +1,49 @@
+//! The logging macros used throughout the crate. With the `log` feature they
+//! are those of the `log` crate; without it they compile to nothing, though
+//! their arguments are still type-checked.
+
+#![cfg_attr(not(feature = "log"), allow(unused_macros))]
+
+#[cfg(feature = "log")]
+pub(crate) use log::{debug, error, info, trace, warn};
+
+#[cfg(not(feature = "log"))]
+macro_rules! discard {
+    ($($arg:tt)+) => {
+        if false {
+            let _ = format_args!($($arg)+);
+        }
+    };
+}
+
+#[cfg(not(feature = "log"))]
+macro_rules! discard_debug {
+    ($($arg:tt)+) => { $crate::logging::discard!($($arg)+) };
+}
+
+#[cfg(not(feature = "log"))]
+macro_rules! discard_error {
+    ($($arg:tt)+) => { $crate::logging::discard!($($arg)+) };
+}
+
+#[cfg(not(feature = "log"))]
+macro_rules! discard_info {
+    ($($arg:tt)+) => { $crate::logging::discard!($($arg)+) };
+}
+
+#[cfg(not(feature = "log"))]
+macro_rules! discard_trace {
+    ($($arg:tt)+) => { $crate::logging::discard!($($arg)+) };
+}
+
+#[cfg(not(feature = "log"))]
+macro_rules! discard_warn {
+    ($($arg:tt)+) => { $crate::logging::discard!($($arg)+) };
+}
+
+#[cfg(not(feature = "log"))]
+#[allow(unused_imports)]
+pub(crate) use {
+    discard, discard_debug as debug, discard_error as error, discard_info as info,
+    discard_trace as trace, discard_warn as warn,
+};
@@ -0,0 +1,254 @@
+#[allow(unused)]
+use crate::logging::{debug, error, info, trace, warn};
+
+use encoding_rs::Encoding;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+
+use crate::{Error, ShellLinkHeader, DEFAULT_MAX_STRING_LEN};
+
+/// The default maximum number of bytes read when parsing a shell link from a
+/// file or reader. Shell links are rarely larger than a few kilobytes.
+pub const DEFAULT_MAX_INPUT_SIZE: usize = 16 * 1024 * 1024;
+
+/// The default maximum size, in bytes, a single structure in a shell link may
+/// declare.
+pub const DEFAULT_MAX_STRUCTURE_SIZE: usize = 4 * 1024 * 1024;
+
+/// Options controlling how a shell link is parsed.
+///
+/// Construct one with struct update syntax, for example
+/// `ParseOptions { strict: true, ..Default::default() }`, so that options
+/// added later keep their defaults.
+#[derive(Clone, Debug)]
+pub struct ParseOptions {
+    /// Fail with [`Error::NonConformant`] when the shell link does not
+    /// conform to the specification, instead of logging a warning and
+    /// carrying on. Defaults to `false`.
+    pub strict: bool,
+    /// The code page used to decode strings that are stored in the system
+    /// default code page of the machine that wrote the shell link, rather
+    /// than as Unicode. Defaults to Windows-1252.
+    pub default_encoding: &'static Encoding,
+    /// The maximum length, in bytes, of a NULL-terminated string. Defaults
+    /// to [`DEFAULT_MAX_STRING_LEN`].
+    pub max_string_len: usize,
+    /// When an ExtraData block is malformed, skip it and carry on with the
+    /// next one, or if its BlockSize is unusable keep the blocks read before
+    /// it, instead of failing. Defaults to `true`.
+    pub recover_extra_data: bool,
+    /// When the LinkTargetIDList or LinkInfo is malformed but declares its
+    /// size, skip to the end of it and carry on with the structures after
+    /// it, instead of failing. Defaults to `true`.
+    pub resynchronize: bool,
+    /// The maximum number of bytes read when parsing from a file or reader.
+    /// Defaults to [`DEFAULT_MAX_INPUT_SIZE`].
+    pub max_input_size: usize,
+    /// The maximum size, in bytes, a LinkTargetIDList, LinkInfo or ExtraData
+    /// block may declare. Defaults to [`DEFAULT_MAX_STRUCTURE_SIZE`].
+    pub max_structure_size: usize,
+    /// Keep a copy of any data found after the end of the shell link, as
+    /// well as its offset and size. Defaults to `false`.
+    pub keep_trailing_data: bool,
+    /// Parse only the ShellLinkHeader, leaving every other structure unset,
+    /// and read no more than the 0x4C bytes it occupies. Defaults to
+    /// `false`.
+    pub header_only: bool,
+    /// Parse data whose HeaderSize or LinkCLSID is not that of a shell
+    /// link, as some malicious shell links change them to evade tools,
+    /// reading the header as though they were right. Each wrong value is
+    /// reported as a [`ParseWarning`], or in strict mode fails the parse with
+    /// [`Error::NonConformant`]. Defaults to `false`.
+    pub ignore_signature: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            strict: false,
+            default_encoding: encoding_rs::WINDOWS_1252,
+            max_string_len: DEFAULT_MAX_STRING_LEN,
+            recover_extra_data: true,
+            resynchronize: true,
+            max_input_size: DEFAULT_MAX_INPUT_SIZE,
+            max_structure_size: DEFAULT_MAX_STRUCTURE_SIZE,
+            keep_trailing_data: false,
+            header_only: false,
+            ignore_signature: false,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Whether `data` should be parsed as a shell link: it starts with the
+    /// signature of one, or the signature is ignored.
+    pub(crate) fn accepts_signature(&self, data: &[u8]) -> bool {
+        ShellLinkHeader::has_signature(data) || (self.ignore_signature && data.len() >= 0x4c)
+    }
+
+    /// Decode a string stored in the system default code page.
+    pub(crate) fn decode(&self, data: &[u8]) -> String {
+        crate::strings::decode_exact(self.default_encoding, data)
+    }
+}
+
+/// A problem found while parsing a shell link that did not stop the parse,
+/// because the structure it was found in could be skipped or read in a
+/// degraded form.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseWarning {
+    structure: &'static str,
+    offset: usize,
+    message: String,
+}
+
+impl ParseWarning {
+    /// The name of the structure the problem was found in, as named in the
+    /// specification, for example `"LinkInfo"`.
+    pub fn structure(&self) -> &str {
+        self.structure
+    }
+
+    /// The offset in the shell link, in bytes, at which the problem was
+    /// found.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// A description of the problem.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at offset 0x{:x}: {}",
+            self.structure, self.offset, self.message
+        )
+    }
+}
+
+/// The state shared by the parsers of each structure while a shell link is
+/// parsed: the options, the structure being parsed and where it starts, and
+/// the warnings so far.
+#[derive(Debug, Default)]
+pub(crate) struct ParseContext {
+    pub(crate) options: ParseOptions,
+    structure: Cell<&'static str>,
+    offset: Cell<usize>,
+    warnings: RefCell<Vec<ParseWarning>>,
+}
+
+impl ParseContext {
+    pub(crate) fn new(options: &ParseOptions) -> Self {
+        Self {
+            options: options.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Record that the data parsed from now on is part of `structure`, which
+    /// starts `offset` bytes into the shell link.
+    pub(crate) fn enter(&self, structure: &'static str, offset: usize) {
+        self.structure.set(structure);
+        self.offset.set(offset);
+    }
+
+    /// Run `f` to parse `structure`, which is nested `offset` bytes into the
+    /// current structure.
+    pub(crate) fn within<T>(
+        &self,
+        structure: &'static str,
+        offset: usize,
+        f: impl FnOnce() -> T,
+    ) -> T {
+        let outer = (self.structure.get(), self.offset.get());
+        self.enter(structure, outer.1 + offset);
+        let result = f();
+        self.enter(outer.0, outer.1);
+        result
+    }
+
+    /// Report that the current structure does not conform to the
+    /// specification `offset` bytes into it: an error in strict mode,
+    /// otherwise a warning.
+    pub(crate) fn nonconformance(&self, offset: usize, message: String) -> Result<(), Error> {
+        let structure = self.structure.get();
+        let offset = self.offset.get() + offset;
+        if self.options.strict {
+            return Err(Error::NonConformant {
+                structure,
+                offset,
+                message,
+            });
+        }
+        let warning = ParseWarning {
+            structure,
+            offset,
+            message,
+        };
+        warn!("{}", warning);
+        self.warnings.borrow_mut().push(warning);
+        Ok(())
+    }
+
+    /// An error for a field `offset` bytes into the current structure that
+    /// holds a value the parser cannot handle.
+    pub(crate) fn unexpected_value(
+        &self,
+        offset: usize,
+        field: &'static str,
+        expected: impl fmt::Display,
+        found: impl fmt::Display,
+    ) -> Error {
+        Error::UnexpectedValue {
+            structure: self.structure.get(),
+            offset: self.offset.get() + offset,
+            field,
+            expected: expected.to_string(),
+            found: found.to_string(),
+        }
+    }
+
+    /// Check that a size of `size` bytes, declared by the current structure
+    /// `offset` bytes into it, is within the configured limit.
+    pub(crate) fn check_size(&self, offset: usize, size: usize) -> Result<(), Error> {
+        let limit = self.options.max_structure_size;
+        if size > limit {
+            return Err(Error::LimitExceeded {
+                structure: self.structure.get(),
+                offset: self.offset.get() + offset,
+                limit,
+                size,
+            });
+        }
+        Ok(())
+    }
+
+    /// Check that `needed` bytes are available `offset` bytes into the
+    /// current structure, whose remaining data is `data`.
+    pub(crate) fn need(&self, data: &[u8], offset: usize, needed: usize) -> Result<(), Error> {
+        let available = data.len().saturating_sub(offset);
+        if available < needed {
+            return Err(Error::UnexpectedEof {
+                structure: self.structure.get(),
+                offset: self.offset.get() + offset,
+                needed,
+                available,
+            });
+        }
+        Ok(())
+    }
+
+    /// Decode a string stored in the system default code page.
+    pub(crate) fn decode(&self, data: &[u8]) -> String {
+        self.options.decode(data)
+    }
+
+    pub(crate) fn into_warnings(self) -> Vec<ParseWarning> {
+        self.warnings.into_inner()
+    }
+}
@@ -0,0 +1,175 @@
+//! Python bindings, as a module named `lnk` with a `ShellLink` class.
+//!
+//! Build and install the module with [maturin](https://www.maturin.rs/),
+//! which reads its settings from `pyproject.toml`:
+//!
+//! ```sh
+//! maturin develop --release
+//! ```
+//!
+//! and then, in Python:
+//!
+//! ```python
+//! import lnk
+//! link = lnk.ShellLink.open("shortcut.lnk")
+//! print(link.local_base_path, link.arguments, link.write_time)
+//! ```
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use pyo3::exceptions::{PyOSError, PyValueError};
+use pyo3::prelude::*;
+
+use crate::{Error, ShellLink};
+
+impl From<Error> for PyErr {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::IoError(e) => PyOSError::new_err(e.to_string()),
+            e => PyValueError::new_err(e.to_string()),
+        }
+    }
+}
+
+/// A parsed shell link (.lnk file).
+#[pyclass(name = "ShellLink", module = "lnk", frozen)]
+struct PyShellLink(ShellLink);
+
+#[pymethods]
+impl PyShellLink {
+    /// Open and parse the shell link at `path`.
+    #[staticmethod]
+    fn open(path: PathBuf) -> PyResult<Self> {
+        Ok(Self(ShellLink::open(path)?))
+    }
+
+    /// Parse a shell link from the start of `data`.
+    #[staticmethod]
+    fn from_bytes(data: &[u8]) -> PyResult<Self> {
+        let (link, _) = ShellLink::from_slice(data)?;
+        Ok(Self(link))
+    }
+
+    /// The name (description) of the shell link, or None.
+    #[getter]
+    fn name(&self) -> Option<&str> {
+        self.0.name().as_deref()
+    }
+
+    /// The path of the target relative to the shell link, or None.
+    #[getter]
+    fn relative_path(&self) -> Option<&str> {
+        self.0.relative_path().as_deref()
+    }
+
+    /// The working directory to launch the target in, or None.
+    #[getter]
+    fn working_dir(&self) -> Option<&str> {
+        self.0.working_dir().as_deref()
+    }
+
+    /// The command line arguments to launch the target with, or None.
+    #[getter]
+    fn arguments(&self) -> Option<&str> {
+        self.0.arguments().as_deref()
+    }
+
+    /// The location of the icon, or None.
+    #[getter]
+    fn icon_location(&self) -> Option<&str> {
+        self.0.icon_location().as_deref()
+    }
+
+    /// The index of the icon within the icon location.
+    #[getter]
+    fn icon_index(&self) -> i32 {
+        self.0.header().icon_index()
+    }
+
+    /// The local base path of the target from the LinkInfo, preferring the
+    /// Unicode one, or None.
+    #[getter]
+    fn local_base_path(&self) -> Option<&str> {
+        let link_info = self.0.link_info().as_ref()?;
+        link_info
+            .local_base_path_unicode()
+            .as_deref()
+            .or(link_info.local_base_path().as_deref())
+    }
+
+    /// The LinkFlags, as an integer.
+    #[getter]
+    fn link_flags(&self) -> u32 {
+        self.0.header().link_flags().bits()
+    }
+
+    /// The target's file attributes, as an integer.
+    #[getter]
+    fn file_attributes(&self) -> u32 {
+        self.0.header().file_attributes().bits()
+    }
+
+    /// The creation time of the target, as a UTC datetime, or None if it is
+    /// not set or not valid.
+    #[getter]
+    fn creation_time(&self) -> Option<DateTime<Utc>> {
+        self.0.header().creation_time().datetime_opt()
+    }
+
+    /// The access time of the target, as a UTC datetime, or None if it is
+    /// not set or not valid.
+    #[getter]
+    fn access_time(&self) -> Option<DateTime<Utc>> {
+        self.0.header().access_time().datetime_opt()
+    }
+
+    /// The write time of the target, as a UTC datetime, or None if it is
+    /// not set or not valid.
+    #[getter]
+    fn write_time(&self) -> Option<DateTime<Utc>> {
+        self.0.header().write_time().datetime_opt()
+    }
+
+    /// The size of the target, in bytes.
+    #[getter]
+    fn file_size(&self) -> u32 {
+        self.0.header().file_size()
+    }
+
+    /// The ShowCommand, as stored.
+    #[getter]
+    fn show_command(&self) -> u32 {
+        self.0.header().show_command_raw()
+    }
+
+    /// The hotkey, such as "Ctrl+Alt+F5", or "None".
+    #[getter]
+    fn hotkey(&self) -> String {
+        self.0.header().hotkey().to_string()
+    }
+
+    /// The problems found while parsing that did not stop the parse.
+    #[getter]
+    fn warnings(&self) -> Vec<String> {
+        self.0.warnings().iter().map(ToString::to_string).collect()
+    }
+
+    /// Serialize the whole shell link to JSON.
+    fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string(&self.0).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    fn __repr__(&self) -> String {
+        match self.local_base_path().or(self.relative_path()) {
+            Some(target) => format!("<ShellLink {:?}>", target),
+            None => "<ShellLink>".to_string(),
+        }
+    }
+}
+
+/// Parse Windows shell links (.lnk files).
+#[pymodule]
+fn lnk(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyShellLink>()
+}
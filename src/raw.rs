@@ -0,0 +1,135 @@
+use byteorder::{ByteOrder, LE};
+use std::ops::Range;
+
+use crate::borrowed::Sections;
+use crate::{Error, LinkFlags, ParseContext, ParseOptions, ParseWarning};
+
+/// A shell link split into the bytes of each of its structures, none of
+/// which are decoded, for callers that parse the structures themselves.
+///
+/// Only the header and the sizes the other structures declare are read, so
+/// this succeeds on shell links whose other structures this crate cannot
+/// parse. Set [`ParseOptions::ignore_signature`] to also split data whose
+/// header does not carry the signature of a shell link.
+#[derive(Clone, Debug)]
+pub struct RawShellLink<'a> {
+    data: &'a [u8],
+    sections: Sections,
+    string_data: Range<usize>,
+    terminal_block: Option<Range<usize>>,
+    warnings: Vec<ParseWarning>,
+}
+
+impl<'a> RawShellLink<'a> {
+    /// Split the shell link at the start of `data`, returning it along with
+    /// the number of bytes it occupies.
+    pub fn parse(data: &'a [u8]) -> Result<(Self, usize), Error> {
+        Self::parse_with_options(data, &ParseOptions::default())
+    }
+
+    /// Split the shell link at the start of `data` with the given options,
+    /// returning it along with the number of bytes it occupies.
+    pub fn parse_with_options(
+        data: &'a [u8],
+        options: &ParseOptions,
+    ) -> Result<(Self, usize), Error> {
+        let ctx = ParseContext::new(options);
+        let (_, sections) = Sections::locate(data, &ctx)?;
+        let size = sections.size;
+        let start = [&sections.link_target_id_list, &sections.link_info]
+            .into_iter()
+            .flatten()
+            .map(|range| range.end)
+            .max()
+            .unwrap_or(0x4c);
+        let (string_data, terminal_block) = match options.header_only {
+            true => (start..start, None),
+            false => (
+                start..sections.extra_data.start,
+                Some(sections.extra_data.end..size),
+            ),
+        };
+        let link = Self {
+            data: &data[..size],
+            sections,
+            string_data,
+            terminal_block,
+            warnings: ctx.into_warnings(),
+        };
+        Ok((link, size))
+    }
+
+    /// The data of the whole shell link.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// The 0x4C bytes of the ShellLinkHeader.
+    pub fn header(&self) -> &'a [u8] {
+        &self.data[..0x4c]
+    }
+
+    /// The LinkFlags of the header, which decide the structures that follow
+    /// it.
+    pub fn link_flags(&self) -> LinkFlags {
+        LinkFlags::from_bits_retain(LE::read_u32(&self.data[0x14..]))
+    }
+
+    /// The LinkTargetIDList, starting with its IDListSize, if present.
+    pub fn link_target_id_list(&self) -> Option<&'a [u8]> {
+        self.slice(&self.sections.link_target_id_list)
+    }
+
+    /// The LinkInfo, starting with its LinkInfoSize, if present.
+    pub fn link_info(&self) -> Option<&'a [u8]> {
+        self.slice(&self.sections.link_info)
+    }
+
+    /// The StringData structures, one after another, each starting with its
+    /// CountCharacters. This is empty when there are none.
+    pub fn string_data(&self) -> &'a [u8] {
+        &self.data[self.string_data.clone()]
+    }
+
+    /// The characters of each StringData structure, without CountCharacters,
+    /// in the order they are stored: NAME_STRING, RELATIVE_PATH,
+    /// WORKING_DIR, COMMAND_LINE_ARGUMENTS and ICON_LOCATION. Each is UTF-16
+    /// when the IsUnicode flag is set, otherwise in the system default code
+    /// page.
+    pub fn strings(&self) -> [Option<&'a [u8]>; 5] {
+        self.sections
+            .strings
+            .each_ref()
+            .map(|range| self.slice(range))
+    }
+
+    /// The ExtraData blocks, each starting with its BlockSize and
+    /// BlockSignature, without the TerminalBlock.
+    pub fn extra_data(&self) -> impl Iterator<Item = &'a [u8]> + 'a {
+        let mut data = &self.data[self.sections.extra_data.clone()];
+        std::iter::from_fn(move || {
+            if data.is_empty() {
+                return None;
+            }
+            let (block, rest) = data.split_at(LE::read_u32(data) as usize);
+            data = rest;
+            Some(block)
+        })
+    }
+
+    /// The TerminalBlock that ends the ExtraData, unless only the header
+    /// was read.
+    pub fn terminal_block(&self) -> Option<&'a [u8]> {
+        self.slice(&self.terminal_block)
+    }
+
+    /// Get the problems found while reading the header that did not stop
+    /// the split.
+    pub fn warnings(&self) -> &[ParseWarning] {
+        &self.warnings
+    }
+
+    fn slice(&self, range: &Option<Range<usize>>) -> Option<&'a [u8]> {
+        range.clone().map(|range| &self.data[range])
+    }
+}
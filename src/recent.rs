@@ -0,0 +1,81 @@
+#[allow(unused)]
+use crate::logging::{debug, error, info, trace, warn};
+
+use std::collections::BTreeMap;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::{Error, ParseOptions, ShellLink};
+
+/// The folders holding a user's recent items, as components of their paths
+/// under the roaming application data folder (`%APPDATA%`): Recent Items,
+/// which Explorer adds a shell link to for each file opened, and the
+/// recent files of Microsoft Office.
+pub const RECENT_FOLDERS: &[&[&str]] = &[
+    &["Microsoft", "Windows", "Recent"],
+    &["Microsoft", "Office", "Recent"],
+];
+
+/// Parse the shell links in the current user's Recent Items and Office
+/// Recent folders, found under `%APPDATA%`, with the given options. See
+/// [`recent_items_in`].
+#[cfg(windows)]
+pub fn recent_items(
+    options: &ParseOptions,
+) -> Result<BTreeMap<PathBuf, Result<ShellLink, Error>>, Error> {
+    let app_data = std::env::var_os("APPDATA").ok_or_else(|| {
+        Error::IoError(std::io::Error::new(
+            ErrorKind::NotFound,
+            "the APPDATA environment variable is not set",
+        ))
+    })?;
+    Ok(recent_items_in(app_data, options))
+}
+
+/// Parse the shell links in the Recent Items and Office Recent folders under
+/// `app_data`, the roaming application data folder of a user, with the
+/// given options. This works on any platform, so it can be pointed at a
+/// profile copied from another machine.
+///
+/// Each file with a `.lnk` extension is returned, keyed by its path, along
+/// with the result of parsing it. Subfolders, such as those holding jump
+/// lists, are not looked in, and folders that do not exist are skipped.
+/// A folder that cannot be read is returned along with the error reading it.
+pub fn recent_items_in<P: AsRef<Path>>(
+    app_data: P,
+    options: &ParseOptions,
+) -> BTreeMap<PathBuf, Result<ShellLink, Error>> {
+    let mut items = BTreeMap::new();
+    for components in RECENT_FOLDERS {
+        let folder: PathBuf = components
+            .iter()
+            .fold(app_data.as_ref().to_path_buf(), |path, c| path.join(c));
+        trace!("Reading {:?}", folder);
+        let entries = match std::fs::read_dir(&folder) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => {
+                items.insert(folder, Err(e.into()));
+                continue;
+            }
+        };
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    items.insert(folder.clone(), Err(e.into()));
+                    break;
+                }
+            };
+            let path = entry.path();
+            let is_link = path
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("lnk"));
+            if is_link && entry.file_type().is_ok_and(|t| t.is_file()) {
+                let result = ShellLink::open_with_options(&path, options);
+                items.insert(path, result);
+            }
+        }
+    }
+    items
+}
@@ -0,0 +1,343 @@
+use byteorder::{ByteOrder, LE};
+use encoding_rs::Encoding;
+
+use crate::borrowed::Sections;
+use crate::linkinfo::LinkInfoFlags;
+use crate::{Error, ExtraData, ParseContext, ParseOptions, ShellLink};
+
+/// Which identifying details [`ShellLink::redact`] and [`redact`] remove.
+/// By default, all of them are.
+#[derive(Clone, Debug)]
+pub struct RedactOptions {
+    /// Blank the NetBIOS name of the machine in the TrackerDataBlock.
+    pub machine_id: bool,
+    /// Replace the droids in the TrackerDataBlock with the nil GUID. They
+    /// hold the MAC address of the machine that made them.
+    pub droids: bool,
+    /// Blank the serial number and label of the volume in the LinkInfo.
+    pub volume: bool,
+    /// Replace the names of user profile folders, such as `alice` in
+    /// `C:\Users\alice\Documents`, with `X`s wherever they appear. The
+    /// names are found in the paths the shell link holds; the short 8.3
+    /// form of a long name is not replaced.
+    pub user_names: bool,
+    /// Replace the digits of security identifiers (SIDs), such as those in
+    /// the PropertyStoreDataBlock, with zeros.
+    pub sids: bool,
+}
+
+impl Default for RedactOptions {
+    fn default() -> Self {
+        Self {
+            machine_id: true,
+            droids: true,
+            volume: true,
+            user_names: true,
+            sids: true,
+        }
+    }
+}
+
+/// The folders holding user profiles, whose subfolders are named after the
+/// users.
+const PROFILE_FOLDERS: &[&str] = &["\\users\\", "\\documents and settings\\"];
+
+/// Profile folders that are not named after a user.
+const SHARED_PROFILES: &[&str] = &["all users", "default", "default user", "public"];
+
+/// Removes identifying details from the strings and data of a shell link,
+/// keeping their lengths so that offsets stay valid.
+pub(crate) struct Redactor<'a> {
+    pub(crate) options: &'a RedactOptions,
+    names: Vec<String>,
+    encoding: &'static Encoding,
+}
+
+impl<'a> Redactor<'a> {
+    fn new(link: &ShellLink, options: &'a RedactOptions, encoding: &'static Encoding) -> Self {
+        let mut names = Vec::new();
+        if options.user_names {
+            for path in link.strings() {
+                for name in user_names(path) {
+                    if !names.contains(&name) {
+                        names.push(name);
+                    }
+                }
+            }
+        }
+        Self {
+            options,
+            names,
+            encoding,
+        }
+    }
+
+    /// Redact a string in place.
+    pub(crate) fn string(&self, s: &mut String) {
+        let mut chars: Vec<char> = s.chars().collect();
+        for name in &self.names {
+            let name: Vec<char> = name.chars().collect();
+            let mut i = 0;
+            while i + name.len() <= chars.len() {
+                if chars[i..i + name.len()]
+                    .iter()
+                    .zip(&name)
+                    .all(|(a, b)| a.to_lowercase().eq(b.to_lowercase()))
+                {
+                    chars[i..i + name.len()].fill('X');
+                    i += name.len();
+                } else {
+                    i += 1;
+                }
+            }
+        }
+        if self.options.sids {
+            let mut i = 0;
+            while let Some(start) = find_sid(&chars[i..]) {
+                let mut j = i + start + 4;
+                while j < chars.len() && (chars[j].is_ascii_digit() || chars[j] == '-') {
+                    if chars[j].is_ascii_digit() {
+                        chars[j] = '0';
+                    }
+                    j += 1;
+                }
+                i = j;
+            }
+        }
+        *s = chars.into_iter().collect();
+    }
+
+    /// Redact the strings in some raw data in place, whether they are stored
+    /// as ANSI or Unicode.
+    pub(crate) fn bytes(&self, data: &mut [u8]) {
+        for name in &self.names {
+            let (ansi, _, _) = self.encoding.encode(name);
+            mask(data, &ansi, 1);
+            let unicode: Vec<u8> = name.encode_utf16().flat_map(u16::to_le_bytes).collect();
+            mask(data, &unicode, 2);
+        }
+        if self.options.sids {
+            mask_sids(data, 1);
+            mask_sids(data, 2);
+        }
+    }
+}
+
+/// The names of the user profile folders in a path.
+fn user_names(path: &str) -> Vec<String> {
+    let lower = path.to_lowercase();
+    let mut names = Vec::new();
+    for folder in PROFILE_FOLDERS {
+        let mut rest = lower.as_str();
+        while let Some(start) = rest.find(folder) {
+            rest = &rest[start + folder.len()..];
+            let name = rest.split(['\\', '"', ';']).next().unwrap_or_default();
+            if !name.is_empty() && !SHARED_PROFILES.contains(&name) && !name.starts_with('%') {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+/// Where the first SID starts in some characters.
+fn find_sid(chars: &[char]) -> Option<usize> {
+    chars
+        .windows(5)
+        .position(|w| w[..4] == ['S', '-', '1', '-'] && w[4].is_ascii_digit())
+}
+
+/// Replace, ignoring ASCII case, each occurrence of `needle` in `data` with
+/// `X`s, for strings whose characters are `unit` bytes.
+fn mask(data: &mut [u8], needle: &[u8], unit: usize) {
+    if needle.is_empty() {
+        return;
+    }
+    let mut i = 0;
+    while i + needle.len() <= data.len() {
+        if data[i..i + needle.len()].eq_ignore_ascii_case(needle) {
+            for (j, b) in data[i..i + needle.len()].iter_mut().enumerate() {
+                *b = if j % unit == 0 { b'X' } else { 0 };
+            }
+            i += needle.len();
+        } else {
+            i += 1;
+        }
+    }
+}
+
+/// Replace the digits of each SID in `data` with zeros, for strings whose
+/// characters are `unit` bytes.
+fn mask_sids(data: &mut [u8], unit: usize) {
+    let char_at = |data: &[u8], i: usize| -> Option<u8> {
+        let c = data.get(i..i + unit)?;
+        c[1..].iter().all(|b| *b == 0).then_some(c[0])
+    };
+    let mut i = 0;
+    while i + 5 * unit <= data.len() {
+        let prefix = (0..4).all(|k| char_at(data, i + k * unit) == Some(b"S-1-"[k]))
+            && char_at(data, i + 4 * unit).is_some_and(|c| c.is_ascii_digit());
+        if !prefix {
+            i += 1;
+            continue;
+        }
+        i += 4 * unit;
+        while let Some(c) = char_at(data, i).filter(|c| c.is_ascii_digit() || *c == b'-') {
+            if c.is_ascii_digit() {
+                data[i] = b'0';
+            }
+            i += unit;
+        }
+    }
+}
+
+impl ShellLink {
+    /// Remove identifying details from the shell link, so that it can be
+    /// shared: those chosen in `options`, wherever this crate parses them.
+    /// To remove them from a file, including the structures this crate does
+    /// not parse, use [`redact`].
+    pub fn redact(&mut self, options: &RedactOptions) {
+        let redactor = Redactor::new(self, options, ParseOptions::default().default_encoding);
+        for s in [
+            &mut self.name_string,
+            &mut self.relative_path,
+            &mut self.working_dir,
+            &mut self.command_line_arguments,
+            &mut self.icon_location,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            redactor.string(s);
+        }
+        if let Some(id_list) = &mut self.linktarget_id_list {
+            id_list.redact(&redactor);
+        }
+        if let Some(link_info) = &mut self.link_info {
+            link_info.redact(&redactor);
+        }
+        for block in &mut self._extra_data {
+            block.redact(&redactor);
+        }
+    }
+
+    /// The strings of the shell link that may hold paths.
+    fn strings(&self) -> Vec<&str> {
+        let mut strings: Vec<&str> = [
+            &self.name_string,
+            &self.relative_path,
+            &self.working_dir,
+            &self.command_line_arguments,
+            &self.icon_location,
+        ]
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+        .collect();
+        if let Some(link_info) = &self.link_info {
+            strings.extend(link_info.local_base_path().as_deref());
+            strings.extend(link_info.local_base_path_unicode().as_deref());
+            strings.push(link_info.common_path_suffix());
+            strings.extend(link_info.common_path_suffix_unicode().as_deref());
+            if let Some(network) = link_info.common_network_relative_link() {
+                strings.push(network.net_name_ansi());
+                strings.extend(network.net_name_unicode().as_deref());
+            }
+        }
+        for block in &self._extra_data {
+            match block {
+                ExtraData::EnvironmentProps(props) => {
+                    strings.push(props.target_ansi());
+                    strings.extend(props.target_unicode().as_deref());
+                }
+                ExtraData::IconEnvironmentProps(props) => {
+                    strings.push(props.target_ansi());
+                    strings.extend(props.target_unicode().as_deref());
+                }
+                _ => {}
+            }
+        }
+        strings
+    }
+}
+
+/// Remove identifying details from the shell link at the start of `data`,
+/// returning the data with them overwritten, ready to be saved. Every
+/// string keeps its length, so the structure of the shell link and
+/// anything after it is unchanged. User names and SIDs are replaced
+/// wherever they appear, including in structures this crate does not
+/// parse.
+pub fn redact(data: &[u8], options: &RedactOptions) -> Result<Vec<u8>, Error> {
+    redact_with_options(data, options, &ParseOptions::default())
+}
+
+/// Remove identifying details from the shell link at the start of `data`,
+/// parsing it with the given options. See [`redact`].
+pub fn redact_with_options(
+    data: &[u8],
+    options: &RedactOptions,
+    parse_options: &ParseOptions,
+) -> Result<Vec<u8>, Error> {
+    let (link, _) = ShellLink::from_slice_with_options(data, parse_options)?;
+    let redactor = Redactor::new(&link, options, parse_options.default_encoding);
+    let ctx = ParseContext::new(parse_options);
+    let (_, sections) = Sections::locate(data, &ctx)?;
+    let mut data = data.to_vec();
+
+    if let (true, Some(range)) = (options.volume, sections.link_info.clone()) {
+        redact_volume(&mut data[range]);
+    }
+
+    let mut offset = sections.extra_data.start;
+    while offset < sections.extra_data.end {
+        let size = LE::read_u32(&data[offset..]) as usize;
+        let block = &mut data[offset..offset + size];
+        if LE::read_u32(&block[4..]) == 0xa0000003 && block.len() >= 0x60 {
+            // The MachineID is 16 bytes, unless a longer Length that fits
+            // makes room for more.
+            let length = LE::read_u32(&block[8..]) as usize;
+            let machine_id_end = if length > 0x58 && length + 8 <= block.len() {
+                length - 0x38
+            } else {
+                0x20
+            };
+            if options.machine_id {
+                block[0x10..machine_id_end].fill(0);
+            }
+            if options.droids {
+                block[machine_id_end..machine_id_end + 0x40].fill(0);
+            }
+        }
+        offset += size;
+    }
+
+    redactor.bytes(&mut data);
+    Ok(data)
+}
+
+/// Blank the serial number and label of the VolumeID in a LinkInfo.
+fn redact_volume(link_info: &mut [u8]) {
+    if link_info.len() < 0x1c {
+        return;
+    }
+    let flags = LinkInfoFlags::from_bits_retain(LE::read_u32(&link_info[8..]));
+    if !flags.contains(LinkInfoFlags::VOLUME_ID_AND_LOCAL_BASE_PATH) {
+        return;
+    }
+    let start = LE::read_u32(&link_info[0x0c..]) as usize;
+    let Some(volume) = link_info.get_mut(start..) else {
+        return;
+    };
+    if volume.len() < 0x10 {
+        return;
+    }
+    let size = (LE::read_u32(volume) as usize).min(volume.len());
+    volume[8..0x0c].fill(0);
+    let mut label = LE::read_u32(&volume[0x0c..]) as usize;
+    if label == 0x14 && size >= 0x14 {
+        label = LE::read_u32(&volume[0x10..]) as usize;
+    }
+    if (0x10..size).contains(&label) {
+        volume[label..size].fill(0);
+    }
+}
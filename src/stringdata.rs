@@ -1,31 +1,48 @@
-use crate::LinkFlags;
+use crate::logging::debug;
+use crate::{strings, Error, LinkFlags, ParseContext};
 use byteorder::{ByteOrder, LE};
-use log::debug;
 
-pub fn parse_string(data: &[u8], flags: LinkFlags) -> (usize, String) {
+/// Parse a StringData structure, returning the number of bytes it occupies
+/// and the string. A string that runs past the end of the data is reported
+/// and cut short.
+pub fn parse_string(
+    data: &[u8],
+    flags: LinkFlags,
+    ctx: &ParseContext,
+) -> Result<(usize, String), Error> {
+    if data.len() < 2 {
+        ctx.nonconformance(
+            0,
+            "The StringData is missing its CountCharacters.".to_string(),
+        )?;
+        return Ok((data.len(), String::new()));
+    }
+    let char_count = LE::read_u16(data) as usize;
+    let char_size = if flags.contains(LinkFlags::IS_UNICODE) {
+        2
+    } else {
+        1
+    };
+    let mut total_bytes = 2 + char_count * char_size;
+    if total_bytes > data.len() {
+        ctx.nonconformance(
+            0,
+            format!(
+                "The StringData declares {} characters, but only {} bytes remain.",
+                char_count,
+                data.len() - 2
+            ),
+        )?;
+        total_bytes = data.len();
+    }
+    let char_data = &data[2..total_bytes];
     let result = if !flags.contains(LinkFlags::IS_UNICODE) {
-        let char_bytes = LE::read_u16(data) as usize;
-        let total_bytes = 2 + char_bytes;
-        let char_data = &data[2..total_bytes];
-        // FIXME: Should be decoding with the system default encoding.
-        //        This is effectively Latin-1, as that is the first 256 code points
-        //        in Unicode.
-        let mut s = String::new();
-        s.reserve(char_bytes);
-        for char in char_data {
-            s.push(*char as char);
-        }
-        (total_bytes, s)
+        (total_bytes, ctx.decode(char_data))
     } else {
-        let char_count = LE::read_u16(data) as usize;
-        let total_bytes = 2 + char_count * 2;
-        let char_data = &data[2..total_bytes];
-        let mut u16_chars = vec![0u16; char_count];
-        LE::read_u16_into(char_data, &mut u16_chars);
-        (total_bytes, String::from_utf16_lossy(&u16_chars))
+        (total_bytes, strings::decode_utf16le(char_data))
     };
     debug!("Parsed string: {:?}", result);
-    result
+    Ok(result)
 }
 
 #[cfg(feature = "experimental_save")]
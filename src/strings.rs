@@ -1,5 +1,172 @@
-pub fn trim_nul_terminated_string<S: Into<String>>(s: S) -> String {
-    let s = s.into();
-    let end_index = s.find('\0').unwrap_or(0);
-    s[..end_index].to_string()
+#[allow(unused)]
+use crate::logging::{debug, error, info, trace, warn};
+
+use encoding_rs::Encoding;
+use std::cell::RefCell;
+
+use crate::{Error, ParseContext};
+
+/// The default maximum length, in bytes, of a NULL-terminated string read
+/// from a shell link. This comfortably exceeds the longest path Windows
+/// supports (32,767 UTF-16 code units).
+pub const DEFAULT_MAX_STRING_LEN: usize = 0x10000;
+
+/// Scratch capacity above which the buffer is given back after use, so that
+/// one unusually long string does not pin its memory for the thread's life.
+const SCRATCH_KEEP: usize = 0x1000;
+
+thread_local! {
+    /// The buffer strings are decoded into before being copied out.
+    static SCRATCH: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+/// The number of characters, including the NULL terminator, held by the
+/// fixed-size path buffers of the ExtraData blocks: 260 bytes in the
+/// default code page, and 260 UTF-16 code units.
+const MAX_PATH: usize = 260;
+
+/// Fit `string` into the pair of fixed-size path buffers of the field
+/// `field` of `structure`, returning the string for the buffer in the
+/// default code page and the one for the Unicode buffer.
+///
+/// A string that cannot be written in Windows-1252 is stored only as
+/// Unicode, with the other buffer left empty, as Windows does. A string too
+/// long for the buffers is an error, rather than being cut short.
+pub fn fixed_path(
+    structure: &'static str,
+    field: &'static str,
+    string: String,
+) -> Result<(String, Option<String>), Error> {
+    let length = string.encode_utf16().count();
+    if length >= MAX_PATH {
+        return Err(Error::FieldTooLong {
+            structure,
+            field,
+            limit: MAX_PATH - 1,
+            length,
+        });
+    }
+    let (_, _, unmappable) = encoding_rs::WINDOWS_1252.encode(&string);
+    let ansi = match unmappable {
+        true => String::new(),
+        false => string.clone(),
+    };
+    Ok((ansi, Some(string)))
+}
+
+/// Decode `data` with `encoding`, replacing anything malformed with U+FFFD.
+///
+/// Decoders reserve room for the worst case, around three times the length
+/// of the string for UTF-16, so the string is decoded into a reused scratch
+/// buffer and copied out at its exact size. That keeps decoding to one
+/// allocation and keeps parsed links small when many are held in memory.
+pub fn decode_exact(encoding: &'static Encoding, data: &[u8]) -> String {
+    SCRATCH.with(|scratch| {
+        let mut scratch = scratch.borrow_mut();
+        let mut decoder = encoding.new_decoder_without_bom_handling();
+        let Some(max_len) = decoder.max_utf8_buffer_length(data.len()) else {
+            return encoding.decode_without_bom_handling(data).0.into_owned();
+        };
+        scratch.clear();
+        scratch.reserve(max_len);
+        let _ = decoder.decode_to_string(data, &mut scratch, true);
+        let s = scratch.as_str().to_owned();
+        if scratch.capacity() > SCRATCH_KEEP {
+            *scratch = String::new();
+        }
+        s
+    })
+}
+
+/// Decode UTF-16LE `data` straight into a single new string, replacing
+/// anything that is not valid UTF-16 with U+FFFD.
+pub fn decode_utf16le(data: &[u8]) -> String {
+    decode_exact(encoding_rs::UTF_16LE, data)
+}
+
+/// Decode the NULL-terminated string in the default code page held in the
+/// fixed-size buffer `data`, ignoring everything from the terminator on.
+pub fn decode_fixed(data: &[u8], ctx: &ParseContext) -> String {
+    let end = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+    ctx.decode(&data[..end])
+}
+
+/// Decode the NULL-terminated UTF-16LE string held in the fixed-size buffer
+/// `data`, ignoring everything from the terminator on.
+pub fn decode_fixed_unicode(data: &[u8]) -> String {
+    let end = data
+        .chunks_exact(2)
+        .position(|unit| unit == [0, 0])
+        .map_or(data.len(), |i| i * 2);
+    decode_utf16le(&data[..end])
+}
+
+/// Read a NULL-terminated string in the default code page starting `offset`
+/// bytes into `data`, the data of the current structure, looking at no more
+/// than the configured maximum string length for the terminator.
+///
+/// If the data ends before a terminator is found, the remaining bytes are
+/// used as the string. If the maximum number of bytes are available and none
+/// of them is a terminator, [`Error::StringTooLong`] is returned.
+pub fn read_nul_terminated_string(
+    data: &[u8],
+    offset: usize,
+    ctx: &ParseContext,
+) -> Result<String, Error> {
+    ctx.need(data, offset, 1)?;
+    let data = &data[offset..];
+    let max_len = ctx.options.max_string_len;
+    let window = &data[..data.len().min(max_len)];
+    let end_index = match window.iter().position(|b| *b == 0) {
+        Some(idx) => idx,
+        None if data.len() >= max_len => return Err(Error::StringTooLong(max_len)),
+        None => {
+            ctx.nonconformance(
+                offset,
+                "NULL-terminated string runs to the end of the data.".to_string(),
+            )?;
+            window.len()
+        }
+    };
+    Ok(ctx.decode(&window[..end_index]))
+}
+
+/// Read a NULL-terminated UTF-16LE string starting `offset` bytes into
+/// `data`, the data of the current structure, in the same way as
+/// [`read_nul_terminated_string`].
+pub fn read_nul_terminated_unicode_string(
+    data: &[u8],
+    offset: usize,
+    ctx: &ParseContext,
+) -> Result<String, Error> {
+    ctx.need(data, offset, 2)?;
+    let data = &data[offset..];
+    let max_len = ctx.options.max_string_len;
+    let window = &data[..data.len().min(max_len) & !1];
+    let end_index = match window.chunks_exact(2).position(|unit| unit == [0, 0]) {
+        Some(idx) => idx * 2,
+        None if data.len() >= max_len => return Err(Error::StringTooLong(max_len)),
+        None => {
+            ctx.nonconformance(
+                offset,
+                "NULL-terminated string runs to the end of the data.".to_string(),
+            )?;
+            window.len()
+        }
+    };
+    Ok(decode_utf16le(&window[..end_index]))
+}
+
+/// The size, in bytes and including the terminator, of the NULL-terminated
+/// string starting `offset` bytes into `data`, or `None` if `data` ends
+/// first.
+pub fn nul_terminated_size(data: &[u8], offset: usize, unicode: bool) -> Option<usize> {
+    let data = data.get(offset..)?;
+    if unicode {
+        data.chunks_exact(2)
+            .position(|c| c == [0, 0])
+            .map(|i| i * 2 + 2)
+    } else {
+        data.iter().position(|b| *b == 0).map(|i| i + 1)
+    }
 }
@@ -0,0 +1,578 @@
+//! Byte-level shell links built from a description, for tests that need a
+//! particular structure rather than a binary file that happens to hold it.
+//!
+//! A [`LinkFixture`] describes a shell link; its fields default to a link
+//! holding only a header, and [`LinkFixture::to_bytes`] writes it out with
+//! the flags, sizes and offsets filled in. ExtraData blocks and the items of
+//! an IDList are written by the functions in this module, and can be put
+//! in a fixture as they are or altered first to make malformed links.
+//!
+//! Strings in the default code page are written in Windows-1252. A string
+//! too long for the fixed-size field it is written to, with its NULL
+//! terminator, panics rather than being cut short.
+
+use byteorder::{ByteOrder, LE};
+
+use crate::appx::APP_USER_MODEL_ID;
+use crate::guids::{CLSID_APPS_FOLDER, CLSID_INTERNET, CLSID_SHELL_LINK, FMTID_APP_USER_MODEL};
+use crate::linkinfo::DriveType;
+use crate::url::{URI_ITEM, URI_UNICODE};
+use crate::{FileAttributeFlags, FileTime, Guid, LinkFlags};
+
+/// The network provider type of a Windows file share, `WNNC_NET_LANMAN`.
+const WNNC_NET_LANMAN: u32 = 0x0002_0000;
+
+/// A description of a shell link to write as bytes. The flags saying which
+/// structures are present are set from the fields that are.
+#[derive(Clone, Debug)]
+pub struct LinkFixture {
+    /// Flags to set in addition to those for the structures present.
+    pub link_flags: LinkFlags,
+    /// The attributes of the target.
+    pub file_attributes: FileAttributeFlags,
+    /// The time the target was created.
+    pub creation_time: FileTime,
+    /// The time the target was last accessed.
+    pub access_time: FileTime,
+    /// The time the target was last written.
+    pub write_time: FileTime,
+    /// The size of the target.
+    pub file_size: u32,
+    /// The index of the icon in the file holding it.
+    pub icon_index: i32,
+    /// The window state to launch the target in, which should be 1, 3 or 7.
+    pub show_command: u32,
+    /// The data of each ItemID of a LinkTargetIDList, if it has one.
+    pub id_list: Option<Vec<Vec<u8>>>,
+    /// The LinkInfo, if the link has one.
+    pub link_info: Option<LinkInfoFixture>,
+    /// The NameString.
+    pub name: Option<String>,
+    /// The RelativePath.
+    pub relative_path: Option<String>,
+    /// The WorkingDir.
+    pub working_dir: Option<String>,
+    /// The CommandLineArguments.
+    pub arguments: Option<String>,
+    /// The IconLocation.
+    pub icon_location: Option<String>,
+    /// Write the StringData in UTF-16 and set `IS_UNICODE`, rather than in
+    /// the default code page.
+    pub unicode: bool,
+    /// The ExtraData blocks, each including its BlockSize and
+    /// BlockSignature.
+    pub extra_data: Vec<Vec<u8>>,
+    /// Bytes written after the TerminalBlock.
+    pub trailing_data: Vec<u8>,
+}
+
+impl Default for LinkFixture {
+    fn default() -> Self {
+        Self {
+            link_flags: LinkFlags::empty(),
+            file_attributes: FileAttributeFlags::empty(),
+            creation_time: FileTime::from_raw(0),
+            access_time: FileTime::from_raw(0),
+            write_time: FileTime::from_raw(0),
+            file_size: 0,
+            icon_index: 0,
+            show_command: 1,
+            id_list: None,
+            link_info: None,
+            name: None,
+            relative_path: None,
+            working_dir: None,
+            arguments: None,
+            icon_location: None,
+            unicode: true,
+            extra_data: Vec::new(),
+            trailing_data: Vec::new(),
+        }
+    }
+}
+
+/// A LinkInfo to write in a [`LinkFixture`].
+#[derive(Clone, Debug)]
+pub enum LinkInfoFixture {
+    /// A target on a volume of the machine: a LinkInfo with a VolumeID and
+    /// LocalBasePath.
+    Local {
+        /// The type of the drive holding the volume.
+        drive_type: DriveType,
+        /// The serial number of the volume.
+        serial_number: u32,
+        /// The label of the volume.
+        label: String,
+        /// The path of the target, such as `C:\test\a.txt`.
+        local_base_path: String,
+        /// The CommonPathSuffix, which is usually empty for a local target.
+        common_path_suffix: String,
+    },
+    /// A target on a network share: a LinkInfo with a
+    /// CommonNetworkRelativeLink.
+    Network {
+        /// The share, such as `\\server\share`.
+        net_name: String,
+        /// The drive letter the share is mapped to, such as `Z:`, if any.
+        device_name: Option<String>,
+        /// The path of the target in the share.
+        common_path_suffix: String,
+    },
+}
+
+impl LinkFixture {
+    /// A shell link with a LinkInfo for `path` on a fixed drive, and a
+    /// relative path and working directory to match.
+    pub fn local(path: &str) -> Self {
+        let (working_dir, file_name) = path.rsplit_once('\\').unwrap_or(("", path));
+        Self {
+            link_info: Some(LinkInfoFixture::Local {
+                drive_type: DriveType::DriveFixed,
+                serial_number: 0x1234_5678,
+                label: String::new(),
+                local_base_path: path.to_string(),
+                common_path_suffix: String::new(),
+            }),
+            relative_path: Some(format!(".\\{}", file_name)),
+            working_dir: Some(working_dir.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// A shell link with a LinkInfo for `suffix` on the network share
+    /// `net_name`, such as `\\server\share`.
+    pub fn network(net_name: &str, suffix: &str) -> Self {
+        Self {
+            link_info: Some(LinkInfoFixture::Network {
+                net_name: net_name.to_string(),
+                device_name: None,
+                common_path_suffix: suffix.to_string(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// A shell link to the packaged (UWP or AppX) app `app_user_model_id`,
+    /// such as `Microsoft.WindowsCalculator_8wekyb3d8bbwe!App`, as the
+    /// Start menu makes: an IDList of the app in the Applications folder,
+    /// and a PropertyStoreDataBlock holding the System.AppUserModel.ID.
+    pub fn appx(app_user_model_id: &str) -> Self {
+        Self {
+            id_list: Some(vec![
+                root_item(CLSID_APPS_FOLDER),
+                app_item(app_user_model_id),
+            ]),
+            extra_data: vec![property_store_block(&string_property_store(
+                FMTID_APP_USER_MODEL,
+                APP_USER_MODEL_ID,
+                app_user_model_id,
+            ))],
+            ..Default::default()
+        }
+    }
+
+    /// A shell link to the web page `url`, as Windows makes: an IDList of
+    /// a URI item in the Internet folder, and no LinkInfo. This is for
+    /// testing [`ShellLink::target_url`](crate::ShellLink::target_url), not
+    /// for making shortcuts to deploy.
+    pub fn url(url: &str) -> Self {
+        Self {
+            id_list: Some(vec![root_item(CLSID_INTERNET), uri_item(url)]),
+            ..Default::default()
+        }
+    }
+
+    /// Write the shell link.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let strings = [
+            (&self.name, LinkFlags::HAS_NAME),
+            (&self.relative_path, LinkFlags::HAS_RELATIVE_PATH),
+            (&self.working_dir, LinkFlags::HAS_WORKING_DIR),
+            (&self.arguments, LinkFlags::HAS_ARGUMENTS),
+            (&self.icon_location, LinkFlags::HAS_ICON_LOCATION),
+        ];
+        let mut flags = self.link_flags;
+        flags.set(LinkFlags::HAS_LINK_TARGET_ID_LIST, self.id_list.is_some());
+        flags.set(LinkFlags::HAS_LINK_INFO, self.link_info.is_some());
+        flags.set(LinkFlags::IS_UNICODE, self.unicode);
+        for (string, flag) in strings {
+            flags.set(flag, string.is_some());
+        }
+
+        let mut data = vec![0; 0x4c];
+        LE::write_u32(&mut data[0x00..], 0x4c);
+        data[0x04..0x14].copy_from_slice(&CLSID_SHELL_LINK.to_bytes_le());
+        LE::write_u32(&mut data[0x14..], flags.bits());
+        LE::write_u32(&mut data[0x18..], self.file_attributes.bits());
+        LE::write_u64(&mut data[0x1c..], self.creation_time.raw());
+        LE::write_u64(&mut data[0x24..], self.access_time.raw());
+        LE::write_u64(&mut data[0x2c..], self.write_time.raw());
+        LE::write_u32(&mut data[0x34..], self.file_size);
+        LE::write_i32(&mut data[0x38..], self.icon_index);
+        LE::write_u32(&mut data[0x3c..], self.show_command);
+
+        if let Some(items) = &self.id_list {
+            let id_list = id_list(items);
+            data.extend_from_slice(&(id_list.len() as u16).to_le_bytes());
+            data.extend_from_slice(&id_list);
+        }
+        if let Some(link_info) = &self.link_info {
+            data.extend_from_slice(&link_info.to_bytes());
+        }
+        for (string, _) in strings {
+            let Some(string) = string else {
+                continue;
+            };
+            if self.unicode {
+                let units: Vec<u16> = string.encode_utf16().collect();
+                data.extend_from_slice(&(units.len() as u16).to_le_bytes());
+                data.extend(units.iter().flat_map(|unit| unit.to_le_bytes()));
+            } else {
+                let bytes = ansi(string);
+                data.extend_from_slice(&(bytes.len() as u16).to_le_bytes());
+                data.extend_from_slice(&bytes);
+            }
+        }
+        for block in &self.extra_data {
+            data.extend_from_slice(block);
+        }
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&self.trailing_data);
+        data
+    }
+}
+
+impl LinkInfoFixture {
+    /// Write the LinkInfo, with a header of 0x1c bytes and no Unicode
+    /// strings.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut header = [0u32; 7];
+        header[1] = 0x1c;
+        let mut body = Vec::new();
+        let offset = |body: &Vec<u8>| 0x1c + body.len() as u32;
+        let common_path_suffix = match self {
+            LinkInfoFixture::Local {
+                drive_type,
+                serial_number,
+                label,
+                local_base_path,
+                common_path_suffix,
+            } => {
+                header[2] = 1;
+                header[3] = offset(&body);
+                let label = nul_terminated(label);
+                body.extend_from_slice(&(0x10 + label.len() as u32).to_le_bytes());
+                body.extend_from_slice(&(drive_type.clone() as u32).to_le_bytes());
+                body.extend_from_slice(&serial_number.to_le_bytes());
+                body.extend_from_slice(&0x10u32.to_le_bytes());
+                body.extend_from_slice(&label);
+                header[4] = offset(&body);
+                body.extend_from_slice(&nul_terminated(local_base_path));
+                common_path_suffix
+            }
+            LinkInfoFixture::Network {
+                net_name,
+                device_name,
+                common_path_suffix,
+            } => {
+                header[2] = 2;
+                header[5] = offset(&body);
+                let net_name = nul_terminated(net_name);
+                let device_name = device_name.as_deref().map(nul_terminated);
+                let device_length = device_name.as_ref().map_or(0, Vec::len);
+                let size = 0x14 + net_name.len() + device_length;
+                let network_flags = match device_name {
+                    Some(_) => 3u32,
+                    None => 2,
+                };
+                let device_offset = match device_name {
+                    Some(_) => 0x14 + net_name.len() as u32,
+                    None => 0,
+                };
+                body.extend_from_slice(&(size as u32).to_le_bytes());
+                body.extend_from_slice(&network_flags.to_le_bytes());
+                body.extend_from_slice(&0x14u32.to_le_bytes());
+                body.extend_from_slice(&device_offset.to_le_bytes());
+                body.extend_from_slice(&WNNC_NET_LANMAN.to_le_bytes());
+                body.extend_from_slice(&net_name);
+                body.extend(device_name.into_iter().flatten());
+                common_path_suffix
+            }
+        };
+        header[6] = offset(&body);
+        body.extend_from_slice(&nul_terminated(common_path_suffix));
+        header[0] = offset(&body);
+
+        let mut data: Vec<u8> = header
+            .iter()
+            .flat_map(|field| field.to_le_bytes())
+            .collect();
+        data.extend_from_slice(&body);
+        data
+    }
+}
+
+thread_local! {
+    pub(crate) static CLOCK_READS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// The number of times [`FileTime::now`] has been called on this thread, to
+/// check that parsing does not read the clock, which is not available on
+/// every target (such as `wasm32-unknown-unknown`).
+pub fn clock_reads() -> usize {
+    CLOCK_READS.with(std::cell::Cell::get)
+}
+
+/// Write an ItemID for a root folder, such as
+/// [`CLSID_MY_COMPUTER`](crate::guids::CLSID_MY_COMPUTER), without
+/// its ItemIDSize.
+pub fn root_item(clsid: Guid) -> Vec<u8> {
+    let mut item = vec![0x1f, 0x50];
+    item.extend_from_slice(&clsid.to_bytes_le());
+    item
+}
+
+/// Write an ItemID for a drive, such as `C:\`, without its ItemIDSize.
+pub fn drive_item(path: &str) -> Vec<u8> {
+    let mut item = vec![0x2f];
+    let mut name = ansi(path);
+    name.resize(22, 0);
+    item.extend_from_slice(&name);
+    item
+}
+
+/// Write a URI item for `uri`, in UTF-16, without its ItemIDSize.
+pub fn uri_item(uri: &str) -> Vec<u8> {
+    let mut item = vec![URI_ITEM, URI_UNICODE, 0, 0];
+    item.extend(uri.encode_utf16().chain([0]).flat_map(u16::to_le_bytes));
+    item
+}
+
+/// Write an ItemID for the app `app_user_model_id` in the Applications
+/// folder, without its ItemIDSize. This is a simplified form of the item
+/// Windows writes, holding only a property storage with the
+/// System.AppUserModel.ID.
+pub fn app_item(app_user_model_id: &str) -> Vec<u8> {
+    let mut item = vec![0x00, 0x00];
+    item.extend_from_slice(&string_property_store(
+        FMTID_APP_USER_MODEL,
+        APP_USER_MODEL_ID,
+        app_user_model_id,
+    ));
+    item
+}
+
+/// Write a serialized property store holding the one string property `id`
+/// of the property set `format_id`.
+pub fn string_property_store(format_id: Guid, id: u32, value: &str) -> Vec<u8> {
+    let characters: Vec<u16> = value.encode_utf16().chain([0]).collect();
+    let mut typed_value = 0x001fu32.to_le_bytes().to_vec();
+    typed_value.extend_from_slice(&(characters.len() as u32).to_le_bytes());
+    typed_value.extend(characters.iter().flat_map(|c| c.to_le_bytes()));
+    typed_value.resize(typed_value.len().next_multiple_of(4), 0);
+
+    let mut value = ((9 + typed_value.len()) as u32).to_le_bytes().to_vec();
+    value.extend_from_slice(&id.to_le_bytes());
+    value.push(0);
+    value.extend_from_slice(&typed_value);
+
+    let mut storage = ((24 + value.len() + 4) as u32).to_le_bytes().to_vec();
+    storage.extend_from_slice(b"1SPS");
+    storage.extend_from_slice(&format_id.to_bytes_le());
+    storage.extend_from_slice(&value);
+    storage.extend_from_slice(&[0; 4]);
+    // The terminator of the list of storages.
+    storage.extend_from_slice(&[0; 4]);
+    storage
+}
+
+/// Write a file entry item for a file with the short (8.3) name
+/// `short_name`, in the default code page, and a version 3 extension block
+/// holding the long name `long_name`, without its ItemIDSize.
+pub fn file_entry_item(short_name: &str, long_name: &str) -> Vec<u8> {
+    let mut item = vec![0x32, 0];
+    item.extend_from_slice(&[0; 10]);
+    item.extend_from_slice(&nul_terminated(short_name));
+    if item.len() % 2 != 0 {
+        item.push(0);
+    }
+    let offset = item.len() as u16 + 2;
+    let mut extension = vec![0; 10];
+    extension.extend_from_slice(&[0; 2]);
+    extension.extend(
+        long_name
+            .encode_utf16()
+            .chain([0])
+            .flat_map(u16::to_le_bytes),
+    );
+    extension.extend_from_slice(&offset.to_le_bytes());
+    item.extend(extension_block(
+        crate::extensionblock::FILE_ENTRY_BLOCK,
+        3,
+        &extension,
+    ));
+    item
+}
+
+/// Write an extension block of a shell item, with its size, `version` and
+/// `signature` before `data`.
+pub fn extension_block(signature: u32, version: u16, data: &[u8]) -> Vec<u8> {
+    let mut block = (8 + data.len() as u16).to_le_bytes().to_vec();
+    block.extend_from_slice(&version.to_le_bytes());
+    block.extend_from_slice(&signature.to_le_bytes());
+    block.extend_from_slice(data);
+    block
+}
+
+/// Write an IDList of items, as given without their ItemIDSize, and the
+/// TerminalID.
+fn id_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let mut data = Vec::new();
+    for item in items {
+        data.extend_from_slice(&(item.len() as u16 + 2).to_le_bytes());
+        data.extend_from_slice(item);
+    }
+    data.extend_from_slice(&[0; 2]);
+    data
+}
+
+/// Write an ExtraData block with its BlockSize and BlockSignature.
+fn block(signature: u32, body: &[u8]) -> Vec<u8> {
+    let mut data = (8 + body.len() as u32).to_le_bytes().to_vec();
+    data.extend_from_slice(&signature.to_le_bytes());
+    data.extend_from_slice(body);
+    data
+}
+
+/// `string` in the default code page, padded with NULLs to `length` bytes.
+fn fixed_ansi(string: &str, length: usize) -> Vec<u8> {
+    fixed(string, ansi(string), length)
+}
+
+/// `string` in UTF-16, padded with NULLs to `length` bytes.
+fn fixed_unicode(string: &str, length: usize) -> Vec<u8> {
+    let data = string.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    fixed(string, data, length)
+}
+
+/// The encoded `string`, `data`, padded with NULLs to `length` bytes.
+fn fixed(string: &str, mut data: Vec<u8>, length: usize) -> Vec<u8> {
+    assert!(
+        data.len() < length,
+        "{:?} does not fit in a field of {} bytes with its terminator",
+        string,
+        length
+    );
+    data.resize(length, 0);
+    data
+}
+
+/// `string` in the default code page.
+fn ansi(string: &str) -> Vec<u8> {
+    encoding_rs::WINDOWS_1252.encode(string).0.into_owned()
+}
+
+/// `string` in the default code page, followed by a NULL.
+fn nul_terminated(string: &str) -> Vec<u8> {
+    let mut data = ansi(string);
+    data.push(0);
+    data
+}
+
+/// Write an EnvironmentVariableDataBlock for the target `target`, in both
+/// the default code page and UTF-16.
+pub fn environment_block(target: &str) -> Vec<u8> {
+    let mut body = fixed_ansi(target, 260);
+    body.extend_from_slice(&fixed_unicode(target, 520));
+    block(0xa0000001, &body)
+}
+
+/// Write a ConsoleDataBlock for an 80 by 25 window with a buffer of 300
+/// lines, in the Consolas font.
+pub fn console_block() -> Vec<u8> {
+    let mut body = vec![0; 0xc4];
+    LE::write_u16(&mut body[0x00..], 0x07);
+    LE::write_u16(&mut body[0x02..], 0xf5);
+    LE::write_u16(&mut body[0x04..], 80);
+    LE::write_u16(&mut body[0x06..], 300);
+    LE::write_u16(&mut body[0x08..], 80);
+    LE::write_u16(&mut body[0x0a..], 25);
+    LE::write_u32(&mut body[0x18..], 0x0010_0000);
+    LE::write_u32(&mut body[0x1c..], 0x36);
+    LE::write_u32(&mut body[0x20..], 400);
+    body[0x24..0x64].copy_from_slice(&fixed_unicode("Consolas", 64));
+    LE::write_u32(&mut body[0x64..], 25);
+    LE::write_u32(&mut body[0x78..], 50);
+    LE::write_u32(&mut body[0x7c..], 4);
+    block(0xa0000002, &body)
+}
+
+/// Write a TrackerDataBlock for the machine `machine_id`, with the same
+/// droids as the birth droids.
+pub fn tracker_block(machine_id: &str, volume: Guid, file: Guid) -> Vec<u8> {
+    let mut body = 0x58u32.to_le_bytes().to_vec();
+    body.extend_from_slice(&0u32.to_le_bytes());
+    body.extend_from_slice(&fixed_ansi(machine_id, 16));
+    for _ in 0..2 {
+        body.extend_from_slice(&volume.to_bytes_le());
+        body.extend_from_slice(&file.to_bytes_le());
+    }
+    block(0xa0000003, &body)
+}
+
+/// Write a ConsoleFEDataBlock for the code page `code_page`.
+pub fn console_fe_block(code_page: u32) -> Vec<u8> {
+    block(0xa0000004, &code_page.to_le_bytes())
+}
+
+/// Write a SpecialFolderDataBlock for the folder `id`, a CSIDL, whose item
+/// is `offset` bytes into the LinkTargetIDList.
+pub fn special_folder_block(id: u32, offset: u32) -> Vec<u8> {
+    let mut body = id.to_le_bytes().to_vec();
+    body.extend_from_slice(&offset.to_le_bytes());
+    block(0xa0000005, &body)
+}
+
+/// Write a DarwinDataBlock for the application identifier `data`, in both
+/// the default code page and UTF-16.
+pub fn darwin_block(data: &str) -> Vec<u8> {
+    let mut body = fixed_ansi(data, 260);
+    body.extend_from_slice(&fixed_unicode(data, 520));
+    block(0xa0000006, &body)
+}
+
+/// Write an IconEnvironmentDataBlock for the icon path `target`, in both the
+/// default code page and UTF-16.
+pub fn icon_environment_block(target: &str) -> Vec<u8> {
+    let mut body = fixed_ansi(target, 260);
+    body.extend_from_slice(&fixed_unicode(target, 520));
+    block(0xa0000007, &body)
+}
+
+/// Write a ShimDataBlock for the shim layer `layer_name`.
+pub fn shim_block(layer_name: &str) -> Vec<u8> {
+    let mut body = fixed_unicode(layer_name, (layer_name.encode_utf16().count() + 1) * 2);
+    // The block is at least 0x88 bytes long.
+    body.resize(body.len().max(0x80), 0);
+    block(0xa0000008, &body)
+}
+
+/// Write a PropertyStoreDataBlock holding `property_store`, a serialized
+/// property storage.
+pub fn property_store_block(property_store: &[u8]) -> Vec<u8> {
+    block(0xa0000009, property_store)
+}
+
+/// Write a VistaAndAboveIDListDataBlock holding items given without their
+/// ItemIDSize.
+pub fn vista_id_list_block(items: &[Vec<u8>]) -> Vec<u8> {
+    block(0xa000000a, &id_list(items))
+}
+
+/// Write a KnownFolderDataBlock for the folder `id`, whose item is `offset`
+/// bytes into the LinkTargetIDList.
+pub fn known_folder_block(id: Guid, offset: u32) -> Vec<u8> {
+    let mut body = id.to_bytes_le().to_vec();
+    body.extend_from_slice(&offset.to_le_bytes());
+    block(0xa000000b, &body)
+}
@@ -0,0 +1,314 @@
+use std::fmt;
+use std::fs::Metadata;
+
+use crate::extensionblock::FileEntryExtension;
+use crate::guids::FMTID_STORAGE;
+use crate::{ExtraData, FileTime, Guid, ShellLink};
+
+/// Something that holds timestamps, which can be laid out as events on a
+/// timeline alongside those of other artifacts.
+pub trait Timeline {
+    /// The events recorded, in order of time. Times that are not set or not
+    /// valid are left out.
+    fn events(&self) -> Vec<TimelineEvent>;
+}
+
+/// A timestamped event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct TimelineEvent {
+    /// When the event happened.
+    pub time: FileTime,
+    /// What happened.
+    pub kind: EventKind,
+    /// Where the time was recorded.
+    pub source: EventSource,
+}
+
+impl TimelineEvent {
+    fn new(time: FileTime, kind: EventKind, source: EventSource) -> Self {
+        Self { time, kind, source }
+    }
+}
+
+impl fmt::Display for TimelineEvent {
+    /// Write the event in the form `2008-09-12 20:27:17.101 UTC Target
+    /// modified (ShellLinkHeader)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} ({})", self.time, self.kind, self.source)
+    }
+}
+
+/// What happened at the time of a [`TimelineEvent`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum EventKind {
+    /// The shell link file was created.
+    LinkCreated,
+    /// The shell link file was last written.
+    LinkModified,
+    /// The shell link file was last read.
+    LinkAccessed,
+    /// The target was created.
+    TargetCreated,
+    /// The target was last written.
+    TargetModified,
+    /// The target was last read.
+    TargetAccessed,
+    /// The file or folder of a shell item in the LinkTargetIDList was
+    /// created.
+    ItemCreated,
+    /// The file or folder of a shell item was last written.
+    ItemModified,
+    /// The file or folder of a shell item was last read.
+    ItemAccessed,
+    /// The target's current object droid was made, which is usually when
+    /// the Link Tracking service first saw it.
+    DroidCreated,
+    /// The target's birth object droid was made, which is usually when the
+    /// Link Tracking service first saw it at its original location.
+    BirthDroidCreated,
+    /// The target was last opened through a jump list.
+    LastUsed,
+    /// The time held by a property in a PropertyStoreDataBlock, such as
+    /// `System.DateModified`, which the shell records for the target.
+    Property {
+        /// The FormatID of the property set holding the property.
+        format_id: Guid,
+        /// The ID of the property within the property set.
+        property_id: u32,
+    },
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            EventKind::LinkCreated => "Link created",
+            EventKind::LinkModified => "Link modified",
+            EventKind::LinkAccessed => "Link accessed",
+            EventKind::TargetCreated => "Target created",
+            EventKind::TargetModified => "Target modified",
+            EventKind::TargetAccessed => "Target accessed",
+            EventKind::ItemCreated => "Item created",
+            EventKind::ItemModified => "Item modified",
+            EventKind::ItemAccessed => "Item accessed",
+            EventKind::DroidCreated => "Droid created",
+            EventKind::BirthDroidCreated => "Birth droid created",
+            EventKind::LastUsed => "Last used",
+            EventKind::Property {
+                format_id,
+                property_id,
+            } => {
+                return match (*format_id == FMTID_STORAGE, property_id) {
+                    (true, 14) => f.write_str("Property System.DateModified"),
+                    (true, 15) => f.write_str("Property System.DateCreated"),
+                    (true, 16) => f.write_str("Property System.DateAccessed"),
+                    _ => write!(f, "Property {} {}", format_id, property_id),
+                };
+            }
+        })
+    }
+}
+
+/// Where the time of a [`TimelineEvent`] was recorded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum EventSource {
+    /// The file system holding the shell link.
+    FileSystem,
+    /// The times in the ShellLinkHeader.
+    ShellLinkHeader,
+    /// The times of the file entry item at index `item` of the
+    /// LinkTargetIDList. They are local times in MS-DOS format, to two
+    /// seconds, and are given as though they were UTC.
+    LinkTargetIdList {
+        /// The index of the item.
+        item: usize,
+    },
+    /// The droids in the TrackerDataBlock.
+    TrackerDataBlock,
+    /// The properties in the PropertyStoreDataBlock.
+    PropertyStoreDataBlock,
+    /// The `DestList` stream of an automatic jump list.
+    DestList,
+}
+
+impl fmt::Display for EventSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventSource::LinkTargetIdList { item } => {
+                write!(f, "LinkTargetIDList/ItemID[{}]", item)
+            }
+            _ => fmt::Debug::fmt(self, f),
+        }
+    }
+}
+
+/// Collect the events of the times given, leaving out those not set or not
+/// valid, in order of time.
+fn collect(
+    times: impl IntoIterator<Item = (Option<FileTime>, EventKind)>,
+    source: EventSource,
+) -> Vec<TimelineEvent> {
+    let mut events: Vec<TimelineEvent> = times
+        .into_iter()
+        .filter_map(|(time, kind)| time.map(|time| TimelineEvent::new(time, kind, source)))
+        .filter(|event| event.time.is_set())
+        .collect();
+    events.sort_by_key(|event| event.time);
+    events
+}
+
+impl ShellLink {
+    /// Every timestamp embedded in the shell link, labelled with what it
+    /// records and where it is stored: the target times from the header,
+    /// the times of the file entry items of the LinkTargetIDList, the times
+    /// the object droids of any TrackerDataBlock were made, and the
+    /// FILETIME properties of any PropertyStoreDataBlock. They are in the
+    /// order of the structures holding them, and include times that are not
+    /// set or not valid, except for items and droids, whose times are left
+    /// out when they hold none.
+    pub fn timestamps(&self) -> Vec<TimelineEvent> {
+        let header = self.header();
+        let mut timestamps = vec![
+            TimelineEvent::new(
+                header.creation_time(),
+                EventKind::TargetCreated,
+                EventSource::ShellLinkHeader,
+            ),
+            TimelineEvent::new(
+                header.write_time(),
+                EventKind::TargetModified,
+                EventSource::ShellLinkHeader,
+            ),
+            TimelineEvent::new(
+                header.access_time(),
+                EventKind::TargetAccessed,
+                EventSource::ShellLinkHeader,
+            ),
+        ];
+        let items = self
+            .link_target_id_list()
+            .iter()
+            .flat_map(|list| list.id_list());
+        for (index, item) in items.enumerate() {
+            let Some(entry) = item.file_entry() else {
+                continue;
+            };
+            let extension = entry.extension().as_ref();
+            for (time, kind) in [
+                (
+                    extension.map(FileEntryExtension::created),
+                    EventKind::ItemCreated,
+                ),
+                (Some(entry.modified()), EventKind::ItemModified),
+                (
+                    extension.map(FileEntryExtension::accessed),
+                    EventKind::ItemAccessed,
+                ),
+            ] {
+                if let Some(time) = time.and_then(|time| time.to_filetime()) {
+                    timestamps.push(TimelineEvent::new(
+                        time,
+                        kind,
+                        EventSource::LinkTargetIdList { item: index },
+                    ));
+                }
+            }
+        }
+        for block in self.extra_data() {
+            match block {
+                ExtraData::TrackerProps(tracker) => {
+                    for (droid, kind) in [
+                        (tracker.droid(), EventKind::DroidCreated),
+                        (tracker.droid_birth(), EventKind::BirthDroidCreated),
+                    ] {
+                        if let Some(time) = droid[1].timestamp() {
+                            timestamps.push(TimelineEvent::new(
+                                time,
+                                kind,
+                                EventSource::TrackerDataBlock,
+                            ));
+                        }
+                    }
+                }
+                ExtraData::PropertyStoreProps(props) => {
+                    timestamps.extend(props.filetimes().into_iter().map(
+                        |(format_id, property_id, time)| {
+                            TimelineEvent::new(
+                                time,
+                                EventKind::Property {
+                                    format_id,
+                                    property_id,
+                                },
+                                EventSource::PropertyStoreDataBlock,
+                            )
+                        },
+                    ));
+                }
+                _ => {}
+            }
+        }
+        timestamps
+    }
+}
+
+impl Timeline for ShellLink {
+    /// The timestamps of the shell link that are set, as returned by
+    /// [`ShellLink::timestamps`].
+    fn events(&self) -> Vec<TimelineEvent> {
+        let mut events: Vec<TimelineEvent> = self
+            .timestamps()
+            .into_iter()
+            .filter(|event| event.time.is_set())
+            .collect();
+        events.sort_by_key(|event| event.time);
+        events
+    }
+}
+
+impl Timeline for Metadata {
+    /// The times the file system records for a shell link file, as far as
+    /// the platform provides them.
+    fn events(&self) -> Vec<TimelineEvent> {
+        collect(
+            [
+                (
+                    self.created().ok().map(FileTime::from),
+                    EventKind::LinkCreated,
+                ),
+                (
+                    self.modified().ok().map(FileTime::from),
+                    EventKind::LinkModified,
+                ),
+                (
+                    self.accessed().ok().map(FileTime::from),
+                    EventKind::LinkAccessed,
+                ),
+            ],
+            EventSource::FileSystem,
+        )
+    }
+}
+
+#[cfg(feature = "cfb")]
+impl Timeline for crate::jumplist::Destination {
+    /// When the entry was last used, and the times its object droids were
+    /// made.
+    fn events(&self) -> Vec<TimelineEvent> {
+        collect(
+            [
+                (Some(self.modification_time()), EventKind::LastUsed),
+                (self.droid()[1].timestamp(), EventKind::DroidCreated),
+                (
+                    self.droid_birth()[1].timestamp(),
+                    EventKind::BirthDroidCreated,
+                ),
+            ],
+            EventSource::DestList,
+        )
+    }
+}
